@@ -2,9 +2,17 @@ use inflector::Inflector as _;
 use proc_macro::TokenStream;
 use syn::Result;
 use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::Token;
 
 #[derive(Debug)]
 struct ConfigurationInput {
+    /// The guards to be generated, one per file stem.
+    guards: Vec<GuardInput>,
+}
+
+#[derive(Debug)]
+struct GuardInput {
     /// The name of the structure to be generated.
     type_name: proc_macro2::Ident,
 
@@ -12,7 +20,7 @@ struct ConfigurationInput {
     file_stem: String,
 }
 
-impl Parse for ConfigurationInput {
+impl Parse for GuardInput {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
         let head = input.fork();
 
@@ -21,11 +29,32 @@ impl Parse for ConfigurationInput {
             _ => return Err(head.error("expected string literal"))
         };
 
-        let type_name = (stem.clone() + "Configuration").to_pascal_case();
+        // The type name is either given with `as`, or derived from the stem
+        let type_name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        }
+        else {
+            format_ident!("{}", (stem.clone() + "Configuration").to_pascal_case())
+        };
 
         Ok(Self {
             file_stem: stem,
-            type_name: format_ident!("{}", type_name)
+            type_name
+        })
+    }
+}
+
+impl Parse for ConfigurationInput {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let guards = Punctuated::<GuardInput, Token![,]>::parse_terminated(input)?;
+
+        if guards.is_empty() {
+            return Err(input.error("expected string literal"));
+        }
+
+        Ok(Self {
+            guards: guards.into_iter().collect()
         })
     }
 }
@@ -33,8 +62,16 @@ impl Parse for ConfigurationInput {
 #[allow(non_snake_case)]
 pub fn configuration_function(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as ConfigurationInput);
+    let guards = input.guards.iter().map(guard);
+
+    (quote! {
+        #(#guards)*
+    }).into()
+}
 
+fn guard(input: &GuardInput) -> proc_macro2::TokenStream {
     // Store everything we're going to need to generate code.
+    // A `String`, so that it is interpolated as a string literal
     let configuration_stem = &input.file_stem;
     let configuration_type = &input.type_name;
 
@@ -44,6 +81,7 @@ pub fn configuration_function(input: TokenStream) -> TokenStream {
     let factory = quote!(::rocket_config::Factory);
     let index = quote!(::rocket_config::Index);
     let outcome = quote!(::rocket::outcome::Outcome);
+    let redacted_keys = quote!(::rocket_config::REDACTED_KEYS);
     let request = quote!(::rocket::request);
     let result = quote!(::rocket_config::Result);
     let state = quote!(::rocket::State);
@@ -52,17 +90,165 @@ pub fn configuration_function(input: TokenStream) -> TokenStream {
 
     let generated_type = quote! {
         /// The request guard type.
-        #[derive(Clone, Debug)]
+        ///
+        /// The request fails with `InternalServerError` when the configuration
+        /// is missing. Use `Option` of this type as the guard to get `None`
+        /// instead.
+        ///
+        /// Its `Debug` output redacts the values of the keys listed in
+        /// `rocket_config::REDACTED_KEYS`.
+        #[derive(Clone)]
         pub struct #configuration_type(#configuration);
     };
 
+    let impl_debug = quote! {
+        impl ::std::fmt::Debug for #configuration_type {
+            fn fmt(&self, formatter: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result
+            {
+                let configuration = self.0.debug_redacted(#redacted_keys)
+                    .map_err(|_| ::std::fmt::Error)?;
+
+                formatter.debug_tuple(stringify!(#configuration_type))
+                    .field(&format_args!("{}", configuration))
+                    .finish()
+            }
+        }
+    };
+
     let impl_generated_type = quote! {
         impl #configuration_type {
+            #[allow(dead_code)]
+            pub fn path(&self) -> #result<::std::path::PathBuf>
+            {
+                self.0.path()
+            }
+
+            #[allow(dead_code)]
+            pub fn source_format(&self) -> #result<&'static str>
+            {
+                self.0.source_format()
+            }
+
             #[allow(dead_code)]
             pub fn get<I: #index>(&self, index: I) -> #result<Option<#value>>
             {
                 self.0.get(index)
             }
+
+            #[allow(dead_code)]
+            pub fn with<I, R, F>(&self, index: I, f: F) -> #result<R>
+            where I: #index, F: FnOnce(Option<&#value>) -> R
+            {
+                self.0.with(index, f)
+            }
+
+            #[allow(dead_code)]
+            pub fn contains<I: #index>(&self, index: I) -> #result<bool>
+            {
+                self.0.contains(index)
+            }
+
+            #[allow(dead_code)]
+            pub fn contains_path(&self, path: &str) -> #result<bool>
+            {
+                self.0.contains_path(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn pointer(&self, pointer: &str) -> #result<Option<#value>>
+            {
+                self.0.pointer(pointer)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_string(&self, path: &str) -> #result<Option<String>>
+            {
+                self.0.get_string(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_i64(&self, path: &str) -> #result<Option<i64>>
+            {
+                self.0.get_i64(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_u64(&self, path: &str) -> #result<Option<u64>>
+            {
+                self.0.get_u64(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_f64(&self, path: &str) -> #result<Option<f64>>
+            {
+                self.0.get_f64(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_bool(&self, path: &str) -> #result<Option<bool>>
+            {
+                self.0.get_bool(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_str_or<S: Into<String>>(&self, path: &str, default: S) -> String
+            {
+                self.0.get_str_or(path, default)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_i64_or(&self, path: &str, default: i64) -> i64
+            {
+                self.0.get_i64_or(path, default)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_u64_or(&self, path: &str, default: u64) -> u64
+            {
+                self.0.get_u64_or(path, default)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_f64_or(&self, path: &str, default: f64) -> f64
+            {
+                self.0.get_f64_or(path, default)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_bool_or(&self, path: &str, default: bool) -> bool
+            {
+                self.0.get_bool_or(path, default)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_bytes_base64<I: #index>(&self, index: I) -> #result<Option<Vec<u8>>>
+            {
+                self.0.get_bytes_base64(index)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_bytes_hex<I: #index>(&self, index: I) -> #result<Option<Vec<u8>>>
+            {
+                self.0.get_bytes_hex(index)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_file_path(&self, path: &str) -> #result<Option<::std::path::PathBuf>>
+            {
+                self.0.get_file_path(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_socket_addr(&self, path: &str) -> #result<Option<::std::net::SocketAddr>>
+            {
+                self.0.get_socket_addr(path)
+            }
+
+            #[allow(dead_code)]
+            pub fn get_ip_addr(&self, path: &str) -> #result<Option<::std::net::IpAddr>>
+            {
+                self.0.get_ip_addr(path)
+            }
         }
     };
 
@@ -89,19 +275,33 @@ pub fn configuration_function(input: TokenStream) -> TokenStream {
                             #status::InternalServerError,
                             Self::Error::new(
                                 #error::ErrorKind::Other,
-                                ("failed to get".to_owned() + &#configuration_stem).to_owned() + "configuration"
+                                format!("failed to get {} configuration", #configuration_stem)
                             )
                         ))
                     }
-                    #outcome::Forward(_)         => { unreachable!() },
+                    // The `State` guard never forwards, but a failure is
+                    // still safer than a panic in a request handler
+                    #outcome::Forward(_)         => {
+                        #outcome::Failure((
+                            #status::InternalServerError,
+                            Self::Error::new(
+                                #error::ErrorKind::Other,
+                                format!(
+                                    "failed to get {} configuration, the factory state guard forwarded",
+                                    #configuration_stem
+                                )
+                            )
+                        ))
+                    },
                 }
             }
         }
     };
 
-    (quote! {
+    quote! {
         #generated_type
+        #impl_debug
         #impl_generated_type
         #impl_from_request
-    }).into()
+    }
 }
\ No newline at end of file
@@ -12,10 +12,29 @@
 //!
 //! * **configuration**
 //!
+//! And the following procedural attribute:
+//!
+//! * **typed_configuration**
+//!
 //! The syntax for the `configuration` macro is:
 //!
 //! <pre>
-//! macro := configuration!(CONFIGURATION_FILE_STEM)
+//! macro := configuration!(GUARD (, GUARD)* ,?)
+//!
+//! GUARD := CONFIGURATION_FILE_STEM (as TYPE_NAME)?
+//! </pre>
+//!
+//! One request guard type is generated per file stem: `configuration!("diesel",
+//! "redis")` generates `DieselConfiguration` and `RedisConfiguration`. The
+//! name of the type can be chosen with `as`: `configuration!("my-db" as MyDb)`
+//! generates `MyDb`.
+//!
+//! The `typed_configuration` attribute is put on a type implementing
+//! `serde::Deserialize`, which then becomes a request guard deserialized from
+//! the configuration file of the given stem:
+//!
+//! <pre>
+//! attribute := #[typed_configuration(CONFIGURATION_FILE_STEM)]
 //! </pre>
 //!
 //! ## Usage
@@ -60,6 +79,7 @@
 extern crate proc_macro;
 
 mod configuration;
+mod typed_configuration;
 
 #[allow(unused_imports)]
 use proc_macro::TokenStream;
@@ -68,4 +88,10 @@ use proc_macro::TokenStream;
 #[proc_macro]
 pub fn configuration(input: TokenStream) -> TokenStream {
     configuration::configuration_function(input)
-}
\ No newline at end of file
+}
+
+/// The procedural macro for the `typed_configuration` attribute.
+#[proc_macro_attribute]
+pub fn typed_configuration(args: TokenStream, input: TokenStream) -> TokenStream {
+    typed_configuration::typed_configuration_attribute(args, input)
+}
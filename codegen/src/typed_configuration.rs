@@ -0,0 +1,101 @@
+use proc_macro::TokenStream;
+use syn::Result;
+use syn::parse::{Parse, ParseStream};
+
+#[derive(Debug)]
+struct TypedConfigurationArgs {
+    /// The file stem as passed in via `#[typed_configuration("configuration file stem")]`.
+    file_stem: String,
+}
+
+impl Parse for TypedConfigurationArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let head = input.fork();
+
+        let stem = match input.parse()? {
+            syn::Lit::Str(lit) => lit.value(),
+            _ => return Err(head.error("expected string literal"))
+        };
+
+        Ok(Self {
+            file_stem: stem
+        })
+    }
+}
+
+pub fn typed_configuration_attribute(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as TypedConfigurationArgs);
+    let item = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    if !item.generics.params.is_empty() {
+        return syn::Error::new_spanned(&item.generics, "generic types are not supported")
+            .to_compile_error()
+            .into();
+    }
+
+    // Store everything we're going to need to generate code.
+    // A `String`, so that it is interpolated as a string literal
+    let configuration_stem = &args.file_stem;
+    let configuration_type = &item.ident;
+
+    // A few useful paths.
+    let error = quote!(::rocket_config::error);
+    let factory = quote!(::rocket_config::Factory);
+    let outcome = quote!(::rocket::outcome::Outcome);
+    let request = quote!(::rocket::request);
+    let state = quote!(::rocket::State);
+    let status = quote!(::rocket::http::Status);
+
+    let impl_from_request = quote! {
+        impl<'a, 'r> #request::FromRequest<'a, 'r> for #configuration_type {
+            type Error = #error::Error;
+
+            fn from_request(request: &'a #request::Request<'r>) -> #request::Outcome<Self, Self::Error>
+            {
+                match request.guard::<#state<#factory>>() {
+                    #outcome::Success(factory)   => {
+                        match factory.get(#configuration_stem)
+                            .and_then(|configuration| configuration.deserialize_into::<Self>())
+                        {
+                            Ok(config)          => #outcome::Success(config),
+                            Err(err)            => {
+                                #outcome::Failure((
+                                    #status::InternalServerError,
+                                    err
+                                ))
+                            }
+                        }
+                    },
+                    #outcome::Failure(_failure)  => {
+                        #outcome::Failure((
+                            #status::InternalServerError,
+                            Self::Error::new(
+                                #error::ErrorKind::Other,
+                                format!("failed to get {} configuration", #configuration_stem)
+                            )
+                        ))
+                    }
+                    // The `State` guard never forwards, but a failure is
+                    // still safer than a panic in a request handler
+                    #outcome::Forward(_)         => {
+                        #outcome::Failure((
+                            #status::InternalServerError,
+                            Self::Error::new(
+                                #error::ErrorKind::Other,
+                                format!(
+                                    "failed to get {} configuration, the factory state guard forwarded",
+                                    #configuration_stem
+                                )
+                            )
+                        ))
+                    },
+                }
+            }
+        }
+    };
+
+    (quote! {
+        #item
+        #impl_from_request
+    }).into()
+}
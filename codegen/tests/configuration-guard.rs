@@ -0,0 +1,37 @@
+#![feature(decl_macro, proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+extern crate rocket_config;
+#[macro_use] extern crate rocket_config_codegen;
+
+use rocket::http::Status;
+use rocket::local::Client;
+
+configuration!("diesel");
+
+#[get("/error")]
+fn error(diesel: Result<DieselConfiguration, rocket_config::error::Error>) -> String {
+    match diesel {
+        Ok(_) => "configured".to_owned(),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[get("/driver")]
+fn driver(_diesel: DieselConfiguration) -> &'static str {
+    "configured"
+}
+
+// This checks the error of the guard when the factory is not managed
+#[test]
+fn test_unmanaged_factory() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![error, driver]);
+    let client = Client::new(rocket).expect("valid rocket instance");
+
+    let mut response = client.get("/error").dispatch();
+    assert_eq!(response.body_string(), Some("failed to get diesel configuration".to_owned()));
+
+    let response = client.get("/driver").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
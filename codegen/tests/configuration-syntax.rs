@@ -2,6 +2,8 @@ extern crate rocket_config;
 #[macro_use] extern crate rocket_config_codegen;
 
 configuration!("diesel");
+configuration!("redis", "smtp", "message_queue",);
+configuration!("my-db" as MyDb, "my-cache");
 
 // This just checks that the DieselConfiguration struct exists
 #[test]
@@ -11,4 +13,27 @@ fn test_valid() {
             std::path::Path::new("/tmp/diesel.json")
         )
     );
-}
\ No newline at end of file
+}
+
+// This checks that every struct of a multiple stems invocation exists
+#[test]
+fn test_multiple_stems() {
+    let configuration = rocket_config::Configuration::new(
+        std::path::Path::new("/tmp/redis.json")
+    );
+
+    let _redis = RedisConfiguration(configuration.clone());
+    let _smtp = SmtpConfiguration(configuration.clone());
+    let _message_queue = MessageQueueConfiguration(configuration);
+}
+
+// This checks that explicit and derived type names of hyphenated stems exist
+#[test]
+fn test_type_names() {
+    let configuration = rocket_config::Configuration::new(
+        std::path::Path::new("/tmp/my-db.json")
+    );
+
+    let _my_db = MyDb(configuration.clone());
+    let _my_cache = MyCacheConfiguration(configuration);
+}
@@ -0,0 +1,78 @@
+#![feature(decl_macro, proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+extern crate rocket_config;
+#[macro_use] extern crate rocket_config_codegen;
+extern crate serde;
+extern crate tempfile;
+
+use rocket::local::Client;
+use rocket_config::Factory as ConfigurationsFairing;
+use serde::Deserialize;
+
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+struct Dbal {
+    driver: String,
+    port: u16,
+}
+
+#[typed_configuration("diesel")]
+#[derive(Debug, Deserialize)]
+struct Diesel {
+    dbal: Dbal,
+}
+
+#[get("/driver")]
+fn driver(diesel: Diesel) -> String {
+    format!("{}:{}", diesel.dbal.driver, diesel.dbal.port)
+}
+
+#[get("/error")]
+fn error(diesel: Result<Diesel, rocket_config::error::Error>) -> String {
+    match diesel {
+        Ok(_) => "configured".to_owned(),
+        Err(err) => err.to_string(),
+    }
+}
+
+// This checks that the attributed struct is deserialized as a request guard
+#[test]
+fn test_typed_field() {
+    let temp_dir = tempfile::tempdir()
+        .expect("failed to create temp dir");
+    let directory = temp_dir.path().join("config");
+
+    fs::create_dir_all(directory.join("dev"))
+        .expect("failed to create configuration directories");
+    fs::write(
+        directory.join("diesel.json"),
+        r#"{"dbal": {"driver": "mysql", "port": 3306}}"#
+    ).expect("failed to write diesel.json");
+
+    {
+        let rocket = rocket::ignite()
+            .attach(ConfigurationsFairing::with_directory(&directory))
+            .mount("/", routes![driver]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/driver").dispatch();
+
+        assert_eq!(response.body_string(), Some("mysql:3306".to_owned()));
+    }
+
+    temp_dir.close().expect("failed to close temp dir");
+}
+
+// This checks the error of the guard when the factory is not managed
+#[test]
+fn test_unmanaged_factory() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![error]);
+    let client = Client::new(rocket).expect("valid rocket instance");
+
+    let mut response = client.get("/error").dispatch();
+
+    assert_eq!(response.body_string(), Some("failed to get diesel configuration".to_owned()));
+}
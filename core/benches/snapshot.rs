@@ -0,0 +1,89 @@
+#![feature(test)]
+
+extern crate test;
+
+use {
+    rocket_config::Configuration,
+    std::{fs::OpenOptions, io::Write},
+    test::Bencher
+};
+
+/// Creates a JSON configuration file of about 1 MB.
+fn large_configuration() -> tempfile::NamedTempFile
+{
+    let temp_file = tempfile::Builder::new()
+        .prefix("bench")
+        .suffix(".json")
+        .rand_bytes(8)
+        .tempfile()
+        .expect("failed to create a named temp file");
+
+    let servers = (0..9_000).map(|index| format!(
+        r#""server-{0}": {{"host": "10.0.{1}.{2}", "port": {3}, "tags": ["primary", "eu-west-{1}"], "weight": {4}.5}}"#,
+        index, index / 256, index % 256, 8000 + index, index % 10
+    )).collect::<Vec<_>>();
+
+    let mut dot_json = OpenOptions::new()
+        .write(true)
+        .open(temp_file.path())
+        .expect("failed to open benchXXXXXXXX.json");
+    write!(dot_json, "{{\"servers\": {{{}}}}}", servers.join(", "))
+        .expect("failed to write benchXXXXXXXX.json");
+
+    temp_file
+}
+
+#[bench]
+fn clone_whole_configuration(bencher: &mut Bencher)
+{
+    let temp_file = large_configuration();
+    let configuration = Configuration::new(temp_file.path());
+
+    configuration.load().expect("failed to load configuration");
+    bencher.iter(|| configuration.pointer("").expect("configuration got poisoned"));
+}
+
+#[bench]
+fn snapshot_whole_configuration(bencher: &mut Bencher)
+{
+    let temp_file = large_configuration();
+    let configuration = Configuration::new(temp_file.path());
+
+    configuration.load().expect("failed to load configuration");
+    bencher.iter(|| configuration.snapshot().expect("configuration got poisoned"));
+}
+
+#[bench]
+fn get_servers(bencher: &mut Bencher)
+{
+    let temp_file = large_configuration();
+    let configuration = Configuration::new(temp_file.path());
+
+    configuration.load().expect("failed to load configuration");
+    bencher.iter(|| configuration.get("servers").expect("configuration got poisoned").map(|servers| servers.len()));
+}
+
+#[bench]
+fn with_servers(bencher: &mut Bencher)
+{
+    let temp_file = large_configuration();
+    let configuration = Configuration::new(temp_file.path());
+
+    configuration.load().expect("failed to load configuration");
+    bencher.iter(|| configuration.with("servers", |servers| servers.map(|servers| servers.len()))
+        .expect("configuration got poisoned"));
+}
+
+#[bench]
+fn get_servers_and_change_one(bencher: &mut Bencher)
+{
+    let temp_file = large_configuration();
+    let configuration = Configuration::new(temp_file.path());
+
+    configuration.load().expect("failed to load configuration");
+    bencher.iter(|| {
+        let mut servers = configuration.get("servers").expect("configuration got poisoned").expect("expected servers");
+        servers["server-0"]["port"] = 443.into();
+        servers
+    });
+}
@@ -1,2 +1,16 @@
 pub const CONFIGURATION_DIRECTORY: &str = "./config";
-pub const DEV_CONFIGURATION_DIRECTORY: &str = "./config/dev";
\ No newline at end of file
+pub const DEV_CONFIGURATION_DIRECTORY: &str = "./config/dev";
+pub const DUMP_MAX_STRING_LENGTH: usize = 80;
+
+/// The extensions of the configuration files, from the one which wins to the
+/// one which loses when files of a directory share a configuration name.
+pub const FORMAT_PRECEDENCE: &[&str] = &["json", "yaml", "yml", "ini", "properties", "env"];
+
+pub const PROFILE_VARIABLE: &str = "ROCKET_CONFIG_PROFILE";
+pub const WATCH_DEBOUNCE_DELAY_MS: u64 = 500;
+
+/// The keys whose values are redacted when a generated request guard is
+/// formatted with `Debug`.
+pub const REDACTED_KEYS: &[&str] = &[
+    "password", "passwd", "secret", "token", "api_key", "apikey", "url", "dsn"
+];
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+use crate::error;
+use crate::result;
+use crate::value::{Map, Value};
+
+/// Parses dotenv content into an object of strings.
+///
+/// The content is read line by line:
+///
+///   * blank lines and lines starting with `#` are ignored,
+///   * `KEY=value` sets `KEY`, and may be prefixed by `export `,
+///   * unquoted values are trimmed and end at a ` #` comment,
+///   * single-quoted values are taken literally,
+///   * double-quoted values understand the `\n`, `\r`, `\t`, `\"` and `\\`
+///     escapes.
+///
+/// A key set twice keeps its last value.
+pub(crate) fn parse(content: &str) -> result::Result<Value>
+{
+    let mut map = Map::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let separator = line.find('=').ok_or_else(|| format_error(
+            index, "expected a key and a value separated by '='"
+        ))?;
+        let key = line[..separator].trim();
+
+        if key.is_empty() {
+            return Err(format_error(index, "empty key"));
+        }
+
+        let value = parse_value(line[separator + 1..].trim())
+            .map_err(|message| format_error(index, message))?;
+
+        map.insert(key.to_owned(), Value::String(value));
+    }
+
+    Ok(Value::Object(Arc::new(map)))
+}
+
+/// Parses the value of a line, quoted or not.
+fn parse_value(raw: &str) -> Result<String, &'static str>
+{
+    let (value, rest) = if let Some(quoted) = raw.strip_prefix('\'') {
+        let end = quoted.find('\'').ok_or("unterminated single-quoted value")?;
+
+        (quoted[..end].to_owned(), &quoted[end + 1..])
+    }
+    else if let Some(quoted) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut characters = quoted.char_indices();
+        let end = loop {
+            match characters.next() {
+                Some((index, '"')) => break index,
+                Some((_, '\\')) => match characters.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, character)) => value.push(character),
+                    None => return Err("unterminated double-quoted value"),
+                },
+                Some((_, character)) => value.push(character),
+                None => return Err("unterminated double-quoted value"),
+            }
+        };
+
+        (value, &quoted[end + 1..])
+    }
+    else {
+        let end = raw.find(" #").unwrap_or(raw.len());
+
+        return Ok(raw[..end].trim_end().to_owned());
+    };
+
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(value)
+    }
+    else {
+        Err("unexpected characters after a quoted value")
+    }
+}
+
+/// Builds the error reported for the malformed line at `index`.
+fn format_error(index: usize, message: &str) -> error::Error
+{
+    error::Error::new(
+        error::ErrorKind::Parse,
+        format!("{} at line {}", message, index + 1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::error;
+    use crate::value::Value;
+
+    #[test]
+    fn unquoted() {
+        let value = parse("# database settings
+DATABASE_URL=mysql://localhost:3306/app
+export APP_ENV = prod # overridden below
+export APP_ENV=dev
+EMPTY=
+HASH=color#fff
+").expect("failed to parse dotenv");
+
+        assert_eq!(value, Value::from(&json!({
+            "DATABASE_URL": "mysql://localhost:3306/app",
+            "APP_ENV": "dev",
+            "EMPTY": "",
+            "HASH": "color#fff",
+        })));
+    }
+
+    #[test]
+    fn quoted() {
+        let value = parse(r#"
+SINGLE='%env(HOME)% \n # not a comment'
+DOUBLE="line\nbreak \"quoted\" \\ # not a comment" # comment
+SPACES="  padded  "
+EQUALS='a=b'
+"#).expect("failed to parse dotenv");
+
+        assert_eq!(value, Value::from(&json!({
+            "SINGLE": "%env(HOME)% \\n # not a comment",
+            "DOUBLE": "line\nbreak \"quoted\" \\ # not a comment",
+            "SPACES": "  padded  ",
+            "EQUALS": "a=b",
+        })));
+    }
+
+    #[test]
+    fn malformed() {
+        for (content, message) in &[
+            ("APP_ENV=dev\nDATABASE_URL", "expected a key and a value separated by '=' at line 2"),
+            ("=dev", "empty key at line 1"),
+            ("APP_ENV='dev", "unterminated single-quoted value at line 1"),
+            ("APP_ENV=\"dev\\\"", "unterminated double-quoted value at line 1"),
+            ("APP_ENV=\"dev\" prod", "unexpected characters after a quoted value at line 1"),
+        ] {
+            let err = parse(content).expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), error::ErrorKind::Parse);
+            assert_eq!(err.to_string(), *message);
+        }
+    }
+}
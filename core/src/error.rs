@@ -3,6 +3,7 @@
 
 use std::error;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// The error type for rocket-config operations of the associated traits.
 ///
@@ -25,10 +26,26 @@ enum Repr {
     Custom(Box<Custom>),
 }
 
-#[derive(Debug)]
 struct Custom {
     kind: ErrorKind,
     error: Box<dyn error::Error+Send+Sync>,
+    path: Option<PathBuf>,
+    location: Option<(usize, usize)>,
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = fmt.debug_struct("Custom");
+
+        debug.field("kind", &self.kind).field("error", &self.error);
+        if let Some(ref path) = self.path {
+            debug.field("path", path);
+        }
+        if let Some(ref location) = self.location {
+            debug.field("location", location);
+        }
+        debug.finish()
+    }
 }
 
 /// A list specifying general categories of rocket-config error.
@@ -41,8 +58,16 @@ struct Custom {
 /// [`rocket-config::error::Error`]: struct.Error.html
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ErrorKind {
+    DeserializeError,
     FormatError,
+    /// A file or directory could not be read or written.
+    Io,
     MissingValue,
+    /// A configuration file is not valid in its format.
+    Parse,
+    /// A lock got poisoned by a thread which panicked while holding it.
+    Poisoned,
+    SerializeError,
     UnimplementedFormat,
     Other,
 }
@@ -50,8 +75,13 @@ pub enum ErrorKind {
 impl ErrorKind {
     pub(crate) fn as_str(self) -> &'static str {
         match self {
+            ErrorKind::DeserializeError     => "deserialize_error",
             ErrorKind::FormatError          => "format_error",
+            ErrorKind::Io                   => "io",
             ErrorKind::MissingValue         => "missing_value",
+            ErrorKind::Parse                => "parse",
+            ErrorKind::Poisoned             => "poisoned",
+            ErrorKind::SerializeError       => "serialize_error",
             ErrorKind::UnimplementedFormat  => "unimplemented_format",
             ErrorKind::Other                => "other",
         }
@@ -74,6 +104,24 @@ impl From<ErrorKind> for Error {
     }
 }
 
+/// Converts an I/O error into an [`Error`] of kind `Io`.
+///
+/// [`Error`]: ./struct.Error.html
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::new(ErrorKind::Io, err)
+    }
+}
+
+/// Converts a poisoned lock error into an [`Error`] of kind `Poisoned`.
+///
+/// [`Error`]: ./struct.Error.html
+impl<T> From<std::sync::PoisonError<T>> for Error {
+    fn from(err: std::sync::PoisonError<T>) -> Error {
+        Error::new(ErrorKind::Poisoned, err.to_string())
+    }
+}
+
 impl Error {
     /// Creates a new I/O error from a known kind of error as well as an
     /// arbitrary error payload.
@@ -92,10 +140,79 @@ impl Error {
             repr: Repr::Custom(Box::new(Custom {
                 kind,
                 error,
+                path: None,
+                location: None,
             }))
         }
     }
 
+    /// Attaches the path of the configuration file which caused this error.
+    ///
+    /// The path is then prepended to the displayed message, and can be
+    /// retrieved with [`path`].
+    ///
+    /// [`path`]: #method.path
+    pub(crate) fn with_path<P: AsRef<Path>>(self, path: P) -> Error {
+        let mut custom = match self.repr {
+            Repr::Simple(kind) => Box::new(Custom {
+                kind,
+                error: kind.as_str().into(),
+                path: None,
+                location: None,
+            }),
+            Repr::Custom(custom) => custom,
+        };
+
+        custom.path = Some(path.as_ref().to_path_buf());
+        Error {
+            repr: Repr::Custom(custom)
+        }
+    }
+
+    /// Returns the path of the configuration file which caused this error
+    /// (if any).
+    pub fn path(&self) -> Option<&Path> {
+        match self.repr {
+            Repr::Simple(..) => None,
+            Repr::Custom(ref c) => c.path.as_deref(),
+        }
+    }
+
+    /// Attaches the location, as a 1-based line and column, of the part of
+    /// the configuration file which caused this error.
+    ///
+    /// The location can then be retrieved with [`location`].
+    ///
+    /// [`location`]: #method.location
+    pub(crate) fn with_location(self, line: usize, column: usize) -> Error {
+        let mut custom = match self.repr {
+            Repr::Simple(kind) => Box::new(Custom {
+                kind,
+                error: kind.as_str().into(),
+                path: None,
+                location: None,
+            }),
+            Repr::Custom(custom) => custom,
+        };
+
+        custom.location = Some((line, column));
+        Error {
+            repr: Repr::Custom(custom)
+        }
+    }
+
+    /// Returns the line and column, both starting at 1, of the part of the
+    /// configuration file which caused this error (if known).
+    ///
+    /// It is known for the errors of kind `Parse` reported for malformed
+    /// JSON and YAML files.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self.repr {
+            Repr::Simple(..) => None,
+            Repr::Custom(ref c) => c.location,
+        }
+    }
+
     /// Returns a reference to the inner error wrapped by this error (if any).
     ///
     /// If this `Error` was constructed via `new` then this function will
@@ -151,7 +268,10 @@ impl fmt::Debug for Repr {
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.repr {
-            Repr::Custom(ref c) => c.error.fmt(fmt),
+            Repr::Custom(ref c) => match c.path {
+                Some(ref path) => write!(fmt, "failed to load {}: {}", path.display(), c.error),
+                None => c.error.fmt(fmt),
+            },
             Repr::Simple(kind) => write!(fmt, "{}", kind.as_str()),
         }
     }
@@ -181,6 +301,41 @@ impl error::Error for Error {
     }
 }
 
+/// The errors encountered while loading configurations leniently.
+///
+/// It is wrapped in the error returned by [`Factory::load`] in lenient mode,
+/// and can be retrieved with [`Error::get_ref`].
+///
+/// [`Factory::load`]: ../struct.Factory.html#method.load
+/// [`Error::get_ref`]: struct.Error.html#method.get_ref
+#[derive(Debug)]
+pub struct LoadErrors {
+    errors: Vec<Error>,
+}
+
+impl LoadErrors {
+    pub(crate) fn new(errors: Vec<Error>) -> LoadErrors {
+        LoadErrors { errors }
+    }
+
+    /// Returns the errors, in the order they were encountered.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for LoadErrors {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{} configuration(s) failed to load", self.errors.len())?;
+        for (index, error) in self.errors.iter().enumerate() {
+            write!(fmt, "{} {}", if index == 0 { ":" } else { ";" }, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for LoadErrors {}
+
 fn _assert_error_is_sync_send() {
     fn _is_sync_send<T: Sync+Send>() {}
     _is_sync_send::<Error>();
@@ -189,6 +344,7 @@ fn _assert_error_is_sync_send() {
 #[cfg(test)]
 mod tests {
     use std::error::Error as _;
+    use std::path::Path;
     use super::{Error, ErrorKind};
 
     #[test]
@@ -211,14 +367,18 @@ mod tests {
 
     #[test]
     fn errorkind_as_str() {
+        let error_deserialize_error = Error::from(ErrorKind::DeserializeError);
         let error_format_error = Error::from(ErrorKind::FormatError);
         let error_missing_value = Error::from(ErrorKind::MissingValue);
         let error_other = Error::from(ErrorKind::Other);
+        let error_serialize_error = Error::from(ErrorKind::SerializeError);
         let error_unimplemented_format = Error::from(ErrorKind::UnimplementedFormat);
 
+        assert_eq!(error_deserialize_error.kind().as_str(), "deserialize_error");
         assert_eq!(error_format_error.kind().as_str(), "format_error");
         assert_eq!(error_missing_value.kind().as_str(), "missing_value");
         assert_eq!(error_other.kind().as_str(), "other");
+        assert_eq!(error_serialize_error.kind().as_str(), "serialize_error");
         assert_eq!(error_unimplemented_format.kind().as_str(), "unimplemented_format");
     }
 
@@ -353,4 +513,61 @@ mod tests {
     fn assert_error_is_sync_send() {
         super::_assert_error_is_sync_send();
     }
+
+    #[test]
+    fn with_path() {
+        let error = Error::new(ErrorKind::Other, "test error")
+            .with_path("config/diesel.json");
+
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(error.path(), Some(Path::new("config/diesel.json")));
+        assert_eq!(format!("{}", error), "failed to load config/diesel.json: test error");
+
+        let error = Error::from(ErrorKind::MissingValue).with_path("diesel.json");
+
+        assert_eq!(error.kind(), ErrorKind::MissingValue);
+        assert_eq!(format!("{}", error), "failed to load diesel.json: missing_value");
+        assert!(Error::new(ErrorKind::Other, "test error").path().is_none());
+    }
+
+    #[test]
+    fn from_poison_error() {
+        let lock = std::sync::Arc::new(std::sync::RwLock::new(()));
+        let poisoning = std::sync::Arc::clone(&lock);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoning.write().unwrap();
+            panic!("poisoning the lock");
+        }).join();
+
+        let error = Error::from(lock.read().expect_err("expected an Err, got a result"));
+        assert_eq!(error.kind(), ErrorKind::Poisoned);
+    }
+
+    #[test]
+    fn with_location() {
+        let error = Error::new(ErrorKind::Parse, "expected value")
+            .with_location(12, 5)
+            .with_path("diesel.json");
+
+        assert_eq!(error.kind(), ErrorKind::Parse);
+        assert_eq!(error.location(), Some((12, 5)));
+        assert_eq!(error.path(), Some(Path::new("diesel.json")));
+        assert_eq!(Error::from(ErrorKind::Parse).with_location(1, 1).location(), Some((1, 1)));
+        assert!(Error::new(ErrorKind::Parse, "expected value").location().is_none());
+        assert!(Error::from(ErrorKind::Parse).location().is_none());
+    }
+
+    #[test]
+    fn load_errors() {
+        let errors = super::LoadErrors::new(vec![
+            Error::new(ErrorKind::Other, "test error").with_path("diesel.json"),
+            Error::from(ErrorKind::MissingValue),
+        ]);
+
+        assert_eq!(errors.errors().len(), 2);
+        assert_eq!(
+            format!("{}", errors),
+            "2 configuration(s) failed to load: failed to load diesel.json: test error; missing_value"
+        );
+    }
 }
\ No newline at end of file
@@ -9,11 +9,17 @@ use {
         },
         Rocket,
     },
+    notify::{
+        DebouncedEvent,
+        RecursiveMode,
+        Watcher
+    },
     std::{
-        collections::BTreeMap,
-        error::Error,
-        path::Path,
-        sync::{Arc, RwLock}
+        collections::{BTreeMap, BTreeSet},
+        path::{Path, PathBuf},
+        sync::{mpsc, Arc, PoisonError, RwLock},
+        thread,
+        time::Duration
     },
     super::{
         configuration,
@@ -26,10 +32,13 @@ use {
 fn is_file_handled(path: &Path) -> bool
 {
     lazy_static! {
-        static ref HANDLED_EXTENSIONS: [&'static std::ffi::OsStr; 3] = [
+        static ref HANDLED_EXTENSIONS: [&'static std::ffi::OsStr; 6] = [
             std::ffi::OsStr::new("json"),
             std::ffi::OsStr::new("yml"),
-            std::ffi::OsStr::new("yaml")
+            std::ffi::OsStr::new("yaml"),
+            std::ffi::OsStr::new("ini"),
+            std::ffi::OsStr::new("properties"),
+            std::ffi::OsStr::new("env")
         ];
     }
 
@@ -45,13 +54,200 @@ fn is_file_handled(path: &Path) -> bool
     }
 }
 
+/// Tells whether `text` matches the glob `pattern`, where `*` matches any run
+/// of characters but `/`, `**` any run of characters, and `?` one character
+/// but `/`. Any other character matches itself.
+///
+/// The match runs in O(pattern × text) time: on a mismatch, only the last `*`
+/// and the last `**` seen are tried again, one character further, as any
+/// earlier wildcard could only match what they match.
+fn glob_matches(pattern: &[char], text: &[char]) -> bool
+{
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last `*`, and after the last `**`, which
+    // tells as well whether it is followed by a `/`
+    let mut star: Option<(usize, usize)> = None;
+    let mut globstar: Option<(usize, usize, bool)> = None;
+
+    while p < pattern.len() || t < text.len() {
+        match pattern.get(p) {
+            Some('*') if pattern.get(p + 1) == Some(&'*') => {
+                // `**/` also matches no directory at all
+                let slash = pattern.get(p + 2) == Some(&'/');
+                p += if slash { 3 } else { 2 };
+                globstar = Some((p, t, slash));
+                star = None;
+                continue;
+            },
+            Some('*') => {
+                p += 1;
+                star = Some((p, t));
+                continue;
+            },
+            Some('?') if t < text.len() && text[t] != '/' => {
+                p += 1;
+                t += 1;
+                continue;
+            },
+            Some(character) if *character != '?' && text.get(t) == Some(character) => {
+                p += 1;
+                t += 1;
+                continue;
+            },
+            _ => {},
+        }
+
+        // `*` takes one more character, unless it is a `/`
+        if let Some((star_p, star_t)) = star {
+            if star_t < text.len() && text[star_t] != '/' {
+                star = Some((star_p, star_t + 1));
+                p = star_p;
+                t = star_t + 1;
+                continue;
+            }
+        }
+
+        // `**` takes one more character, or one more directory for `**/`
+        if let Some((globstar_p, globstar_t, slash)) = globstar {
+            let next = if slash {
+                text[globstar_t..].iter().position(|character| *character == '/')
+                    .map(|index| globstar_t + index + 1)
+            }
+            else {
+                Some(globstar_t + 1).filter(|&next| next <= text.len())
+            };
+
+            if let Some(next) = next {
+                globstar = Some((globstar_p, next, slash));
+                star = None;
+                p = globstar_p;
+                t = next;
+                continue;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+/// Tells whether `path`, a file found in `directory` or in one of its
+/// subdirectories, matches the glob `pattern`.
+///
+/// A pattern holding a `/` is matched against the path relative to
+/// `directory`, with `/` separators, and any other pattern against the file
+/// name only, e.g. `*.config.json` matches `database/diesel.config.json`.
+fn path_matches(pattern: &str, directory: &Path, path: &Path) -> bool
+{
+    let text = if pattern.contains('/') {
+        let relative = path.strip_prefix(directory).unwrap_or(path);
+        relative.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+    else {
+        file_name(path).to_string_lossy().into_owned()
+    };
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_matches(&pattern, &text)
+}
+
+/// Returns the name of the configuration stored at `path`, a file found in
+/// `directory` or in one of its subdirectories.
+///
+/// The name is the file stem, prefixed by the names of the subdirectories
+/// leading to the file, all joined with `.` (e.g. `database.diesel` for
+/// `database/diesel.json`).
+fn configuration_name(directory: &Path, path: &Path) -> Option<String>
+{
+    let relative = path.strip_prefix(directory).ok()?;
+    let mut segments = relative.parent()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+
+    segments.push(relative.file_stem()?.to_str()?);
+    Some(segments.join("."))
+}
+
+/// Same as [`configuration_name`], but fails when `path` has no valid name.
+///
+/// [`configuration_name`]: fn.configuration_name.html
+fn file_configuration_name(directory: &Path, path: &Path) -> result::Result<String>
+{
+    configuration_name(directory, path).ok_or_else(|| {
+        error::Error::new(error::ErrorKind::Other, "invalid file name").with_path(path)
+    })
+}
+
+/// Returns the file name of `path`, for logging purposes.
+fn file_name(path: &Path) -> &std::ffi::OsStr
+{
+    path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("invalid file name"))
+}
+
+/// Returns the canonical form of the configuration directory `path`, with
+/// every symbolic link resolved, or `path` itself when it cannot be resolved
+/// (e.g. it does not exist), so that reading it reports the error.
+fn resolve_directory(path: &Path) -> PathBuf
+{
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Watches the configuration directories of a factory until dropped, as
+/// returned by [`Factory::watch`].
+///
+/// [`Factory::watch`]: struct.Factory.html#method.watch
+#[must_use = "the directories stop being watched as soon as the guard is dropped"]
+pub struct WatchGuard
+{
+    watcher: Option<notify::RecommendedWatcher>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for WatchGuard
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        f.debug_struct("WatchGuard").finish()
+    }
+}
+
+impl Drop for WatchGuard
+{
+    fn drop(&mut self)
+    {
+        // Without the watcher, the events channel closes and the thread ends
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Factory
 {
     configurations: Arc<RwLock<BTreeMap<String, configuration::Configuration>>>,
+    directory: Option<PathBuf>,
+    exclude_patterns: Vec<String>,
+    fail_on_error: bool,
+    format_precedence: Option<Vec<String>>,
+    include_patterns: Vec<String>,
+    lenient: bool,
+    merge: bool,
+    profile: Option<String>,
+    profile_configurations: Arc<RwLock<BTreeMap<String, configuration::Configuration>>>,
+    recursive: bool,
+    strict_env: bool,
 
     #[cfg(debug_assertions)] // If running development mode
-    dev_configurations: Arc<RwLock<BTreeMap<String, configuration::Configuration>>>
+    dev_configurations: Arc<RwLock<BTreeMap<String, configuration::Configuration>>>,
+    dev_directory: Option<PathBuf>
 }
 
 impl Factory
@@ -60,292 +256,1207 @@ impl Factory
     {
         Self {
             configurations: Arc::new(RwLock::new(BTreeMap::new())),
+            directory: None,
+            exclude_patterns: Vec::new(),
+            fail_on_error: false,
+            format_precedence: None,
+            include_patterns: Vec::new(),
+            lenient: false,
+            merge: false,
+            profile: None,
+            profile_configurations: Arc::new(RwLock::new(BTreeMap::new())),
+            recursive: false,
+            strict_env: false,
 
             #[cfg(debug_assertions)] // If running development mode
-            dev_configurations: Arc::new(RwLock::new(BTreeMap::new()))
+            dev_configurations: Arc::new(RwLock::new(BTreeMap::new())),
+            dev_directory: None
         }
     }
 
-    fn load_directory(
-        path: &Path,
-        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>
-    )
-        -> Result<(), error::Error>
+    /// Creates a factory loading its configurations from `path` instead of
+    /// `./config`.
+    ///
+    /// Unless overridden with [`with_dev_directory`], development
+    /// configurations are then loaded from the `dev` subdirectory of `path`.
+    ///
+    /// `path` may be a symbolic link: configuration directories are resolved
+    /// to their canonical path when loaded, and files are reported under it.
+    /// Symbolic links to files are followed too, while broken ones are
+    /// skipped with a warning.
+    ///
+    /// [`with_dev_directory`]: #method.with_dev_directory
+    pub fn with_directory<P: Into<PathBuf>>(path: P) -> Self
     {
-        for entry in path.read_dir().map_err(|err| error::Error::new(error::ErrorKind::Other, err.description()))? {
-            let entry = entry.map_err(|err| error::Error::new(error::ErrorKind::Other, err.description()))?;
-            let path = entry.path();
-
-            if is_file_handled(&path) {
-                if let Ok(mut configurations) = configurations_to_load.write() {
-                    if let Some(_previous_value) = configurations.insert(
-                        path.file_stem()
-                            .expect("expected valid file name")
-                            .to_str().ok_or_else(|| error::Error::new(error::ErrorKind::Other, "invalid file name"))?
-                            .to_owned(),
-                        {
-                            eprintln!(
-                                "Configuration file awaiting for initialization: {:?}",
-                                path.file_name().unwrap_or(
-                                    std::ffi::OsStr::new("invalid file name")
-                                )
-                            );
-
-                            let configuration = configuration::Configuration::new(&path);
-                            configuration.load()?;
-
-                            eprintln!(
-                                "Configuration file initialized: {:?}",
-                                path.file_name().unwrap_or(
-                                    std::ffi::OsStr::new("invalid file name")
-                                )
-                            );
-
-                            configuration
-                        }
-                    ) {
-                        return Err(error::Error::new(
-                            error::ErrorKind::Other,
-                            format!(
-                                "a configuration already exists for '{}'",
-                                path.file_stem()
-                                    .expect("expected valid file name")
-                                    .to_str()
-                                    .ok_or_else(|| error::Error::new(
-                                        error::ErrorKind::Other,
-                                        "invalid file name"
-                                    ))?
-                            )
-                        ));
-                    }
-                }
-            }
+        Self {
+            directory: Some(path.into()),
+            ..Self::new()
         }
-        Ok(())
     }
 
-    #[cfg(debug_assertions)] // If running development mode
-    fn load_development_directory(&self)
-        -> Result<(), error::Error>
+    /// Loads the development configurations from `path` instead of the
+    /// default development directory.
+    ///
+    /// Development configurations are only used in debug builds.
+    pub fn with_dev_directory<P: Into<PathBuf>>(mut self, path: P) -> Self
     {
-        Self::load_directory(
-            &Path::new(constants::DEV_CONFIGURATION_DIRECTORY),
-            &self.dev_configurations
-        )
+        self.dev_directory = Some(path.into());
+        self
     }
 
-    fn load_production_directory(&self)
-        -> Result<(), error::Error>
+    /// Loads the configurations of the `name` profile, found in the `name`
+    /// subdirectory of the configuration directory (e.g. `config/staging`),
+    /// over the production ones.
+    ///
+    /// A profile configuration replaces its production counterpart, or is
+    /// deep-merged onto it with [`with_merge`]. In debug builds, development
+    /// configurations still come last, over the profile ones. Loading fails
+    /// if the profile directory does not exist.
+    ///
+    /// [`with_merge`]: #method.with_merge
+    pub fn with_profile<S: Into<String>>(mut self, name: S) -> Self
     {
-        Self::load_directory(
-            &Path::new(constants::CONFIGURATION_DIRECTORY),
-            &self.configurations
-        )
+        self.profile = Some(name.into());
+        self
     }
 
-    pub fn load(&self)
-        -> Result<(), error::Error>
+    /// Same as [`with_profile`], with the profile named by the
+    /// `ROCKET_CONFIG_PROFILE` environment variable. No profile is used when
+    /// the variable is unset or empty.
+    ///
+    /// [`with_profile`]: #method.with_profile
+    pub fn with_profile_from_env(mut self) -> Self
     {
-        self.load_production_directory()?;
+        self.profile = std::env::var(constants::PROFILE_VARIABLE).ok()
+            .filter(|profile| !profile.is_empty());
+        self
+    }
 
-        // If running development mode
-        #[cfg(debug_assertions)] self.load_development_directory()?;
+    /// Deep-merges development configurations onto their production
+    /// counterpart instead of replacing them.
+    ///
+    /// In debug builds, [`get`] then returns the production configuration
+    /// overridden by the keys the development one specifies, as done by
+    /// [`Value::merge`]: objects are merged key by key, and the development
+    /// side wins on any other conflict, arrays included. The merged
+    /// configuration is a snapshot without a file, like the ones given to
+    /// [`register`]: reloading it keeps its value, and it cannot be saved.
+    /// Each call to [`get`] merges the current configurations again.
+    ///
+    /// [`get`]: #method.get
+    /// [`register`]: #method.register
+    /// [`Value::merge`]: enum.Value.html#method.merge
+    pub fn with_merge(mut self, merge: bool) -> Self
+    {
+        self.merge = merge;
+        self
+    }
 
-        Ok(())
+    /// Also loads the configurations found in the subdirectories of the
+    /// configuration directories.
+    ///
+    /// The name of such a configuration is its path relative to the
+    /// configuration directory, without extension and with separators
+    /// replaced by `.`: `config/database/diesel.json` is named
+    /// `database.diesel`. The development directory is never loaded as part
+    /// of the production one.
+    ///
+    /// Symbolic links to subdirectories are followed, unless they link to a
+    /// directory being scanned: such a cycle is skipped with a warning.
+    ///
+    /// Only the files directly in the configuration directories are loaded by
+    /// default.
+    pub fn recursive(mut self, recursive: bool) -> Self
+    {
+        self.recursive = recursive;
+        self
     }
 
-    #[cfg(debug_assertions)]
-    fn get_development(&self, configuration_name: &str)
-        -> result::Result<configuration::Configuration>
+    /// Keeps on loading the other configurations when one of them fails to
+    /// load, instead of stopping at the first error.
+    ///
+    /// The configurations which loaded successfully are then available, and
+    /// [`load`] returns every error encountered at once. Loading is strict by
+    /// default.
+    ///
+    /// [`load`]: #method.load
+    pub fn lenient(mut self, lenient: bool) -> Self
     {
-        if let Ok(guard) = self.dev_configurations.read() {
-            guard.get(configuration_name).ok_or_else(|| error::Error::from(
-                error::ErrorKind::MissingValue
-            )).map(|configuration: &'_ configuration::Configuration|
-                (*configuration).clone()
-            )
-        }
-        else {
-            Err(error::Error::new(
-                error::ErrorKind::Other, "dev_configurations got poisoned"
-            ))
-        }
+        self.lenient = lenient;
+        self
     }
 
-    pub fn get(&self, configuration_name: &str) -> result::Result<configuration::Configuration>
+    /// Fails to load a configuration file when one of its
+    /// `%env(resolve:NAME)%` placeholders refers to an unset environment
+    /// variable without fallback, as done by [`Value::try_resolve_env`].
+    ///
+    /// Such placeholders are left untouched by default.
+    ///
+    /// [`Value::try_resolve_env`]: enum.Value.html#method.try_resolve_env
+    pub fn strict_env(mut self, strict_env: bool) -> Self
     {
-        // First, try to get development configuration if compiled in development
-        #[cfg(debug_assertions)]
-        {
-            if let Ok(configuration) = self.get_development(configuration_name) {
-                return Ok(configuration);
-            }
-            // Error is ignored
-        }
+        self.strict_env = strict_env;
+        self
+    }
 
-        // Then, if not available tries to return production configuration 
-        if let Ok(guard) = self.configurations.read() {
-            guard.get(configuration_name).ok_or_else(|| error::Error::from(
-                error::ErrorKind::MissingValue
-            )).map(|configuration: &'_ configuration::Configuration|
-                (*configuration).clone()
-            )
-        }
-        else {
-            Err(error::Error::new(
-                error::ErrorKind::Other, "configurations got poisoned"
-            ))
-        }
+    /// Aborts the ignition of the Rocket instance the factory is attached to
+    /// when [`load`] fails, instead of launching it with the configurations
+    /// which could be loaded, if any.
+    ///
+    /// The reason is logged before the ignition is aborted. In [`lenient`]
+    /// mode, the ignition is aborted as soon as one file fails to load.
+    /// Failures are ignored by default.
+    ///
+    /// [`load`]: #method.load
+    /// [`lenient`]: #method.lenient
+    pub fn fail_on_error(mut self, fail_on_error: bool) -> Self
+    {
+        self.fail_on_error = fail_on_error;
+        self
     }
-}
 
-impl Fairing for Factory
-{
-    fn info(&self) -> Info
+    /// Sets the order in which formats take precedence when files of a
+    /// directory share a configuration name, such as `diesel.json` and
+    /// `diesel.yaml`: only the file whose extension comes first in
+    /// `extensions` is loaded, and the others are skipped with a warning.
+    ///
+    /// The extensions missing from `extensions` come after the others, in
+    /// alphabetical order. By default, the order is `json`, `yaml`, `yml`,
+    /// `ini`, `properties` and `env`.
+    pub fn with_format_precedence<S: AsRef<str>>(mut self, extensions: &[S]) -> Self
     {
-        Info {
-            name: "Configuration factory",
-            kind: Kind::Attach
-        }
+        self.format_precedence = Some(extensions.iter()
+            .map(|extension| extension.as_ref().to_owned())
+            .collect());
+        self
     }
 
-    fn on_attach(&self, rocket: Rocket)
-        -> std::result::Result<Rocket, Rocket>
+    /// Only loads the configuration files matching the glob `pattern`, or one
+    /// of the other patterns given this way, e.g. `*.config.json` to leave
+    /// out the fixtures and schemas kept along with the configurations.
+    ///
+    /// `*` matches any run of characters but `/`, `**` any run of
+    /// characters, and `?` one character but `/`. A pattern holding a `/` is
+    /// matched against the path of the file relative to its configuration
+    /// directory, and any other pattern against its file name. Only files
+    /// with a handled extension are loaded, whatever the patterns.
+    pub fn with_pattern<S: Into<String>>(mut self, pattern: S) -> Self
     {
-        // Loads available configurations
-        let _ = self.load();
+        self.include_patterns.push(pattern.into());
+        self
+    }
 
-        // Stores himself in the state
-        let rocket = rocket.manage((*self).clone());
+    /// Leaves out the configuration files matching the glob `pattern`, as
+    /// understood by [`with_pattern`], even when they match an included
+    /// pattern.
+    ///
+    /// [`with_pattern`]: #method.with_pattern
+    pub fn with_exclude_pattern<S: Into<String>>(mut self, pattern: S) -> Self
+    {
+        self.exclude_patterns.push(pattern.into());
+        self
+    }
 
-        Ok(rocket)
-    } 
-}
+    /// Tells whether `path`, a file found in `root`, is loaded according to
+    /// the include and exclude patterns.
+    fn is_file_included(&self, root: &Path, path: &Path) -> bool
+    {
+        let matches = |pattern: &String| path_matches(pattern, root, path);
 
-#[cfg(test)]
-mod tests {
-    use std::env;
-    use std::fs::OpenOptions;
-    use std::io::Result;
-    use std::io::Write as _;
-    use std::path::{Path, PathBuf};
-    use tempfile;
+        (self.include_patterns.is_empty() || self.include_patterns.iter().any(matches))
+            && !self.exclude_patterns.iter().any(matches)
+    }
 
-    fn create_temporary_file(prefix: &str, suffix: &str, rand_bytes: usize, dest: &Path)
-        -> Result<tempfile::NamedTempFile>
+    /// Returns the directory production configurations are loaded from.
+    fn production_directory(&self) -> PathBuf
     {
-        tempfile::Builder::new()
-            .prefix(prefix)
-            .suffix(suffix)
-            .rand_bytes(rand_bytes)
-            .tempfile_in(dest)
+        self.directory.clone().unwrap_or_else(|| {
+            PathBuf::from(constants::CONFIGURATION_DIRECTORY)
+        })
     }
 
-    fn delete_temporary_file(temp_file: tempfile::NamedTempFile)
+    /// Returns the directory development configurations are loaded from.
+    fn development_directory(&self) -> PathBuf
     {
-        let _ = temp_file.close();
+        match (&self.dev_directory, &self.directory) {
+            (Some(dev_directory), _) => dev_directory.clone(),
+            (None, Some(directory)) => directory.join("dev"),
+            (None, None) => PathBuf::from(constants::DEV_CONFIGURATION_DIRECTORY),
+        }
     }
 
-    fn create_temporary_directory(prefix: &str, suffix: &str, rand_bytes: usize, dest: &Path)
-        -> Result<tempfile::TempDir>
+    /// Returns the directory the profile configurations are loaded from, if a
+    /// profile is used.
+    fn profile_directory(&self) -> Option<PathBuf>
     {
-        tempfile::Builder::new()
-            .prefix(prefix)
-            .suffix(suffix)
-            .rand_bytes(rand_bytes)
-            .tempdir_in(dest)
+        self.profile.as_ref().map(|profile| self.production_directory().join(profile))
     }
 
-    fn delete_temporary_directory(temp_dir: tempfile::TempDir)
+    /// Returns true if `path` is the directory profile configurations are
+    /// loaded from.
+    fn is_profile_directory(&self, path: &Path) -> bool
     {
-        let _ = temp_dir.close();
+        match self.profile_directory().map(|directory| directory.canonicalize()) {
+            Some(Ok(profile_directory)) => path.canonicalize().ok() == Some(profile_directory),
+            _ => false
+        }
     }
 
-    fn cwd(path: &Path) -> PathBuf
+    /// Returns true if `path` is the directory development configurations are
+    /// loaded from.
+    fn is_development_directory(&self, path: &Path) -> bool
     {
-        let current_dir = env::current_dir()
-            .expect("failed to retrieve current directory");
+        match (path.canonicalize(), self.development_directory().canonicalize()) {
+            (Ok(path), Ok(development_directory)) => path == development_directory,
+            _ => false
+        }
+    }
 
-        env::set_current_dir(path)
-            .expect("failed to change current directory");
-        current_dir
+    /// Returns `result` in strict mode. In lenient mode, its error is pushed
+    /// to `errors` instead, so that the caller keeps on loading.
+    fn tolerate(&self, result: result::Result<()>, errors: &mut Vec<error::Error>)
+        -> Result<(), error::Error>
+    {
+        match result {
+            Err(err) if self.lenient => {
+                errors.push(err);
+                Ok(())
+            },
+            result => result
+        }
     }
 
-    #[test]
-    fn is_file_handled()
+    /// Pushes to `files` the configuration files found in `path`, and in its
+    /// subdirectories in recursive mode.
+    ///
+    /// `ancestors` holds the canonical paths of the directories being scanned,
+    /// so that a symbolic link to one of them is skipped instead of being
+    /// followed forever.
+    fn find_files(
+        &self,
+        path: &Path,
+        files: &mut Vec<PathBuf>,
+        ancestors: &mut BTreeSet<PathBuf>,
+        errors: &mut Vec<error::Error>
+    )
+        -> Result<(), error::Error>
     {
-        assert_eq!(super::is_file_handled(Path::new("/unknown-file")), false);
+        let canonical = resolve_directory(path);
+        if ancestors.contains(&canonical) {
+            warn!("Configuration directory skipped, it links to one of its parents: {:?}", path);
+            return Ok(());
+        }
 
-        let file = create_temporary_file("", "", 24, &env::temp_dir()).unwrap();
-        assert_eq!(super::is_file_handled(file.path()), false);
-        delete_temporary_file(file);
+        ancestors.insert(canonical.clone());
+        let result = self.find_files_in(path, files, ancestors, errors);
+        ancestors.remove(&canonical);
+        result
+    }
 
-        let file = create_temporary_file("", ".json", 24, &env::temp_dir()).unwrap();
-        assert_eq!(super::is_file_handled(file.path()), true);
-        delete_temporary_file(file);
+    /// Scans the entries of `path` for [`find_files`].
+    ///
+    /// [`find_files`]: #method.find_files
+    fn find_files_in(
+        &self,
+        path: &Path,
+        files: &mut Vec<PathBuf>,
+        ancestors: &mut BTreeSet<PathBuf>,
+        errors: &mut Vec<error::Error>
+    )
+        -> Result<(), error::Error>
+    {
+        let entries = match path.read_dir() {
+            Ok(entries) => entries,
+            Err(err) => {
+                let err = error::Error::new(error::ErrorKind::Io, err.to_string()).with_path(path);
+                return self.tolerate(Err(err), errors);
+            }
+        };
 
-        let file = create_temporary_file("", ".yml", 24, &env::temp_dir()).unwrap();
-        assert_eq!(super::is_file_handled(file.path()), true);
-        delete_temporary_file(file);
+        for entry in entries {
+            let entry = entry.map_err(|err| error::Error::new(error::ErrorKind::Io, err.to_string()).with_path(path));
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(err) => {
+                    self.tolerate(Err(err), errors)?;
+                    continue;
+                }
+            };
 
-        let file = create_temporary_file("", ".yaml", 24, &env::temp_dir()).unwrap();
-        assert_eq!(super::is_file_handled(file.path()), true);
-        delete_temporary_file(file);
+            if self.recursive
+                && path.is_dir()
+                && !self.is_development_directory(&path)
+                && !self.is_profile_directory(&path)
+            {
+                self.find_files(&path, files, ancestors, errors)?;
+                continue;
+            }
+
+            if is_file_handled(&path) {
+                files.push(path);
+            }
+            else if path.is_file() {
+                warn!("Configuration file skipped, its format is not handled: {:?}", file_name(&path));
+            }
+            // Listed entries which do not exist are symbolic links to nowhere
+            else if !path.exists() {
+                warn!("Configuration file skipped, its symbolic link is broken: {:?}", file_name(&path));
+            }
+        }
+        Ok(())
     }
 
-    fn mount_load_env(path: &Path)
-        -> (Vec<tempfile::TempDir>, Vec<tempfile::NamedTempFile>)
+    /// Returns the rank of the format of `path` in the precedence order, the
+    /// lowest rank winning.
+    fn format_rank(&self, path: &Path) -> usize
     {
-        let mut directories = Vec::new();
-        let mut files = Vec::new();
+        let extension = path.extension().and_then(std::ffi::OsStr::to_str);
+        let rank = match &self.format_precedence {
+            Some(precedence) => precedence.iter().position(|each| Some(each.as_str()) == extension),
+            None => constants::FORMAT_PRECEDENCE.iter().position(|each| Some(*each) == extension),
+        };
 
-        // Create the following directories tree:
-        // .
-        // └── config
-        //     └── dev
-        {
-            directories.push(
-                create_temporary_directory("config", "", 0, path).unwrap()
-            );
+        rank.unwrap_or(usize::MAX)
+    }
 
-            directories.push(
-                create_temporary_directory("dev", "", 0, directories[0].path()).unwrap()
-            );
+    /// Keeps, among the `files` found in `root` which share a configuration
+    /// name, the one whose format takes precedence, and warns about the
+    /// others. Ties, such as unranked formats, go to the first path in
+    /// alphabetical order.
+    fn skip_shadowed_files(&self, root: &Path, files: Vec<PathBuf>) -> Vec<PathBuf>
+    {
+        let mut files: Vec<_> = files.into_iter()
+            .map(|path| (configuration_name(root, &path), self.format_rank(&path), path))
+            .collect();
+        files.sort();
+
+        let mut kept: Vec<(Option<String>, PathBuf)> = Vec::with_capacity(files.len());
+        for (name, _, path) in files {
+            match kept.last() {
+                Some((Some(kept_name), kept_path)) if name.as_ref() == Some(kept_name) => {
+                    warn!(
+                        "Configuration file skipped, {:?} takes precedence: {:?}",
+                        file_name(kept_path), file_name(&path)
+                    );
+                },
+                _ => kept.push((name, path)),
+            }
         }
 
-        // Create the following final tree:
-        // .
-        // └── config
-        //     └── dieselXXXXXXXX.json          # Valid file
-        //     └── no_extension                 # Invalid file (no ext.)
-        //     └── dev
-        //         └── dieselXXXXXXXX.json      # Valid file
-        //         └── invalid_extension.toto   # Invalid file (unhandled ext.)
-        {
-            // Creates an invalid file in production directory
-            files.push(
-                create_temporary_file("no_extension", "", 16, directories[0].path()).unwrap()
-            );
+        kept.into_iter().map(|(_, path)| path).collect()
+    }
 
-            // Creates an invalid file in development directory
-            files.push(
-                create_temporary_file("invalid_extension_dev", "toto", 4, directories[1].path()).unwrap()
-            );
+    fn load_directory(
+        &self,
+        root: &Path,
+        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>,
+        errors: &mut Vec<error::Error>
+    )
+        -> Result<(), error::Error>
+    {
+        let root = &resolve_directory(root);
+        let mut files = Vec::new();
 
-            // Creates a valid file in production directory
-            {
-                files.push(
-                    create_temporary_file("diesel", ".json", 0, directories[0].path()).unwrap()
-                );
+        self.find_files(root, &mut files, &mut BTreeSet::new(), errors)?;
+        files.retain(|path| self.is_file_included(root, path));
+        for path in self.skip_shadowed_files(root, files) {
+            let result = self.load_file(root, &path, configurations_to_load);
+            self.tolerate(result, errors)?;
+        }
+        Ok(())
+    }
 
-                let mut diesel_dot_json = OpenOptions::new()
-                    .write(true)
-                    .open(files.last().unwrap().path())
-                    .expect("failed to open diesel.json");
-                let _ = diesel_dot_json
-                    .write(&serde_json::to_vec(&json!({
-                        "parameters": {
-                            "env(DATABASE_URL)": "",
+    fn load_file(
+        &self,
+        root: &Path,
+        path: &Path,
+        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>
+    )
+        -> Result<(), error::Error>
+    {
+        let name = file_configuration_name(root, path)?;
+
+        info!("Configuration file awaiting for initialization: {:?}", file_name(path));
+
+        let configuration = configuration::Configuration::new(path)
+            .strict_env(self.strict_env);
+        configuration.load()?;
+
+        info!("Configuration file initialized: {:?}", file_name(path));
+
+        Self::register_configuration(name, configuration, configurations_to_load)
+    }
+
+    /// Stores a loaded `configuration` under `name`.
+    fn register_configuration(
+        name: String,
+        configuration: configuration::Configuration,
+        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>
+    )
+        -> Result<(), error::Error>
+    {
+        let mut configurations = configurations_to_load.write()?;
+
+        if let Some(_previous_value) = configurations.insert(name.clone(), configuration) {
+            return Err(error::Error::new(
+                error::ErrorKind::Other,
+                format!("a configuration already exists for '{}'", name)
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(debug_assertions)] // If running development mode
+    fn load_development_directory(&self, errors: &mut Vec<error::Error>)
+        -> Result<(), error::Error>
+    {
+        let directory = self.development_directory();
+
+        self.load_directory(&directory, &self.dev_configurations, errors)
+    }
+
+    fn load_production_directory(&self, errors: &mut Vec<error::Error>)
+        -> Result<(), error::Error>
+    {
+        let directory = self.production_directory();
+
+        self.load_directory(&directory, &self.configurations, errors)
+    }
+
+    fn load_profile_directory(&self, errors: &mut Vec<error::Error>)
+        -> Result<(), error::Error>
+    {
+        match self.profile_directory() {
+            Some(directory) => self.load_directory(&directory, &self.profile_configurations, errors),
+            None => Ok(())
+        }
+    }
+
+    /// Registers a production configuration named `name`, parsed from
+    /// `content` instead of being read from a file, e.g. in tests.
+    ///
+    /// `format` is one of the extensions a configuration file could have,
+    /// such as `"json"` or `"yaml"`. The configuration is then returned by
+    /// [`get`] like any other, and is never reloaded. Fails if `content`
+    /// cannot be parsed, or if a configuration named `name` already exists.
+    ///
+    /// [`get`]: #method.get
+    pub fn register(&self, name: &str, format: &str, content: &str)
+        -> result::Result<()>
+    {
+        let configuration = configuration::Configuration::from_str(format, content)?;
+
+        Self::register_configuration(name.to_owned(), configuration, &self.configurations)
+    }
+
+    /// Loads the configurations found in the configuration directories.
+    ///
+    /// By default, loading stops at the first file which fails to load. In
+    /// [`lenient`] mode, every other file is still loaded, and the errors are
+    /// returned together once done, as an error wrapping
+    /// [`error::LoadErrors`].
+    ///
+    /// [`lenient`]: #method.lenient
+    /// [`error::LoadErrors`]: error/struct.LoadErrors.html
+    pub fn load(&self)
+        -> Result<(), error::Error>
+    {
+        let mut errors = Vec::new();
+
+        self.load_production_directory(&mut errors)?;
+        self.load_profile_directory(&mut errors)?;
+
+        // If running development mode
+        #[cfg(debug_assertions)] self.load_development_directory(&mut errors)?;
+
+        Self::aggregate(errors)
+    }
+
+    /// Returns the errors collected while loading leniently, if any.
+    fn aggregate(errors: Vec<error::Error>)
+        -> Result<(), error::Error>
+    {
+        if errors.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(error::Error::new(
+                error::ErrorKind::Other, error::LoadErrors::new(errors)
+            ))
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn load_directory_async(
+        &self,
+        root: &Path,
+        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>,
+        errors: &mut Vec<error::Error>
+    )
+        -> Result<(), error::Error>
+    {
+        let (root, mut files) = self.find_files_async(root, errors).await?;
+        let root = &root;
+
+        files.retain(|path| self.is_file_included(root, path));
+        for path in self.skip_shadowed_files(root, files) {
+            let result = self.load_file_async(root, &path, configurations_to_load).await;
+            self.tolerate(result, errors)?;
+        }
+        Ok(())
+    }
+
+    /// Resolves `root` and finds its configuration files, as [`find_files`]
+    /// does, on a thread where blocking is allowed, so that walking the
+    /// directories does not block the executor.
+    ///
+    /// [`find_files`]: #method.find_files
+    #[cfg(feature = "async")]
+    async fn find_files_async(&self, root: &Path, errors: &mut Vec<error::Error>)
+        -> Result<(PathBuf, Vec<PathBuf>), error::Error>
+    {
+        let factory = self.clone();
+        let root = root.to_path_buf();
+
+        let (root, files, found_errors, result) = tokio::task::spawn_blocking(move || {
+            let root = resolve_directory(&root);
+            let mut files = Vec::new();
+            let mut errors = Vec::new();
+
+            let result = factory.find_files(&root, &mut files, &mut BTreeSet::new(), &mut errors);
+            (root, files, errors, result)
+        }).await.map_err(|err| error::Error::new(error::ErrorKind::Other, err.to_string()))?;
+
+        errors.extend(found_errors);
+        result.map(|_| (root, files))
+    }
+
+    #[cfg(feature = "async")]
+    async fn load_file_async(
+        &self,
+        root: &Path,
+        path: &Path,
+        configurations_to_load: &RwLock<BTreeMap<String, configuration::Configuration>>
+    )
+        -> Result<(), error::Error>
+    {
+        let name = file_configuration_name(root, path)?;
+
+        info!("Configuration file awaiting for initialization: {:?}", file_name(path));
+
+        let configuration = configuration::Configuration::new(path)
+            .strict_env(self.strict_env);
+        configuration.load_async().await?;
+
+        info!("Configuration file initialized: {:?}", file_name(path));
+
+        Self::register_configuration(name, configuration, configurations_to_load)
+    }
+
+    /// Same as [`load`], but reads the configuration files with
+    /// [`Configuration::load_async`], and walks the configuration directories
+    /// with `tokio::task::spawn_blocking`, so that it does not block the
+    /// executor it runs on.
+    ///
+    /// Only available with the `async` feature.
+    ///
+    /// [`load`]: #method.load
+    /// [`Configuration::load_async`]: struct.Configuration.html#method.load_async
+    #[cfg(feature = "async")]
+    pub async fn load_async(&self)
+        -> Result<(), error::Error>
+    {
+        let mut errors = Vec::new();
+
+        let directory = self.production_directory();
+        self.load_directory_async(&directory, &self.configurations, &mut errors).await?;
+
+        if let Some(directory) = self.profile_directory() {
+            self.load_directory_async(&directory, &self.profile_configurations, &mut errors).await?;
+        }
+
+        // If running development mode
+        #[cfg(debug_assertions)]
+        {
+            let directory = self.development_directory();
+            self.load_directory_async(&directory, &self.dev_configurations, &mut errors).await?;
+        }
+
+        Self::aggregate(errors)
+    }
+
+    #[cfg(debug_assertions)]
+    fn get_development(&self, configuration_name: &str)
+        -> result::Result<configuration::Configuration>
+    {
+        let guard = self.dev_configurations.read().unwrap_or_else(PoisonError::into_inner);
+
+        guard.get(configuration_name).ok_or_else(|| error::Error::from(
+            error::ErrorKind::MissingValue
+        )).map(|configuration: &'_ configuration::Configuration|
+            (*configuration).clone()
+        )
+    }
+
+    pub fn get(&self, configuration_name: &str) -> result::Result<configuration::Configuration>
+    {
+        // First, try to get development configuration if compiled in development
+        #[cfg(debug_assertions)]
+        {
+            if let Ok(configuration) = self.get_development(configuration_name) {
+                return match self.get_profile_or_production(configuration_name) {
+                    Ok(ref production) if self.merge => {
+                        Self::merge_configurations(production, &configuration)
+                    },
+                    _ => Ok(configuration),
+                };
+            }
+            // Error is ignored
+        }
+
+        // Then, if not available tries to return production configuration 
+        self.get_profile_or_production(configuration_name)
+    }
+
+    /// Returns the profile configuration named `configuration_name`, over its
+    /// production counterpart when merging, or the production one if there is
+    /// no such profile configuration.
+    fn get_profile_or_production(&self, configuration_name: &str)
+        -> result::Result<configuration::Configuration>
+    {
+        let profile = self.profile_configurations.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(configuration_name)
+            .cloned();
+
+        match profile {
+            Some(configuration) => match self.get_production(configuration_name) {
+                Ok(ref production) if self.merge => {
+                    Self::merge_configurations(production, &configuration)
+                },
+                _ => Ok(configuration),
+            },
+            None => self.get_production(configuration_name)
+        }
+    }
+
+    /// Returns `production` overridden by `development`, or by a profile
+    /// configuration.
+    fn merge_configurations(
+        production: &configuration::Configuration,
+        development: &configuration::Configuration
+    )
+        -> result::Result<configuration::Configuration>
+    {
+        let missing = || error::Error::new(
+            error::ErrorKind::MissingValue, "configuration is not loaded"
+        );
+
+        let mut value = production.pointer("")?.ok_or_else(missing)?;
+        value.merge(&development.pointer("")?.ok_or_else(missing)?);
+
+        Ok(configuration::Configuration::with_value(value))
+    }
+
+    fn get_production(&self, configuration_name: &str)
+        -> result::Result<configuration::Configuration>
+    {
+        let guard = self.configurations.read().unwrap_or_else(PoisonError::into_inner);
+
+        guard.get(configuration_name).ok_or_else(|| error::Error::from(
+            error::ErrorKind::MissingValue
+        )).map(|configuration: &'_ configuration::Configuration|
+            (*configuration).clone()
+        )
+    }
+
+    /// Returns the names of the loaded configurations, sorted.
+    ///
+    /// The names of the profile configurations and, in debug builds, of the
+    /// development configurations are included as well, each name being
+    /// listed once.
+    pub fn names(&self) -> result::Result<Vec<String>>
+    {
+        let mut names = BTreeSet::new();
+
+        names.extend(self.configurations.read().unwrap_or_else(PoisonError::into_inner).keys().cloned());
+        names.extend(self.profile_configurations.read().unwrap_or_else(PoisonError::into_inner).keys().cloned());
+
+        // If running development mode
+        #[cfg(debug_assertions)]
+        names.extend(self.dev_configurations.read().unwrap_or_else(PoisonError::into_inner).keys().cloned());
+
+        Ok(names.into_iter().collect())
+    }
+
+    /// Estimates the bytes used by each loaded configuration, by name, as
+    /// described by [`Value::estimated_size`].
+    ///
+    /// The profile configurations and, in debug builds, the development
+    /// configurations are counted as well: the estimate of a name is the sum
+    /// of those of every configuration kept under it.
+    ///
+    /// [`Value::estimated_size`]: enum.Value.html#method.estimated_size
+    pub fn memory_report(&self) -> BTreeMap<String, usize>
+    {
+        let mut report = BTreeMap::new();
+        let mut add = |configurations: &RwLock<BTreeMap<String, configuration::Configuration>>| {
+            for (name, configuration) in configurations.read().unwrap_or_else(PoisonError::into_inner).iter() {
+                *report.entry(name.clone()).or_insert(0) += configuration.estimated_size();
+            }
+        };
+
+        add(&self.configurations);
+        add(&self.profile_configurations);
+
+        // If running development mode
+        #[cfg(debug_assertions)]
+        add(&self.dev_configurations);
+
+        report
+    }
+
+    /// Watches the configuration directories and reloads a configuration
+    /// whenever its file is written, created or renamed.
+    ///
+    /// File system events are debounced so that a file being written is only
+    /// parsed once the writes are over. The watch runs in a background thread
+    /// until the returned guard is dropped.
+    pub fn watch(&self) -> result::Result<WatchGuard>
+    {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::watcher(
+            sender,
+            Duration::from_millis(constants::WATCH_DEBOUNCE_DELAY_MS)
+        ).map_err(|err| error::Error::new(error::ErrorKind::Other, err))?;
+
+        let mode = if self.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+
+        watcher.watch(self.production_directory(), mode)
+            .map_err(|err| error::Error::new(error::ErrorKind::Other, err))?;
+
+        if let Some(directory) = self.profile_directory().filter(|directory| directory.is_dir()) {
+            watcher.watch(directory, mode)
+                .map_err(|err| error::Error::new(error::ErrorKind::Other, err))?;
+        }
+
+        // If running development mode
+        #[cfg(debug_assertions)]
+        {
+            if self.development_directory().is_dir() {
+                watcher.watch(self.development_directory(), mode)
+                    .map_err(|err| error::Error::new(error::ErrorKind::Other, err))?;
+            }
+        }
+
+        // Directories are resolved now, the current directory may change
+        let production_directory = self.production_directory()
+            .canonicalize()
+            .map_err(|err| error::Error::new(error::ErrorKind::Io, err))?;
+        let development_directory = self.development_directory()
+            .canonicalize()
+            .ok();
+        let profile_directory = self.profile_directory()
+            .and_then(|directory| directory.canonicalize().ok());
+
+        let factory = self.clone();
+        let thread = thread::spawn(move || {
+            for event in receiver {
+                let path = match event {
+                    DebouncedEvent::Create(path)
+                    | DebouncedEvent::Write(path)
+                    | DebouncedEvent::Rename(_, path) => path,
+                    _ => continue,
+                };
+                // Only the parent is canonicalized, the file may have been removed
+                let path = match (path.parent(), path.file_name()) {
+                    (Some(parent), Some(file_name)) => match parent.canonicalize() {
+                        Ok(parent) => parent.join(file_name),
+                        Err(_) => continue,
+                    },
+                    _ => continue,
+                };
+
+                let development = development_directory.as_ref()
+                    .filter(|directory| path.starts_with(directory));
+
+                let (directory, configurations) = match development {
+                    // If running development mode
+                    #[cfg(debug_assertions)]
+                    Some(directory) => (directory, &factory.dev_configurations),
+                    // Development configurations never belong to production
+                    #[cfg(not(debug_assertions))]
+                    Some(_) => continue,
+                    None => match profile_directory.as_ref() {
+                        Some(directory) if path.starts_with(directory) => {
+                            (directory, &factory.profile_configurations)
+                        },
+                        _ if path.starts_with(&production_directory) => {
+                            (&production_directory, &factory.configurations)
+                        },
+                        _ => continue,
+                    },
+                };
+
+                if let Err(err) = factory.reload_file(directory, &path, configurations) {
+                    warn!(
+                        "Configuration file failed to reload: {:?} ({})",
+                        path, err
+                    );
+                }
+            }
+        });
+
+        Ok(WatchGuard { watcher: Some(watcher), thread: Some(thread) })
+    }
+
+    fn reload_file(
+        &self,
+        directory: &Path,
+        path: &Path,
+        configurations_to_reload: &RwLock<BTreeMap<String, configuration::Configuration>>
+    )
+        -> result::Result<()>
+    {
+        if !is_file_handled(path) || !self.is_file_included(directory, path) {
+            return Ok(());
+        }
+
+        // Nested files are only loaded in recursive mode
+        if !self.recursive && path.parent() != Some(directory) {
+            return Ok(());
+        }
+
+        let name = configuration_name(directory, path)
+            .ok_or_else(|| error::Error::new(error::ErrorKind::Other, "invalid file name"))?;
+
+        let mut configurations = configurations_to_reload.write()?;
+
+        match configurations.get(&name) {
+            Some(configuration) => configuration.reload()?,
+            None => {
+                let configuration = configuration::Configuration::new(path)
+                    .strict_env(self.strict_env);
+                configuration.load()?;
+                configurations.insert(name, configuration);
+            }
+        }
+
+        info!(
+            "Configuration file reloaded: {:?}",
+            path.file_name().unwrap_or(
+                std::ffi::OsStr::new("invalid file name")
+            )
+        );
+        Ok(())
+    }
+}
+
+impl Fairing for Factory
+{
+    fn info(&self) -> Info
+    {
+        Info {
+            name: "Configuration factory",
+            kind: Kind::Attach
+        }
+    }
+
+    fn on_attach(&self, rocket: Rocket)
+        -> std::result::Result<Rocket, Rocket>
+    {
+        // Loads available configurations
+        if let Err(err) = self.load() {
+            if self.fail_on_error {
+                error!("Configurations failed to load: {}", err);
+                return Err(rocket);
+            }
+        }
+
+        // Stores himself in the state
+        let rocket = rocket.manage((*self).clone());
+
+        Ok(rocket)
+    } 
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::OpenOptions;
+    use std::io::Result;
+    use std::io::Write as _;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, MutexGuard};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile;
+
+    lazy_static! {
+        // The current directory is shared by every test of the process
+        static ref CWD_LOCK: Mutex<()> = Mutex::new(());
+    }
+
+    fn lock_cwd() -> MutexGuard<'static, ()>
+    {
+        CWD_LOCK.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    lazy_static! {
+        // The logger is shared by every test of the process
+        static ref RECORDS: Mutex<Vec<(log::Level, String)>> = Mutex::new(Vec::new());
+    }
+
+    /// Keeps the records logged by every test, in `RECORDS`.
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger
+    {
+        fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool
+        {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>)
+        {
+            RECORDS.lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger;
+
+    /// Installs the capturing logger, once for the whole process.
+    fn capture_logs()
+    {
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(log::LevelFilter::Trace);
+        }
+    }
+
+    /// Returns the records captured so far which mention `pattern`.
+    fn captured_logs(pattern: &str) -> Vec<(log::Level, String)>
+    {
+        RECORDS.lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .filter(|(_, message)| message.contains(pattern))
+            .cloned()
+            .collect()
+    }
+
+    fn create_temporary_file(prefix: &str, suffix: &str, rand_bytes: usize, dest: &Path)
+        -> Result<tempfile::NamedTempFile>
+    {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .rand_bytes(rand_bytes)
+            .tempfile_in(dest)
+    }
+
+    fn delete_temporary_file(temp_file: tempfile::NamedTempFile)
+    {
+        let _ = temp_file.close();
+    }
+
+    fn create_temporary_directory(prefix: &str, suffix: &str, rand_bytes: usize, dest: &Path)
+        -> Result<tempfile::TempDir>
+    {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .rand_bytes(rand_bytes)
+            .tempdir_in(dest)
+    }
+
+    fn delete_temporary_directory(temp_dir: tempfile::TempDir)
+    {
+        let _ = temp_dir.close();
+    }
+
+    fn cwd(path: &Path) -> PathBuf
+    {
+        let current_dir = env::current_dir()
+            .expect("failed to retrieve current directory");
+
+        env::set_current_dir(path)
+            .expect("failed to change current directory");
+        current_dir
+    }
+
+    #[test]
+    fn is_file_handled()
+    {
+        assert_eq!(super::is_file_handled(Path::new("/unknown-file")), false);
+
+        let file = create_temporary_file("", "", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), false);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".json", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".yml", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".yaml", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".ini", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".properties", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+
+        let file = create_temporary_file("", ".env", 24, &env::temp_dir()).unwrap();
+        assert_eq!(super::is_file_handled(file.path()), true);
+        delete_temporary_file(file);
+    }
+
+    #[test]
+    fn path_matches()
+    {
+        let directory = Path::new("/config");
+        let matches = |pattern: &str, path: &str| super::path_matches(pattern, directory, Path::new(path));
+
+        assert!(matches("*.config.json", "/config/app.config.json"));
+        assert!(matches("*.config.json", "/config/database/diesel.config.json"));
+        assert!(!matches("*.config.json", "/config/fixture.json"));
+        assert!(matches("app.???", "/config/app.env"));
+        assert!(!matches("app.???", "/config/app.json"));
+        assert!(matches("*", "/config/app.json"));
+
+        // Patterns holding a separator
+        assert!(matches("database/*.json", "/config/database/diesel.json"));
+        assert!(!matches("database/*.json", "/config/database/nested/diesel.json"));
+        assert!(!matches("*/diesel.json", "/config/diesel.json"));
+        assert!(matches("**/diesel.json", "/config/diesel.json"));
+        assert!(matches("**/diesel.json", "/config/database/nested/diesel.json"));
+        assert!(matches("database/**", "/config/database/nested/diesel.json"));
+        assert!(!matches("database/**", "/config/diesel.json"));
+        assert!(!matches("**/diesel.json", "/config/database/mydiesel.json"));
+        assert!(matches("**/nested/*.json", "/config/database/nested/diesel.json"));
+        assert!(matches("database/**/diesel.json", "/config/database/diesel.json"));
+        assert!(matches("**.json", "/config/database/diesel.json"));
+        assert!(matches("*a*b*c", "/config/xaxbxxc"));
+        assert!(!matches("*a*b*c", "/config/xaxcxxb"));
+        assert!(matches("d*/*/diesel.json", "/config/database/nested/diesel.json"));
+        assert!(!matches("d*/diesel.json", "/config/database/nested/diesel.json"));
+    }
+
+    #[test]
+    fn glob_matches_in_linear_time()
+    {
+        // Backtracking over every star would take forever
+        let pattern: Vec<char> = "*a".repeat(50).chars().collect();
+        let text: Vec<char> = "a".repeat(100).chars().chain(Some('b')).collect();
+        let start = Instant::now();
+        assert!(!super::glob_matches(&pattern, &text));
+        let pattern: Vec<char> = "**/a".repeat(50).chars().collect();
+        let text: Vec<char> = "a/".repeat(100).chars().chain(Some('b')).collect();
+        assert!(!super::glob_matches(&pattern, &text));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    fn mount_load_env(path: &Path)
+        -> (Vec<tempfile::TempDir>, Vec<tempfile::NamedTempFile>)
+    {
+        let mut directories = Vec::new();
+        let mut files = Vec::new();
+
+        // Create the following directories tree:
+        // .
+        // └── config
+        //     └── dev
+        {
+            directories.push(
+                create_temporary_directory("config", "", 0, path).unwrap()
+            );
+
+            directories.push(
+                create_temporary_directory("dev", "", 0, directories[0].path()).unwrap()
+            );
+        }
+
+        // Create the following final tree:
+        // .
+        // └── config
+        //     └── dieselXXXXXXXX.json          # Valid file
+        //     └── no_extension                 # Invalid file (no ext.)
+        //     └── dev
+        //         └── dieselXXXXXXXX.json      # Valid file
+        //         └── invalid_extension.toto   # Invalid file (unhandled ext.)
+        {
+            // Creates an invalid file in production directory
+            files.push(
+                create_temporary_file("no_extension", "", 16, directories[0].path()).unwrap()
+            );
+
+            // Creates an invalid file in development directory
+            files.push(
+                create_temporary_file("invalid_extension_dev", "toto", 4, directories[1].path()).unwrap()
+            );
+
+            // Creates a valid file in production directory
+            {
+                files.push(
+                    create_temporary_file("diesel", ".json", 0, directories[0].path()).unwrap()
+                );
+
+                let mut diesel_dot_json = OpenOptions::new()
+                    .write(true)
+                    .open(files.last().unwrap().path())
+                    .expect("failed to open diesel.json");
+                let _ = diesel_dot_json
+                    .write(&serde_json::to_vec(&json!({
+                        "parameters": {
+                            "env(DATABASE_URL)": "",
+                            "inital_id": 0,
+                            "limit_id": -1,
+                        },
+                        "diesel": {
+                            "dbal": {
+                                "driver": "mysql",
+                                "server_version": 5.7,
+                                "charset": "utf8",
+                                "default_table_options": {
+                                    "charset": "utf8",
+                                    "collate": "utf8_unicode_ci"
+                                },
+                                "url": "%env(resolve:DATABASE_URL)%"
+                            }
+                        }
+                    }
+                )).expect("failed to serialize example json")[..]);
+            }
+
+            // Creates a valid file in development directory
+            {
+                files.push(
+                    create_temporary_file("diesel", ".json", 0, directories[1].path()).unwrap()
+                );
+
+                let mut diesel_dot_json = OpenOptions::new()
+                    .write(true)
+                    .open(files.last().unwrap().path())
+                    .expect("failed to open diesel.json");
+                let _ = diesel_dot_json
+                    .write(&serde_json::to_vec(&json!({
+                        "parameters": {
+                            "env(DATABASE_URL)": "",
                             "inital_id": 0,
                             "limit_id": -1,
                         },
@@ -364,86 +1475,856 @@ mod tests {
                     }
                 )).expect("failed to serialize example json")[..]);
             }
+        }
+
+        (directories, files)
+    }
+
+    fn unmount_load_env(directories: Vec<tempfile::TempDir>, files: Vec<tempfile::NamedTempFile>)
+    {
+        // Deletes all files
+        for file in files {
+            delete_temporary_file(file);
+        }
+
+        // Deletes all dirs
+        for directory in directories {
+            delete_temporary_directory(directory);
+        }
+    }
+
+    #[test]
+    fn load()
+    {
+        let _cwd_guard = lock_cwd();
+
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+
+        // Creates temporary environment
+        let (directories, files) = mount_load_env(temp_dir.path());
+
+        // Moves to temporary environment
+        let previous_dir = cwd(temp_dir.path());
+
+        // Real logic
+        {
+            let factory = super::Factory::new();
+
+            factory.load().expect("failed to load factory");
+
+            let _config = factory.get("diesel")
+                .expect("failed to get diesel configuration");
+        }
+
+        // Deletes temporary environment
+        unmount_load_env(directories, files);
+
+        // Comes back to initial dir
+        let _ = cwd(&previous_dir);
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn watch()
+    {
+        let _cwd_guard = lock_cwd();
+
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+
+        // Creates temporary environment
+        let (directories, files) = mount_load_env(temp_dir.path());
+
+        // Moves to temporary environment
+        let previous_dir = cwd(temp_dir.path());
+
+        // Real logic
+        {
+            let factory = super::Factory::new();
+
+            factory.load().expect("failed to load factory");
+            let watch = factory.watch().expect("failed to watch configuration directories");
+
+            let driver = factory.get("diesel")
+                .expect("failed to get diesel configuration")
+                .get_path("diesel.dbal.driver")
+                .expect("failed to get diesel.dbal.driver");
+            assert_eq!(driver.as_ref().and_then(|driver| driver.as_str()), Some("mysql"));
+
+            // Rewrites both the production and the development files
+            for file in &files[2..] {
+                let mut diesel_dot_json = OpenOptions::new()
+                    .write(true)
+                    .truncate(true)
+                    .open(file.path())
+                    .expect("failed to open diesel.json");
+                let _ = diesel_dot_json
+                    .write(&serde_json::to_vec(&json!({
+                        "diesel": {
+                            "dbal": {
+                                "driver": "pgsql",
+                            }
+                        }
+                    }
+                )).expect("failed to serialize example json")[..]);
+            }
+
+            // Waits for the debounced reload
+            let start = Instant::now();
+            loop {
+                let driver = factory.get("diesel")
+                    .expect("failed to get diesel configuration")
+                    .get_path("diesel.dbal.driver")
+                    .expect("failed to get diesel.dbal.driver");
+
+                if driver.as_ref().and_then(|driver| driver.as_str()) == Some("pgsql") {
+                    break;
+                }
+                assert!(
+                    start.elapsed() < Duration::from_secs(10),
+                    "configuration was not reloaded"
+                );
+                thread::sleep(Duration::from_millis(100));
+            }
+
+            // Dropping the guard stops the watch
+            drop(watch);
+            for file in &files[2..] {
+                std::fs::write(file.path(), r#"{"diesel": {"dbal": {"driver": "sqlite"}}}"#)
+                    .expect("failed to write diesel.json");
+            }
+            thread::sleep(Duration::from_millis(4 * super::constants::WATCH_DEBOUNCE_DELAY_MS));
+            let driver = factory.get("diesel")
+                .expect("failed to get diesel configuration")
+                .get_path("diesel.dbal.driver")
+                .expect("failed to get diesel.dbal.driver");
+            assert_eq!(driver.as_ref().and_then(|driver| driver.as_str()), Some("pgsql"));
+        }
+
+        // Deletes temporary environment
+        unmount_load_env(directories, files);
+
+        // Comes back to initial dir
+        let _ = cwd(&previous_dir);
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn with_directory()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+
+        // Creates temporary environment
+        let (directories, files) = mount_load_env(temp_dir.path());
+
+        // Real logic, without moving to the temporary environment
+        {
+            let factory = super::Factory::with_directory(directories[0].path());
+
+            assert_eq!(factory.production_directory(), directories[0].path());
+            assert_eq!(factory.development_directory(), directories[1].path());
+
+            factory.load().expect("failed to load factory");
+
+            let _config = factory.get("diesel")
+                .expect("failed to get diesel configuration");
+
+            let factory = super::Factory::with_directory(directories[0].path())
+                .with_dev_directory(temp_dir.path());
+
+            assert_eq!(factory.development_directory(), temp_dir.path());
+        }
+
+        // Deletes temporary environment
+        unmount_load_env(directories, files);
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn default_directories()
+    {
+        let factory = super::Factory::new();
+
+        assert_eq!(
+            factory.production_directory(),
+            Path::new(super::constants::CONFIGURATION_DIRECTORY)
+        );
+        assert_eq!(
+            factory.development_directory(),
+            Path::new(super::constants::DEV_CONFIGURATION_DIRECTORY)
+        );
+    }
+
+    fn write_file(path: &Path, content: &str)
+    {
+        std::fs::create_dir_all(path.parent().expect("failed to get parent directory"))
+            .expect(&format!("failed to create parent of {:?}", path));
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .expect(&format!("failed to open {:?}", path));
+
+        file.write_all(content.as_bytes())
+            .expect(&format!("failed to write {:?}", path));
+    }
+
+    #[test]
+    fn recursive()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(&directory.join("app.json"), r#"{"name": "app"}"#);
+        write_file(&directory.join("database").join("diesel.json"), r#"{"driver": "mysql"}"#);
+        write_file(&directory.join("database").join("replica").join("diesel.yaml"), "driver: sqlite");
+        write_file(&directory.join("cache").join("redis.yaml"), "port: 6379");
+        write_file(&directory.join("dev").join("database").join("diesel.json"), r#"{"driver": "pgsql"}"#);
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory).recursive(true);
+            factory.load().expect("failed to load factory");
+
+            let names = factory.configurations.read()
+                .expect("configurations got poisoned")
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec![
+                "app", "cache.redis", "database.diesel", "database.replica.diesel"
+            ]);
+
+            assert!(factory.get("cache.redis").is_ok());
+            assert!(factory.get("database.replica.diesel").is_ok());
+
+            // If running development mode
+            #[cfg(debug_assertions)]
+            {
+                let driver = factory.get("database.diesel")
+                    .expect("failed to get database.diesel configuration")
+                    .get("driver")
+                    .expect("failed to get driver");
+                assert_eq!(driver, Some(crate::Value::String("pgsql".to_owned())));
+            }
+
+            // Flat mode ignores subdirectories
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+
+            assert!(factory.get("app").is_ok());
+            assert!(factory.get("database.diesel").is_err());
+            assert!(factory.get("cache.redis").is_err());
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn with_merge()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(
+            &directory.join("diesel.yaml"),
+            "dbal:\n  driver: mysql\n  host: localhost\n  options: [a, b]\nname: app"
+        );
+        write_file(
+            &directory.join("dev").join("diesel.json"),
+            r#"{"dbal": {"host": "127.0.0.1", "options": ["c"]}}"#
+        );
+        write_file(&directory.join("redis.json"), r#"{"port": 6379}"#);
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory).with_merge(true);
+            factory.load().expect("failed to load factory");
+
+            let configuration = factory.get("diesel")
+                .expect("failed to get diesel configuration");
+
+            // If running development mode
+            #[cfg(debug_assertions)]
+            assert_eq!(configuration.pointer("").unwrap(), Some(value!({
+                "dbal": {
+                    "driver": "mysql",
+                    "host": "127.0.0.1",
+                    "options": ["c"]
+                },
+                "name": "app"
+            })));
+
+            // The merged configuration has no file of its own
+            #[cfg(debug_assertions)]
+            {
+                assert_eq!(configuration.path().unwrap(), PathBuf::new());
+                configuration.reload().expect("failed to reload the merged configuration");
+                assert_eq!(configuration.get_path("dbal.driver").unwrap(), Some(value!("mysql")));
+                assert_eq!(
+                    configuration.save().expect_err("expected an Err, got a result").kind(),
+                    super::error::ErrorKind::MissingValue
+                );
+            }
+
+            // If not running development mode
+            #[cfg(not(debug_assertions))]
+            assert_eq!(configuration.get_path("dbal.host").unwrap(), Some(value!("localhost")));
+
+            // Production-only configurations are left untouched
+            assert_eq!(
+                factory.get("redis").unwrap().pointer("").unwrap(),
+                Some(value!({ "port": 6379 }))
+            );
+
+            // Without merging, development configurations replace production ones
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+
+            #[cfg(debug_assertions)]
+            assert_eq!(
+                factory.get("diesel").unwrap().pointer("").unwrap(),
+                Some(value!({ "dbal": { "host": "127.0.0.1", "options": ["c"] } }))
+            );
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn with_profile()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
 
-            // Creates a valid file in development directory
-            {
-                files.push(
-                    create_temporary_file("diesel", ".json", 0, directories[1].path()).unwrap()
-                );
+        write_file(&directory.join("diesel.yaml"), "driver: mysql\nhost: localhost");
+        write_file(&directory.join("staging").join("diesel.json"), r#"{"host": "staging-db"}"#);
+        write_file(&directory.join("staging").join("cache.json"), r#"{"port": 6379}"#);
+        write_file(&directory.join("test").join("diesel.json"), r#"{"driver": "sqlite"}"#);
+        write_file(&directory.join("dev").join("app.json"), r#"{"name": "app"}"#);
 
-                let mut diesel_dot_json = OpenOptions::new()
-                    .write(true)
-                    .open(files.last().unwrap().path())
-                    .expect("failed to open diesel.json");
-                let _ = diesel_dot_json
-                    .write(&serde_json::to_vec(&json!({
-                        "parameters": {
-                            "env(DATABASE_URL)": "",
-                            "inital_id": 0,
-                            "limit_id": -1,
-                        },
-                        "diesel": {
-                            "dbal": {
-                                "driver": "mysql",
-                                "server_version": 5.7,
-                                "charset": "utf8",
-                                "default_table_options": {
-                                    "charset": "utf8",
-                                    "collate": "utf8_unicode_ci"
-                                },
-                                "url": "%env(resolve:DATABASE_URL)%"
-                            }
-                        }
-                    }
-                )).expect("failed to serialize example json")[..]);
-            }
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory).with_profile("staging");
+            factory.load().expect("failed to load factory");
+
+            assert_eq!(
+                factory.get("diesel").unwrap().pointer("").unwrap(),
+                Some(value!({ "host": "staging-db" }))
+            );
+            assert!(factory.get("cache").is_ok());
+
+            let factory = super::Factory::with_directory(&directory)
+                .with_profile("staging")
+                .with_merge(true);
+            factory.load().expect("failed to load factory");
+
+            assert_eq!(
+                factory.get("diesel").unwrap().pointer("").unwrap(),
+                Some(value!({ "driver": "mysql", "host": "staging-db" }))
+            );
+
+            let factory = super::Factory::with_directory(&directory)
+                .with_profile("test")
+                .with_merge(true);
+            factory.load().expect("failed to load factory");
+
+            assert_eq!(
+                factory.get("diesel").unwrap().pointer("").unwrap(),
+                Some(value!({ "driver": "sqlite", "host": "localhost" }))
+            );
+            assert!(factory.get("cache").is_err());
+
+            // Without profile, profile directories are only loaded in recursive mode
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+
+            assert_eq!(
+                factory.get("diesel").unwrap().pointer("").unwrap(),
+                Some(value!({ "driver": "mysql", "host": "localhost" }))
+            );
+            assert!(factory.get("cache").is_err());
+
+            // The profile directory is not loaded as part of the production one
+            let factory = super::Factory::with_directory(&directory)
+                .with_profile("staging")
+                .recursive(true);
+            factory.load().expect("failed to load factory");
+
+            let names = factory.names().expect("failed to get names");
+            assert!(names.contains(&"cache".to_owned()));
+            assert!(names.contains(&"test.diesel".to_owned()));
+            assert!(!names.contains(&"staging.diesel".to_owned()));
+
+            let factory = super::Factory::with_directory(&directory).with_profile("production");
+            assert!(factory.load().is_err());
         }
 
-        (directories, files)
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
     }
 
-    fn unmount_load_env(directories: Vec<tempfile::TempDir>, files: Vec<tempfile::NamedTempFile>)
+    #[test]
+    fn with_profile_from_env()
     {
-        // Deletes all files
-        for file in files {
-            delete_temporary_file(file);
+        env::set_var(super::constants::PROFILE_VARIABLE, "staging");
+        let factory = super::Factory::new().with_profile_from_env();
+        assert_eq!(factory.profile, Some("staging".to_owned()));
+        assert_eq!(factory.profile_directory(), Some(Path::new(super::constants::CONFIGURATION_DIRECTORY).join("staging")));
+
+        env::set_var(super::constants::PROFILE_VARIABLE, "");
+        assert_eq!(super::Factory::new().with_profile_from_env().profile, None);
+
+        env::remove_var(super::constants::PROFILE_VARIABLE);
+        assert_eq!(super::Factory::new().with_profile_from_env().profile, None);
+    }
+
+    #[test]
+    fn load_error_path()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+        let file = directory.join("diesel.json");
+
+        write_file(&directory.join("app.json"), r#"{"name": "app"}"#);
+        write_file(&file, r#"{"driver": "#);
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory);
+            let err = factory.load().expect_err("expected an Err, got a result");
+
+            assert_eq!(err.path(), Some(file.as_path()));
+            assert!(
+                err.to_string().starts_with(&format!("failed to load {}: ", file.display())),
+                "unexpected error message: {}", err
+            );
         }
 
-        // Deletes all dirs
-        for directory in directories {
-            delete_temporary_directory(directory);
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn lenient()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+        let file = directory.join("diesel.yaml");
+
+        write_file(&directory.join("app.json"), r#"{"name": "app"}"#);
+        write_file(&directory.join("dev").join("cache.json"), r#"{"port": 6379}"#);
+        write_file(&file, "driver: [mysql");
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory);
+            assert!(factory.load().is_err());
+
+            let factory = super::Factory::with_directory(&directory).lenient(true);
+            let err = factory.load().expect_err("expected an Err, got a result");
+            let errors = err.get_ref()
+                .and_then(|err| err.downcast_ref::<crate::error::LoadErrors>())
+                .expect("expected load errors");
+
+            assert_eq!(errors.errors().len(), 1);
+            assert_eq!(errors.errors()[0].path(), Some(file.as_path()));
+
+            let name = factory.get("app")
+                .expect("failed to get app configuration")
+                .get("name")
+                .expect("failed to get name");
+            assert_eq!(name, Some(crate::Value::String("app".to_owned())));
+            assert!(factory.get("diesel").is_err());
         }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
     }
 
     #[test]
-    fn load()
+    fn strict_env()
     {
+        std::env::remove_var("ROCKET_CONFIG_TEST_STRICT_ENV_UNSET");
+
         // Creates temporary environment
         let temp_dir = tempfile::tempdir().expect(
             &format!("failed to create temp dir in {:?}", env::temp_dir())
         );
+        let directory = temp_dir.path().join("config");
+        let file = directory.join("diesel.json");
+
+        write_file(&file, r#"{"url": "%env(resolve:ROCKET_CONFIG_TEST_STRICT_ENV_UNSET)%"}"#);
+        std::fs::create_dir_all(directory.join("dev")).expect("failed to create dev directory");
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+            assert_eq!(
+                factory.get("diesel").expect("failed to get diesel").get_string("url")
+                    .expect("failed to get url"),
+                Some("%env(resolve:ROCKET_CONFIG_TEST_STRICT_ENV_UNSET)%".to_owned())
+            );
+
+            let factory = super::Factory::with_directory(&directory).strict_env(true);
+            let err = factory.load().expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), super::error::ErrorKind::MissingValue);
+            assert_eq!(err.path(), Some(file.as_path()));
+            assert!(factory.get("diesel").is_err());
+
+            write_file(&file, r#"{"url": "%env(resolve:ROCKET_CONFIG_TEST_STRICT_ENV_UNSET:-sqlite://)%"}"#);
+            factory.load().expect("failed to load factory");
+            assert_eq!(
+                factory.get("diesel").expect("failed to get diesel").get_string("url")
+                    .expect("failed to get url"),
+                Some("sqlite://".to_owned())
+            );
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn logging()
+    {
+        capture_logs();
 
         // Creates temporary environment
-        let (directories, files) = mount_load_env(temp_dir.path());
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
 
-        // Moves to temporary environment
-        let previous_dir = cwd(temp_dir.path());
+        write_file(&directory.join("logging.json"), r#"{"driver": "mysql"}"#);
+        write_file(&directory.join("logging.txt"), "driver: mysql");
+        write_file(&directory.join("dev").join("logging.yaml"), "driver: sqlite");
 
         // Real logic
         {
-            let factory = super::Factory::new();
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+
+            let mut records = captured_logs("\"logging.");
+            records.sort();
+
+            let mut expected = vec![
+                (log::Level::Warn, "Configuration file skipped, its format is not handled: \"logging.txt\"".to_owned()),
+                (log::Level::Info, "Configuration file awaiting for initialization: \"logging.json\"".to_owned()),
+                (log::Level::Info, "Configuration file initialized: \"logging.json\"".to_owned()),
+            ];
+
+            // If running development mode
+            #[cfg(debug_assertions)]
+            expected.extend(vec![
+                (log::Level::Info, "Configuration file awaiting for initialization: \"logging.yaml\"".to_owned()),
+                (log::Level::Info, "Configuration file initialized: \"logging.yaml\"".to_owned()),
+            ]);
+            expected.sort();
+
+            assert_eq!(records, expected);
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn names()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(&directory.join("diesel.json"), r#"{"driver": "mysql"}"#);
+        write_file(&directory.join("app.yaml"), "name: app");
+        write_file(&directory.join("dev").join("diesel.json"), r#"{"driver": "sqlite"}"#);
+        write_file(&directory.join("dev").join("cache.json"), r#"{"port": 6379}"#);
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory);
+            assert_eq!(factory.names().expect("failed to get names"), Vec::<String>::new());
 
             factory.load().expect("failed to load factory");
 
-            let _config = factory.get("diesel")
-                .expect("failed to get diesel configuration");
+            // If running development mode
+            #[cfg(debug_assertions)]
+            assert_eq!(
+                factory.names().expect("failed to get names"),
+                vec!["app", "cache", "diesel"]
+            );
+
+            // If running release mode
+            #[cfg(not(debug_assertions))]
+            assert_eq!(factory.names().expect("failed to get names"), vec!["app", "diesel"]);
         }
 
-        // Deletes temporary environment
-        unmount_load_env(directories, files);
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
 
-        // Comes back to initial dir
-        let _ = cwd(&previous_dir);
+    #[test]
+    fn format_precedence()
+    {
+        capture_logs();
+
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(&directory.join("diesel.json"), r#"{"driver": "mysql"}"#);
+        write_file(&directory.join("diesel.yaml"), "driver: pgsql");
+        write_file(&directory.join("diesel.env"), "driver=sqlite");
+        std::fs::create_dir_all(directory.join("dev")).expect("failed to create dev directory");
+
+        // Real logic
+        {
+            let driver = |factory: &super::Factory| factory.get("diesel")
+                .expect("failed to get diesel")
+                .get_string("driver")
+                .expect("failed to get driver");
+
+            // JSON comes first by default
+            let factory = super::Factory::with_directory(&directory);
+            factory.load().expect("failed to load factory");
+            assert_eq!(driver(&factory), Some("mysql".to_owned()));
+
+            let records = captured_logs("takes precedence");
+            assert!(records.contains(&(
+                log::Level::Warn,
+                "Configuration file skipped, \"diesel.json\" takes precedence: \"diesel.yaml\"".to_owned()
+            )));
+
+            let factory = super::Factory::with_directory(&directory)
+                .with_format_precedence(&["yaml", "json"]);
+            factory.load().expect("failed to load factory");
+            assert_eq!(driver(&factory), Some("pgsql".to_owned()));
+
+            // Formats missing from the order come last, alphabetically
+            let factory = super::Factory::with_directory(&directory)
+                .with_format_precedence(&["ini"]);
+            factory.load().expect("failed to load factory");
+            assert_eq!(driver(&factory), Some("sqlite".to_owned()));
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn patterns()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(&directory.join("app.config.json"), r#"{"name": "app"}"#);
+        write_file(&directory.join("fixture.json"), r#"[{"id": 1}]"#);
+        write_file(&directory.join("schemas").join("diesel.config.json"), r#"{"type": "object"}"#);
+        std::fs::create_dir_all(directory.join("dev")).expect("failed to create dev directory");
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory).with_pattern("*.config.json");
+            factory.load().expect("failed to load factory");
+            assert_eq!(factory.names().expect("failed to get names"), vec!["app.config"]);
+
+            let factory = super::Factory::with_directory(&directory)
+                .recursive(true)
+                .with_pattern("*.config.json")
+                .with_exclude_pattern("schemas/**");
+            factory.load().expect("failed to load factory");
+            assert_eq!(factory.names().expect("failed to get names"), vec!["app.config"]);
+
+            let factory = super::Factory::with_directory(&directory)
+                .recursive(true)
+                .with_exclude_pattern("fixture.json");
+            factory.load().expect("failed to load factory");
+            assert_eq!(
+                factory.names().expect("failed to get names"),
+                vec!["app.config", "schemas.diesel.config"]
+            );
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    fn memory_report()
+    {
+        let factory = super::Factory::with_directory("missing");
+        assert!(factory.memory_report().is_empty());
+
+        factory.register("small", "json", r#"{"servers": ["first.example.com"]}"#)
+            .expect("failed to register small");
+        factory.register(
+            "large",
+            "json",
+            r#"{"servers": ["first.example.com", "second.example.com", "third.example.com"]}"#
+        ).expect("failed to register large");
+
+        let report = factory.memory_report();
+        assert_eq!(report.keys().collect::<Vec<_>>(), vec!["large", "small"]);
+        assert!(report["small"] > 0);
+        assert!(report["small"] < report["large"]);
+    }
+
+    #[test]
+    fn register()
+    {
+        let factory = super::Factory::with_directory("missing");
+
+        factory.register("diesel", "json", r#"{"dbal": {"driver": "mysql"}}"#)
+            .expect("failed to register diesel");
+        factory.register("app", "yaml", "name: app").expect("failed to register app");
+
+        let diesel = factory.get("diesel").expect("failed to get diesel");
+        assert_eq!(diesel.get_string("dbal.driver").unwrap(), Some("mysql".to_owned()));
+        diesel.reload().expect("failed to reload diesel");
+        assert_eq!(diesel.get_string("dbal.driver").unwrap(), Some("mysql".to_owned()));
+        assert_eq!(factory.get("app").unwrap().get_string("name").unwrap(), Some("app".to_owned()));
+        assert_eq!(factory.names().expect("failed to get names"), vec!["app", "diesel"]);
+
+        // Duplicates, invalid content and unknown formats
+        let err = factory.register("diesel", "json", "{}").expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), super::error::ErrorKind::Other);
+        let err = factory.register("broken", "json", "{").expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), super::error::ErrorKind::Parse);
+        let err = factory.register("broken", "xml", "<a/>").expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), super::error::ErrorKind::UnimplementedFormat);
+        assert!(factory.get("broken").is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn load_async()
+    {
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let directory = temp_dir.path().join("config");
+
+        write_file(&directory.join("app.json"), r#"{"name": "app"}"#);
+        write_file(&directory.join("diesel.yaml"), "driver: mysql");
+        write_file(&directory.join("dev").join("diesel.yaml"), "driver: sqlite");
+
+        // Real logic
+        {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .expect("failed to build a tokio runtime");
+            let factory = super::Factory::with_directory(&directory);
+            let loading = factory.clone();
+
+            // The future must be sendable to any executor thread
+            runtime.block_on(async move {
+                tokio::spawn(async move { loading.load_async().await }).await
+            })
+                .expect("failed to join loading task")
+                .expect("failed to load factory");
+
+            let driver = factory.get("diesel")
+                .expect("failed to get diesel configuration")
+                .get_string("driver")
+                .expect("failed to get driver");
+
+            // If running development mode
+            #[cfg(debug_assertions)]
+            assert_eq!(driver, Some("sqlite".to_owned()));
+
+            // If running release mode
+            #[cfg(not(debug_assertions))]
+            assert_eq!(driver, Some("mysql".to_owned()));
+
+            assert_eq!(factory.names().expect("failed to get names"), vec!["app", "diesel"]);
+        }
+
+        // Deletes temp dir
+        delete_temporary_directory(temp_dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks()
+    {
+        use std::os::unix::fs::symlink;
+
+        capture_logs();
+
+        // Creates temporary environment
+        let temp_dir = tempfile::tempdir().expect(
+            &format!("failed to create temp dir in {:?}", env::temp_dir())
+        );
+        let target = temp_dir.path().join("target");
+        let directory = temp_dir.path().join("config");
+
+        write_file(&target.join("symlinks.json"), r#"{"driver": "mysql"}"#);
+        write_file(&target.join("database").join("diesel.yaml"), "driver: mysql");
+        std::fs::create_dir_all(target.join("dev")).expect("failed to create dev directory");
+        symlink(&target, &directory).expect("failed to link config directory");
+        symlink(target.join("missing.json"), target.join("broken-symlinks.json"))
+            .expect("failed to link missing file");
+        symlink(&target, target.join("database").join("loop"))
+            .expect("failed to link parent directory");
+
+        // Real logic
+        {
+            let factory = super::Factory::with_directory(&directory).recursive(true);
+            factory.load().expect("failed to load factory");
+
+            assert_eq!(
+                factory.names().expect("failed to get names"),
+                vec!["database.diesel", "symlinks"]
+            );
+            assert_eq!(captured_logs("\"broken-symlinks.json\""), vec![(
+                log::Level::Warn,
+                "Configuration file skipped, its symbolic link is broken: \"broken-symlinks.json\"".to_owned()
+            )]);
+            assert_eq!(captured_logs("links to one of its parents").len(), 1);
+        }
 
         // Deletes temp dir
         delete_temporary_directory(temp_dir);
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use crate::error;
+use crate::result;
+use crate::value::{Map, Value};
+
+/// Parses INI and Java-style properties content into a `Value`.
+///
+/// The content is read line by line:
+///
+///   * blank lines and lines starting with `;`, `#` or `!` are ignored,
+///   * `[name]` starts a section, whose keys go into the `name` object,
+///   * `key = value` and `key: value` set a key of the current section, or of
+///     the root object before the first section.
+///
+/// Keys and values are trimmed, and values wrapped in double quotes lose
+/// them. Values always are strings, as INI is untyped. A key set twice keeps
+/// its last value.
+pub(crate) fn parse(content: &str) -> result::Result<Value>
+{
+    let mut root = Map::new();
+    let mut section: Option<String> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(&[';', '#', '!'][..]) {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name.strip_suffix(']').ok_or_else(|| format_error(
+                index, "unterminated section header"
+            ))?.trim();
+
+            match root.entry(name.to_owned()).or_insert_with(|| Value::Object(Arc::default())) {
+                Value::Object(_) => section = Some(name.to_owned()),
+                _ => return Err(format_error(
+                    index, &format!("section '{}' conflicts with a key", name)
+                )),
+            }
+            continue;
+        }
+
+        let separator = line.find(&['=', ':'][..]).ok_or_else(|| format_error(
+            index, "expected a key and a value separated by '=' or ':'"
+        ))?;
+        let key = line[..separator].trim();
+        let value = line[separator + 1..].trim();
+        let value = value.strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+
+        if key.is_empty() {
+            return Err(format_error(index, "empty key"));
+        }
+
+        let target = match section {
+            Some(ref name) => match root.get_mut(name) {
+                Some(Value::Object(map)) => Arc::make_mut(map),
+                _ => unreachable!("sections are objects"),
+            },
+            None => &mut root,
+        };
+        target.insert(key.to_owned(), Value::String(value.to_owned()));
+    }
+
+    Ok(Value::Object(Arc::new(root)))
+}
+
+/// Builds the error reported for the malformed line at `index`.
+fn format_error(index: usize, message: &str) -> error::Error
+{
+    error::Error::new(
+        error::ErrorKind::Parse,
+        format!("{} at line {}", message, index + 1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::error;
+    use crate::value::Value;
+
+    #[test]
+    fn sections() {
+        let value = parse("; database settings
+name = my-app
+
+[database]
+# overridden below
+host = localhost
+host = db.example.com
+port: 5432
+password = \"  secret  \"
+
+[ cache ]
+url=redis://localhost:6379/0
+").expect("failed to parse ini");
+
+        assert_eq!(value, Value::from(&json!({
+            "name": "my-app",
+            "database": {
+                "host": "db.example.com",
+                "port": "5432",
+                "password": "  secret  ",
+            },
+            "cache": {
+                "url": "redis://localhost:6379/0",
+            },
+        })));
+    }
+
+    #[test]
+    fn properties() {
+        let value = parse("! Java-style properties
+app.name = my-app
+app.timeout: 30
+empty =
+").expect("failed to parse properties");
+
+        assert_eq!(value, Value::from(&json!({
+            "app.name": "my-app",
+            "app.timeout": "30",
+            "empty": "",
+        })));
+        assert_eq!(parse("").expect("failed to parse ini"), Value::from(&json!({})));
+    }
+
+    #[test]
+    fn malformed() {
+        for (content, message) in &[
+            ("[database\nhost = localhost", "unterminated section header at line 1"),
+            ("name = my-app\nlocalhost", "expected a key and a value separated by '=' or ':' at line 2"),
+            ("= localhost", "empty key at line 1"),
+            ("database = mysql\n[database]", "section 'database' conflicts with a key at line 2"),
+        ] {
+            let err = parse(content).expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), error::ErrorKind::Parse);
+            assert_eq!(err.to_string(), *message);
+        }
+    }
+}
@@ -6,7 +6,9 @@
 //! Rocket-Config is a Rust library providing a plugin for [Rocket] loading and
 //! managing configuration files for [Rocket].
 //!
-//! It allows two configuration file formats: [YAML] and [JSON].
+//! It allows four configuration file formats: [YAML], [JSON], INI (`.ini`
+//! and Java-style `.properties` files) and dotenv (`.env` files), the last two
+//! only holding strings.
 //! Deserialization is done using [serde] and specialized packages [serde_json]
 //! and [serde_yaml].
 //!
@@ -38,6 +40,19 @@
 //! // ...
 //! ```
 //!
+//! ## Features
+//!
+//! The `preserve_order` feature keeps the keys of configuration objects in
+//! the order they appear in the configuration files, instead of sorting them.
+//!
+//! The `arbitrary_precision` feature keeps the digits of JSON numbers, so
+//! that integers beyond the range of u64 and i64, and floats more precise
+//! than f64, are read and written back without being rounded. YAML numbers
+//! are still limited to u64, i64 and f64.
+//!
+//! The `url` feature adds `Value::as_url` and `Configuration::get_url`, which
+//! parse strings into [`url::Url`].
+//!
 //! [Codegen]: ../rocket_config_codegen/index.html
 //! [JSON]: http://json.org
 //! [Rocket]: https://rocket.rs/
@@ -45,6 +60,7 @@
 //! [serde_json]: https://docs.serde.rs/serde_json/
 //! [serde_yaml]: https://docs.serde.rs/serde_yaml/
 //! [YAML]: http://yaml.org
+//! [`url::Url`]: https://docs.rs/url/2/url/struct.Url.html
 
 #![warn(rust_2018_idioms)]
 
@@ -52,17 +68,24 @@
 #[doc(hidden)] pub use rocket_config_codegen::*;
 
 #[macro_use] extern crate lazy_static;
+#[macro_use] extern crate log;
 #[cfg(test)] #[macro_use] extern crate serde_json;
 #[cfg(test)] extern crate tempfile;
 
+#[macro_use] mod macros;
+
 mod configuration;
 mod constants;
+mod dotenv;
 pub mod error;
 mod factory;
+mod ini;
 mod result;
 mod value;
+mod yaml;
 
 pub use configuration::Configuration;
-pub use factory::Factory;
+pub use constants::REDACTED_KEYS;
+pub use factory::{Factory, WatchGuard};
 pub use result::Result;
 pub use value::*;
\ No newline at end of file
@@ -0,0 +1,340 @@
+/// Constructs a [`rocket_config::Value`] from a JSON-like literal.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket_config;
+/// #
+/// # fn main() {
+/// let port = 3306;
+///
+/// let value = value!({
+///     "driver": "mysql",
+///     "server_version": 5.7,
+///     "port": port,
+///     "options": [
+///         "charset=utf8",
+///         null,
+///         true,
+///     ],
+/// });
+/// # }
+/// ```
+///
+/// Any Rust expression whose type implements `Into<Value>` can be
+/// interpolated, both as a value and, when it converts into a `String`, as an
+/// object key. Trailing commas are allowed inside arrays and objects.
+///
+/// [`rocket_config::Value`]: enum.Value.html
+#[macro_export]
+macro_rules! value {
+    // Hide distracting implementation details from the generated rustdoc.
+    ($($value:tt)+) => {
+        $crate::value_internal!($($value)+)
+    };
+}
+
+// Rocket-config's value! macro is a port of serde_json's json! macro, which
+// munches the tokens of arrays and objects one element or entry at a time.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal {
+    //////////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an array [...]. Produces a vec![...]
+    // of the elements.
+    //
+    // Must be invoked as: value_internal!(@array [] $($tt)*)
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done with trailing comma.
+    (@array [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+
+    // Done without trailing comma.
+    (@array [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+
+    // Next element is `null`.
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!(null)] $($rest)*)
+    };
+
+    // Next element is `true`.
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!(true)] $($rest)*)
+    };
+
+    // Next element is `false`.
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!(false)] $($rest)*)
+    };
+
+    // Next element is an array.
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!([$($array)*])] $($rest)*)
+    };
+
+    // Next element is a map.
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!({$($map)*})] $($rest)*)
+    };
+
+    // Next element is an expression followed by comma.
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!($next),] $($rest)*)
+    };
+
+    // Last element is an expression with no trailing comma.
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::value_internal!(@array [$($elems,)* $crate::value_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Unexpected token after most recent element.
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::value_unexpected!($unexpected)
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an object {...}. Each entry is
+    // inserted into the given map variable.
+    //
+    // Must be invoked as: value_internal!(@object $map () ($($tt)*) ($($tt)*))
+    //
+    // We require two copies of the input tokens so that we can match on one
+    // copy and trigger errors on the other copy.
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done.
+    (@object $object:ident () () ()) => {};
+
+    // Insert the current entry followed by trailing comma.
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+        $crate::value_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+
+    // Current entry followed by unexpected token.
+    (@object $object:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::value_unexpected!($unexpected);
+    };
+
+    // Insert the last entry without trailing comma.
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+    };
+
+    // Next value is `null`.
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(null)) $($rest)*);
+    };
+
+    // Next value is `true`.
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(true)) $($rest)*);
+    };
+
+    // Next value is `false`.
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(false)) $($rest)*);
+    };
+
+    // Next value is an array.
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!([$($array)*])) $($rest)*);
+    };
+
+    // Next value is a map.
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!({$($map)*})) $($rest)*);
+    };
+
+    // Next value is an expression followed by comma.
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is an expression with no trailing comma.
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!($value)));
+    };
+
+    // Missing value for last entry. Trigger a reasonable error message.
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::value_internal!();
+    };
+
+    // Missing colon and value for last entry. Trigger a reasonable error
+    // message.
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::value_internal!();
+    };
+
+    // Misplaced colon. Trigger a reasonable error message.
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `:`".
+        $crate::value_unexpected!($colon);
+    };
+
+    // Found a comma inside a key. Trigger a reasonable error message.
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `,`".
+        $crate::value_unexpected!($comma);
+    };
+
+    // Key is fully parenthesized. This avoids clippy double_parens false
+    // positives because the parenthesization may be necessary here.
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // Munch a token into the current key.
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // The main implementation.
+    //
+    // Must be invoked as: value_internal!($($value)+)
+    //////////////////////////////////////////////////////////////////////////
+
+    (null) => {
+        $crate::Value::Null
+    };
+
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+
+    ([]) => {
+        $crate::Value::Array(::std::sync::Arc::new(vec![]))
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Array(::std::sync::Arc::new($crate::value_internal!(@array [] $($tt)+)))
+    };
+
+    ({}) => {
+        $crate::Value::Object(::std::sync::Arc::new($crate::Map::new()))
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Object(::std::sync::Arc::new({
+            let mut object = $crate::Map::new();
+            $crate::value_internal!(@object object () ($($tt)+) ($($tt)+));
+            object
+        }))
+    };
+
+    // Any Into<Value> type.
+    // Must be below every other rule.
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+// Used by the value! macro to trigger "no rules expected the token" errors on
+// unexpected tokens.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_unexpected {
+    () => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::value::{Map, Number, Value};
+
+    #[test]
+    fn literals() {
+        assert_eq!(value!(null), Value::Null);
+        assert_eq!(value!(true), Value::Bool(true));
+        assert_eq!(value!(false), Value::Bool(false));
+        assert_eq!(value!("test"), Value::String("test".to_owned()));
+        assert_eq!(value!(12), Value::Number(Number::from(12)));
+        assert_eq!(value!(-12), Value::Number(Number::from(-12)));
+        assert_eq!(
+            value!(10.12),
+            Value::Number(Number::from_f64(10.12).expect("failed to create number from float"))
+        );
+        assert_eq!(value!([]), Value::Array(Arc::new(vec![])));
+        assert_eq!(value!({}), Value::Object(Arc::new(Map::new())));
+    }
+
+    #[test]
+    fn nested() {
+        let value = value!({
+            "name": "Doe",
+            "firstname": "John",
+            "rooms": ["kitchen", "toilet"],
+            "car": null,
+            "address": {
+                "city": "Paris",
+                "zip": 75001
+            }
+        });
+
+        assert_eq!(value, Value::from(&json!({
+            "name": "Doe",
+            "firstname": "John",
+            "rooms": ["kitchen", "toilet"],
+            "car": null,
+            "address": {
+                "city": "Paris",
+                "zip": 75001
+            }
+        })));
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let value = value!({
+            "rooms": [
+                "kitchen",
+                "toilet",
+            ],
+            "empty": {},
+        });
+
+        assert_eq!(value, Value::from(&json!({
+            "rooms": ["kitchen", "toilet"],
+            "empty": {}
+        })));
+    }
+
+    #[test]
+    fn expressions() {
+        let name = "Doe".to_owned();
+        let age = 37u8;
+        let key = "job";
+        let nested = value!(["kitchen"]);
+
+        let value = value!({
+            "name": name,
+            "age": age,
+            "twice": age as u64 * 2,
+            key: true,
+            "rooms": nested.clone(),
+            "sizes": [1 + 1, -3],
+        });
+
+        assert_eq!(value["name"], Value::String("Doe".to_owned()));
+        assert_eq!(value["age"], Value::Number(Number::from(37u8)));
+        assert_eq!(value["twice"], Value::Number(Number::from(74u64)));
+        assert_eq!(value["job"], Value::Bool(true));
+        assert_eq!(value["rooms"], nested);
+        assert_eq!(value["sizes"], value!([2, -3]));
+    }
+}
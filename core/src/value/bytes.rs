@@ -0,0 +1,119 @@
+/// Decodes base64, with either the standard or the URL-safe alphabet, but
+/// not both in the same input.
+///
+/// The padding is optional, and ASCII whitespace is skipped, so that keys
+/// wrapped over several lines decode as well. The bits left unused by the
+/// last character must be zero, so that each input decodes from a single
+/// encoding. Errors never quote the input, which is usually a secret.
+pub(crate) fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3 + 2);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut length = 0usize;
+    let mut padding = 0;
+    // Whether the URL-safe alphabet is used, once told by a character
+    let mut url_safe = None;
+
+    for (offset, byte) in input.bytes().enumerate() {
+        let sextet = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' | b'/' | b'_' => {
+                let is_url_safe = byte == b'-' || byte == b'_';
+                if *url_safe.get_or_insert(is_url_safe) != is_url_safe {
+                    return Err(format!("mixed alphabets at offset {}", offset));
+                }
+                if byte == b'+' || byte == b'-' { 62 } else { 63 }
+            },
+            b'=' => {
+                padding += 1;
+                continue;
+            },
+            _ if byte.is_ascii_whitespace() => continue,
+            _ => return Err(format!("invalid character at offset {}", offset)),
+        };
+        if padding > 0 {
+            return Err(format!("unexpected data after the padding at offset {}", offset));
+        }
+
+        buffer = buffer << 6 | u32::from(sextet);
+        bits += 6;
+        length += 1;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if length % 4 == 1 {
+        return Err("invalid length".to_owned());
+    }
+    if padding > 0 && (padding > 2 || (length + padding) % 4 != 0) {
+        return Err("invalid padding".to_owned());
+    }
+    if buffer != 0 {
+        return Err("non-zero trailing bits".to_owned());
+    }
+
+    Ok(output)
+}
+
+/// Decodes hexadecimal, in either case.
+pub(crate) fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    if input.len() % 2 != 0 {
+        return Err("odd number of digits".to_owned());
+    }
+
+    input.as_bytes().chunks(2).enumerate().map(|(index, pair)| {
+        let digit = |offset: usize| char::from(pair[offset]).to_digit(16)
+            .ok_or_else(|| format!("invalid digit at offset {}", 2 * index + offset));
+
+        Ok((digit(0)? << 4 | digit(1)?) as u8)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base64, decode_hex};
+
+    #[test]
+    fn base64() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm8=").unwrap(), b"fo");
+        assert_eq!(decode_base64("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode_base64("Zm9vYmFy").unwrap(), b"foobar");
+
+        // Without padding, URL-safe and wrapped
+        assert_eq!(decode_base64("Zg").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+        assert_eq!(decode_base64("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode_base64("+/8=").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode_base64("Zm9v\n  YmFy\n").unwrap(), b"foobar");
+
+        // Invalid input
+        assert_eq!(decode_base64("Zm9v!").unwrap_err(), "invalid character at offset 4");
+        assert_eq!(decode_base64("Zm9vY").unwrap_err(), "invalid length");
+        assert_eq!(decode_base64("Zg=").unwrap_err(), "invalid padding");
+        assert_eq!(decode_base64("Zg===").unwrap_err(), "invalid padding");
+        assert_eq!(decode_base64("Zg==Zg==").unwrap_err(), "unexpected data after the padding at offset 4");
+        assert_eq!(decode_base64("+_8=").unwrap_err(), "mixed alphabets at offset 1");
+        assert_eq!(decode_base64("-/8=").unwrap_err(), "mixed alphabets at offset 1");
+        assert_eq!(decode_base64("Zh==").unwrap_err(), "non-zero trailing bits");
+        assert_eq!(decode_base64("Zm9=").unwrap_err(), "non-zero trailing bits");
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(decode_hex("").unwrap(), b"");
+        assert_eq!(decode_hex("00ff10").unwrap(), [0x00, 0xff, 0x10]);
+        assert_eq!(decode_hex("DEADbeef").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+
+        // Invalid input
+        assert_eq!(decode_hex("abc").unwrap_err(), "odd number of digits");
+        assert_eq!(decode_hex("0g").unwrap_err(), "invalid digit at offset 1");
+        assert_eq!(decode_hex("é00").unwrap_err(), "invalid digit at offset 0");
+    }
+}
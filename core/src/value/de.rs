@@ -0,0 +1,443 @@
+use std::fmt;
+use std::slice;
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
+};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::forward_to_deserialize_any;
+use super::Value;
+use super::map::{self, Map};
+use crate::error;
+
+/// A deserialization error, located by the path of the offending value.
+#[derive(Debug)]
+struct PathError {
+    path: Vec<String>,
+    message: String,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            formatter.write_str(&self.message)
+        } else {
+            write!(formatter, "{} at '{}'", self.message, self.path.join("."))
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Prepends `segment` to the path of a deserialization error.
+fn within(mut err: error::Error, segment: String) -> error::Error {
+    if let Some(path_error) = err.get_mut().and_then(|err| err.downcast_mut::<PathError>()) {
+        path_error.path.insert(0, segment);
+    }
+    err
+}
+
+impl de::Error for error::Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        error::Error::new(error::ErrorKind::DeserializeError, PathError {
+            path: Vec::new(),
+            message: msg.to_string(),
+        })
+    }
+}
+
+impl Value {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match *self {
+            Value::Null => Unexpected::Unit,
+            Value::Bool(b) => Unexpected::Bool(b),
+            Value::Number(ref n) => {
+                if let Some(u) = n.as_u64() {
+                    Unexpected::Unsigned(u)
+                } else if let Some(i) = n.as_i64() {
+                    Unexpected::Signed(i)
+                } else {
+                    Unexpected::Float(n.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(ref s) => Unexpected::Str(s),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Object(_) => Unexpected::Map,
+        }
+    }
+
+    fn invalid_type<E: de::Error>(&self, exp: &dyn de::Expected) -> E {
+        de::Error::invalid_type(self.unexpected(), exp)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &'de Value {
+    type Error = error::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Number(ref n) => {
+                // Integers are preferred, so that integer hints stay exact
+                if let Some(u) = n.as_u64() {
+                    visitor.visit_u64(u)
+                } else if let Some(i) = n.as_i64() {
+                    visitor.visit_i64(i)
+                } else {
+                    visitor.visit_f64(n.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(ref s) => visitor.visit_borrowed_str(s),
+            Value::Array(ref vec) => {
+                let len = vec.len();
+                let mut deserializer = SeqDeserializer::new(vec);
+                let seq = visitor.visit_seq(&mut deserializer)?;
+
+                if deserializer.iter.len() == 0 {
+                    Ok(seq)
+                } else {
+                    Err(de::Error::invalid_length(len, &"fewer elements in array"))
+                }
+            }
+            Value::Object(ref map) => {
+                let len = map.len();
+                let mut deserializer = MapDeserializer::new(map);
+                let map = visitor.visit_map(&mut deserializer)?;
+
+                if deserializer.iter.len() == 0 {
+                    Ok(map)
+                } else {
+                    Err(de::Error::invalid_length(len, &"fewer elements in map"))
+                }
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self {
+            Value::Null => visitor.visit_unit(),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match *self {
+            Value::Object(ref map) => {
+                let mut iter = map.iter();
+                let (variant, value) = match iter.next() {
+                    Some(entry) => entry,
+                    None => {
+                        return Err(de::Error::invalid_value(
+                            Unexpected::Map,
+                            &"map with a single key",
+                        ));
+                    }
+                };
+                // enums are encoded as maps with a single key:value pair
+                if iter.next().is_some() {
+                    return Err(de::Error::invalid_value(
+                        Unexpected::Map,
+                        &"map with a single key",
+                    ));
+                }
+                (variant, Some(value))
+            }
+            Value::String(ref variant) => (variant, None),
+            _ => {
+                return Err(de::Error::invalid_type(self.unexpected(), &"string or map"));
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+            .map_err(|err| within(err, variant.clone()))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqDeserializer<'de> {
+    iter: slice::Iter<'de, Value>,
+    index: usize,
+}
+
+impl<'de> SeqDeserializer<'de> {
+    fn new(vec: &'de [Value]) -> Self {
+        SeqDeserializer { iter: vec.iter(), index: 0 }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = error::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => {
+                let index = self.index;
+                self.index += 1;
+                seed.deserialize(value)
+                    .map(Some)
+                    .map_err(|err| within(err, index.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer<'de> {
+    iter: map::Iter<'de>,
+    value: Option<(&'de String, &'de Value)>,
+}
+
+impl<'de> MapDeserializer<'de> {
+    fn new(map: &'de Map<String, Value>) -> Self {
+        MapDeserializer { iter: map.iter(), value: None }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'de> {
+    type Error = error::Error;
+
+    fn next_key_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(BorrowedStrDeserializer::<Self::Error>::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some((key, value)) => seed.deserialize(value).map_err(|err| within(err, key.clone())),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de str,
+    value: Option<&'de Value>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = error::Error;
+    type Variant = VariantDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Self::Error>::new(self.variant))?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer<'de> {
+    value: Option<&'de Value>,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer<'de> {
+    type Error = error::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            Some(value) => de::Deserialize::deserialize(value),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value @ Value::Array(_)) => de::Deserializer::deserialize_any(value, visitor),
+            Some(value) => Err(value.invalid_type(&"tuple variant")),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value @ Value::Object(_)) => de::Deserializer::deserialize_any(value, visitor),
+            Some(value) => Err(value.invalid_type(&"struct variant")),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"struct variant")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use serde::Deserialize;
+    use crate::error;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Dbal {
+        driver: String,
+        server_version: f64,
+        port: u16,
+        password: Option<String>,
+    }
+
+    #[test]
+    fn deserialize_struct() {
+        let value = value!({
+            "driver": "mysql",
+            "server_version": 5.7,
+            "port": 3306,
+            "password": null
+        });
+
+        assert_eq!(value.deserialize_into::<Dbal>().unwrap(), Dbal {
+            driver: "mysql".to_owned(),
+            server_version: 5.7,
+            port: 3306,
+            password: None,
+        });
+    }
+
+    #[test]
+    fn deserialize_collections() {
+        let value = value!({ "a": [1, -2, 3.5], "b": [] });
+
+        let map: BTreeMap<String, Vec<f64>> = value.deserialize_into().unwrap();
+        assert_eq!(map["a"], vec![1.0, -2.0, 3.5]);
+        assert!(map["b"].is_empty());
+
+        let tuple: (u8, i32, String) = value!([1, -2, "three"]).deserialize_into().unwrap();
+        assert_eq!(tuple, (1, -2, "three".to_owned()));
+    }
+
+    #[test]
+    fn deserialize_enum() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Driver {
+            Sqlite,
+            Mysql { host: String },
+            Pgsql(u16),
+        }
+
+        assert_eq!(value!("Sqlite").deserialize_into::<Driver>().unwrap(), Driver::Sqlite);
+        assert_eq!(
+            value!({ "Mysql": { "host": "localhost" } }).deserialize_into::<Driver>().unwrap(),
+            Driver::Mysql { host: "localhost".to_owned() }
+        );
+        assert_eq!(value!({ "Pgsql": 5432 }).deserialize_into::<Driver>().unwrap(), Driver::Pgsql(5432));
+        assert!(value!({ "Pgsql": 1, "Sqlite": null }).deserialize_into::<Driver>().is_err());
+    }
+
+    #[test]
+    fn type_mismatch() {
+        #[derive(Debug, Deserialize)]
+        struct Diesel {
+            #[allow(dead_code)]
+            servers: Vec<Dbal>,
+        }
+
+        let value = value!({
+            "servers": [
+                { "driver": "mysql", "server_version": 5.7, "port": 3306 },
+                { "driver": "mysql", "server_version": 5.7, "port": "3306" }
+            ]
+        });
+
+        let err = value.deserialize_into::<Diesel>().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::DeserializeError);
+        assert_eq!(
+            err.to_string(),
+            "invalid type: string \"3306\", expected u16 at 'servers.1.port'"
+        );
+
+        let err = value!({ "port": 70000 }).deserialize_into::<BTreeMap<String, u16>>()
+            .expect_err("expected an Err, got a result");
+        assert_eq!(
+            err.to_string(),
+            "invalid value: integer `70000`, expected u16 at 'port'"
+        );
+
+        let err = value!({ "driver": "mysql" }).deserialize_into::<Dbal>()
+            .expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "missing field `server_version`");
+    }
+}
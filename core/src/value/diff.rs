@@ -0,0 +1,169 @@
+use super::Value;
+use super::value::join_path;
+
+/// How arrays found on both sides of a diff are compared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArrayDiffStrategy {
+    /// Arrays are compared as a whole, any difference is reported as a change
+    /// of the whole array.
+    #[default]
+    Atomic,
+    /// Elements sharing the same index are compared recursively, extra
+    /// elements are reported as added or removed.
+    ElementWise,
+}
+
+/// What happened to the value found at the path of a [`DiffEntry`].
+///
+/// [`DiffEntry`]: struct.DiffEntry.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffKind {
+    /// The value only exists on the right-hand side.
+    Added(Value),
+    /// The value only exists on the left-hand side.
+    Removed(Value),
+    /// The value exists on both sides, but differs.
+    Changed {
+        from: Value,
+        to: Value,
+    },
+}
+
+/// A difference between two values, as reported by [`Value::diff`].
+///
+/// [`Value::diff`]: enum.Value.html#method.diff
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffEntry {
+    /// Where the difference is, as a dotted path understood by
+    /// [`Value::get_path`]. It is empty when the compared values themselves
+    /// differ and are not both objects.
+    ///
+    /// [`Value::get_path`]: enum.Value.html#method.get_path
+    pub path: String,
+    /// What the difference is.
+    pub kind: DiffKind,
+}
+
+/// Pushes to `entries` the differences between `left` and `right`, both found
+/// at `path`.
+pub(crate) fn diff(
+    path: &str,
+    left: &Value,
+    right: &Value,
+    arrays: ArrayDiffStrategy,
+    entries: &mut Vec<DiffEntry>,
+) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            for (key, value) in left.iter() {
+                let path = join_path(path, key);
+
+                match right.get(key) {
+                    Some(other) => diff(&path, value, other, arrays, entries),
+                    None => entries.push(DiffEntry { path, kind: DiffKind::Removed(value.clone()) }),
+                }
+            }
+            for (key, value) in right.iter() {
+                if !left.contains_key(key) {
+                    entries.push(DiffEntry {
+                        path: join_path(path, key),
+                        kind: DiffKind::Added(value.clone()),
+                    });
+                }
+            }
+        },
+        (Value::Array(left), Value::Array(right)) if arrays == ArrayDiffStrategy::ElementWise => {
+            for index in 0..left.len().max(right.len()) {
+                let path = join_path(path, &index.to_string());
+
+                match (left.get(index), right.get(index)) {
+                    (Some(value), Some(other)) => diff(&path, value, other, arrays, entries),
+                    (Some(value), None) => entries.push(DiffEntry { path, kind: DiffKind::Removed(value.clone()) }),
+                    (None, Some(other)) => entries.push(DiffEntry { path, kind: DiffKind::Added(other.clone()) }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        },
+        (left, right) if left != right => entries.push(DiffEntry {
+            path: path.to_owned(),
+            kind: DiffKind::Changed { from: left.clone(), to: right.clone() },
+        }),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayDiffStrategy, DiffEntry, DiffKind};
+    use crate::value::Value;
+
+    fn entry(path: &str, kind: DiffKind) -> DiffEntry {
+        DiffEntry { path: path.to_owned(), kind }
+    }
+
+    /// Sorts `entries` by path, as their order follows the one of the keys.
+    fn sorted(mut entries: Vec<DiffEntry>) -> Vec<DiffEntry> {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    #[test]
+    fn identical() {
+        let value = Value::from(&json!({
+            "diesel": {"driver": "mysql", "pool": 10},
+            "servers": ["first", "second"],
+        }));
+
+        assert_eq!(value.diff(&value.clone()), vec![]);
+        assert_eq!(value.diff_with(&value.clone(), ArrayDiffStrategy::ElementWise), vec![]);
+        assert_eq!(Value::Null.diff(&Value::Null), vec![]);
+    }
+
+    #[test]
+    fn nested() {
+        let staging = Value::from(&json!({
+            "diesel": {"driver": "mysql", "pool": "10", "legacy": true},
+            "servers": ["first", "second"],
+            "env.local": {"url": "sqlite://"},
+        }));
+        let production = Value::from(&json!({
+            "diesel": {"driver": "mysql", "pool": 10, "dbal": {"timeout": 30}},
+            "servers": ["first", "third", "fourth"],
+            "env.local": {"url": "mysql://"},
+        }));
+
+        assert_eq!(sorted(staging.diff(&production)), vec![
+            entry("diesel.dbal", DiffKind::Added(Value::from(&json!({"timeout": 30})))),
+            entry("diesel.legacy", DiffKind::Removed(Value::from(true))),
+            entry("diesel.pool", DiffKind::Changed { from: Value::from("10"), to: Value::from(10) }),
+            entry("env\\.local.url", DiffKind::Changed { from: Value::from("sqlite://"), to: Value::from("mysql://") }),
+            entry("servers", DiffKind::Changed {
+                from: Value::from(&json!(["first", "second"])),
+                to: Value::from(&json!(["first", "third", "fourth"])),
+            }),
+        ]);
+
+        let diff = sorted(staging.diff_with(&production, ArrayDiffStrategy::ElementWise));
+        assert_eq!(&diff[4..], &[
+            entry("servers.1", DiffKind::Changed { from: Value::from("second"), to: Value::from("third") }),
+            entry("servers.2", DiffKind::Added(Value::from("fourth"))),
+        ]);
+        assert_eq!(sorted(production.diff_with(&staging, ArrayDiffStrategy::ElementWise)).last(), Some(
+            &entry("servers.2", DiffKind::Removed(Value::from("fourth")))
+        ));
+
+        for each in staging.diff(&production) {
+            assert_eq!(
+                production.get_path(&each.path).is_some(),
+                !matches!(each.kind, DiffKind::Removed(_))
+            );
+        }
+    }
+
+    #[test]
+    fn root() {
+        assert_eq!(Value::from("mysql").diff(&Value::from(&json!({"driver": "mysql"}))), vec![
+            entry("", DiffKind::Changed { from: Value::from("mysql"), to: Value::from(&json!({"driver": "mysql"})) }),
+        ]);
+    }
+}
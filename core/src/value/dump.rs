@@ -0,0 +1,208 @@
+use super::Value;
+
+const INDENT: usize = 2;
+const ELLIPSIS: char = '…';
+
+/// A node waiting to be written: `lead` holds the bullets of the arrays the
+/// node opens, and `key` the key it is found at in its object.
+struct Line<'a> {
+    value: &'a Value,
+    indent: usize,
+    lead: String,
+    key: Option<&'a str>,
+}
+
+/// Renders `value` as indented, YAML-ish text, one leaf per line, with the
+/// keys of every object sorted. Strings longer than `max_length` characters
+/// are truncated and end with an ellipsis.
+///
+/// The rendering keeps its own stack, so that deeply nested values do not
+/// overflow the call stack.
+pub(crate) fn dump(value: &Value, max_length: usize) -> String {
+    let mut output = String::new();
+    let mut stack = vec![Line { value, indent: 0, lead: String::new(), key: None }];
+
+    while let Some(Line { value, indent, lead, key }) = stack.pop() {
+        let column = indent + lead.chars().count();
+        let mut children = Vec::new();
+
+        output.push_str(&" ".repeat(indent));
+        output.push_str(&lead);
+        if let Some(key) = key {
+            output.push_str(&scalar(key, usize::MAX));
+            output.push(':');
+        }
+
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+
+                for (index, (each_key, each)) in entries.into_iter().enumerate() {
+                    // Without a key, the first entry shares the line of the
+                    // bullets, e.g. `- driver: mysql`
+                    let line = match (key, index) {
+                        (Some(_), _) => Line { value: each, indent: column + INDENT, lead: String::new(), key: Some(each_key) },
+                        (None, 0) => Line { value: each, indent, lead: lead.clone(), key: Some(each_key) },
+                        (None, _) => Line { value: each, indent: column, lead: String::new(), key: Some(each_key) },
+                    };
+                    children.push(line);
+                }
+            },
+            Value::Array(list) if !list.is_empty() => {
+                for (index, each) in list.iter().enumerate() {
+                    let line = match (key, index) {
+                        (Some(_), _) => Line { value: each, indent: column + INDENT, lead: "- ".to_owned(), key: None },
+                        (None, 0) => Line { value: each, indent, lead: format!("{}- ", lead), key: None },
+                        (None, _) => Line { value: each, indent: column, lead: "- ".to_owned(), key: None },
+                    };
+                    children.push(line);
+                }
+            },
+            leaf => {
+                if key.is_some() {
+                    output.push(' ');
+                }
+                match leaf {
+                    Value::String(string) => output.push_str(&scalar(string, max_length)),
+                    leaf => output.push_str(&leaf.to_string()),
+                }
+            },
+        }
+
+        // The first child of a keyless container continues the current line
+        let continues = key.is_none() && !children.is_empty();
+        if continues {
+            output.truncate(output.len() - lead.len() - indent);
+        }
+        else {
+            output.push('\n');
+        }
+
+        // Children are pushed in reverse so that they are popped in order
+        stack.extend(children.into_iter().rev());
+    }
+
+    output
+}
+
+/// Truncates `string` to `max_length` characters, and quotes it when it would
+/// not read as a plain string.
+fn scalar(string: &str, max_length: usize) -> String {
+    let string = match string.char_indices().nth(max_length) {
+        Some((end, _)) => format!("{}{}", &string[..end], ELLIPSIS),
+        None => string.to_owned(),
+    };
+
+    if needs_quotes(&string) {
+        Value::String(string).to_string()
+    }
+    else {
+        string
+    }
+}
+
+/// Tells whether `string` would be mistaken for another kind of scalar, or
+/// for the syntax around it.
+fn needs_quotes(string: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`',
+    ];
+
+    string.is_empty()
+        || string.trim() != string
+        || string.starts_with(INDICATORS)
+        || string.contains(": ")
+        || string.contains(" #")
+        || string.ends_with(':')
+        || string.chars().any(char::is_control)
+        || ["null", "~", "true", "false", "yes", "no", "on", "off"].contains(&string.to_lowercase().as_str())
+        || string.parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{Map, Value};
+
+    #[test]
+    fn dump() {
+        let diesel = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "driver": "mysql",
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": ["mysql://first", {"url": "mysql://second", "weight": 2}],
+                },
+                "port": 3306,
+                "ratio": 0.5,
+                "debug": false,
+                "password": null,
+                "pool": {},
+                "options": [],
+                "names": [["a", "b"], []],
+            },
+            "comment": "yes",
+            "empty": "",
+            "version": "1.0",
+        }));
+
+        assert_eq!(diesel.dump(), "\
+comment: \"yes\"
+diesel:
+  dbal:
+    driver: mysql
+    replicas:
+      - mysql://first
+      - url: mysql://second
+        weight: 2
+    url: \"%env(resolve:DATABASE_URL)%\"
+  debug: false
+  names:
+    - - a
+      - b
+    - []
+  options: []
+  password: null
+  pool: {}
+  port: 3306
+  ratio: 0.5
+empty: \"\"
+version: \"1.0\"
+");
+
+        assert_eq!(Value::from("mysql").dump(), "mysql\n");
+        assert_eq!(Value::Null.dump(), "null\n");
+        assert_eq!(Value::from(Vec::<Value>::new()).dump(), "[]\n");
+        assert_eq!(Value::from(&json!([{"a": 1, "b": [2]}, 3])).dump(), "\
+- a: 1
+  b:
+    - 2
+- 3
+");
+    }
+
+    #[test]
+    fn dump_with_max_length() {
+        let value = Value::from(&json!({"url": "mysql://localhost", "key": "été"}));
+
+        assert_eq!(value.dump_with_max_length(5), "key: été\nurl: mysql…\n");
+        assert_eq!(value.dump_with_max_length(2), "key: ét…\nurl: my…\n");
+        assert_eq!(value.dump_with_max_length(0), "key: …\nurl: …\n");
+    }
+
+    #[test]
+    fn dump_deep() {
+        const DEPTH: usize = 1_000;
+
+        let mut value = Value::from("leaf");
+        for _ in 0..DEPTH {
+            let mut map = Map::new();
+            map.insert("a".to_owned(), value);
+            value = Value::from(map);
+        }
+
+        let dumped = value.dump();
+        assert_eq!(dumped.lines().count(), DEPTH);
+        assert_eq!(dumped.lines().last(), Some(&*format!("{}a: leaf", " ".repeat(2 * (DEPTH - 1)))));
+    }
+}
@@ -0,0 +1,119 @@
+use super::Value;
+use super::map;
+
+/// A view into a single entry of a `Value::Object`, which may either be
+/// vacant or occupied.
+///
+/// This struct is constructed from the [`entry`] and [`try_entry`] methods of
+/// `Value`.
+///
+/// [`entry`]: enum.Value.html#method.entry
+/// [`try_entry`]: enum.Value.html#method.try_entry
+pub struct Entry<'a> {
+    inner: map::Entry<'a>,
+}
+
+impl<'a> Entry<'a> {
+    pub(crate) fn new(inner: map::Entry<'a>) -> Self {
+        Entry { inner }
+    }
+
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &String {
+        self.inner.key()
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty, and
+    /// returns a mutable reference to the value in the entry.
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.inner.or_insert(default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` if
+    /// empty, and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut Value
+    where
+        F: FnOnce() -> Value,
+    {
+        self.inner.or_insert_with(default)
+    }
+
+    /// Ensures a value is in the entry by inserting `Value::Null` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut Value {
+        self.inner.or_default()
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the object.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut Value),
+    {
+        Entry { inner: self.inner.and_modify(f) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error;
+    use crate::value::Value;
+
+    #[test]
+    fn insert_into_empty_object() {
+        let mut value = value!({});
+
+        value.entry("diesel")
+            .or_insert(value!({}))
+            .entry("driver")
+            .or_insert_with(|| Value::from("mysql"));
+        *value.entry("port").or_default() = Value::from(3306);
+
+        assert_eq!(value, value!({
+            "diesel": { "driver": "mysql" },
+            "port": 3306
+        }));
+    }
+
+    #[test]
+    fn modify_existing_keys() {
+        let mut value = value!({ "port": 3306, "driver": "mysql" });
+
+        value.entry("port")
+            .and_modify(|port| *port = Value::from(port.as_u64().unwrap() + 1))
+            .or_insert(Value::from(0));
+        value.entry("driver").or_insert(Value::from("pgsql"));
+        value.entry(String::from("retries"))
+            .and_modify(|_| panic!("retries is not set"))
+            .or_insert(Value::from(3));
+
+        assert_eq!(value.entry("port").key(), "port");
+        assert_eq!(value, value!({ "port": 3307, "driver": "mysql", "retries": 3 }));
+    }
+
+    #[test]
+    fn entry_on_null() {
+        let mut value = Value::Null;
+
+        value.entry("key").or_insert(Value::from(true));
+        assert_eq!(value, value!({ "key": true }));
+    }
+
+    #[test]
+    fn try_entry() {
+        let mut value = value!({});
+        assert!(value.try_entry("key").is_ok());
+
+        let mut value = value!([1, 2]);
+        let err = value.try_entry("key").err().expect("expected an Err, got an entry");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access key \"key\" in JSON number")]
+    fn entry_on_number() {
+        let mut value = Value::from(12);
+
+        value.entry("key");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error;
+use crate::result;
+
+const PLACEHOLDER_START: &str = "%env(";
+const PLACEHOLDER_END: &str = ")%";
+const RESOLVE_PREFIX: &str = "resolve:";
+const DEFAULT_SEPARATOR: &str = ":-";
+
+/// Replaces every environment placeholder found in `input`.
+///
+/// The following placeholders are recognized:
+///
+///   * `%env(NAME)%` is replaced by the value of the `NAME` variable,
+///   * `%env(resolve:NAME)%` behaves the same way, but is mandatory when
+///     `strict` is set,
+///   * `%env(NAME:-fallback)%` is replaced by `fallback` when `NAME` is unset.
+///
+/// Placeholders referring to unset variables without fallback are left
+/// untouched, unless `strict` is set and the placeholder uses the `resolve:`
+/// form, in which case an error of kind `MissingValue` is returned.
+pub(crate) fn interpolate(input: &str, strict: bool) -> result::Result<String>
+{
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find(PLACEHOLDER_START) {
+        let after_start = &rest[start + PLACEHOLDER_START.len()..];
+        let end = match after_start.find(PLACEHOLDER_END) {
+            Some(end) => end,
+            None => break,
+        };
+
+        output.push_str(&rest[..start]);
+
+        let inner = &after_start[..end];
+        let (resolve, inner) = match inner.strip_prefix(RESOLVE_PREFIX) {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+        let (name, default) = match inner.find(DEFAULT_SEPARATOR) {
+            Some(separator) => (
+                &inner[..separator],
+                Some(&inner[separator + DEFAULT_SEPARATOR.len()..])
+            ),
+            None => (inner, None),
+        };
+
+        match (std::env::var(name), default) {
+            (Ok(value), _) => output.push_str(&value),
+            (Err(_), Some(default)) => output.push_str(default),
+            (Err(_), None) if strict && resolve => {
+                return Err(error::Error::new(
+                    error::ErrorKind::MissingValue,
+                    format!("environment variable '{}' is not set", name)
+                ));
+            },
+            (Err(_), None) => {
+                output.push_str(&rest[start..start + PLACEHOLDER_START.len() + end + PLACEHOLDER_END.len()]);
+            },
+        }
+
+        rest = &after_start[end + PLACEHOLDER_END.len()..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::interpolate;
+    use crate::error;
+
+    #[test]
+    fn set_variable() {
+        env::set_var("ROCKET_CONFIG_TEST_ENV_SET", "mysql://localhost");
+
+        assert_eq!(
+            interpolate("%env(ROCKET_CONFIG_TEST_ENV_SET)%", false).unwrap(),
+            "mysql://localhost"
+        );
+        assert_eq!(
+            interpolate("%env(resolve:ROCKET_CONFIG_TEST_ENV_SET)%", true).unwrap(),
+            "mysql://localhost"
+        );
+        assert_eq!(
+            interpolate("url=%env(ROCKET_CONFIG_TEST_ENV_SET:-none)%/db", false).unwrap(),
+            "url=mysql://localhost/db"
+        );
+    }
+
+    #[test]
+    fn unset_variable() {
+        env::remove_var("ROCKET_CONFIG_TEST_ENV_UNSET");
+
+        assert_eq!(
+            interpolate("%env(ROCKET_CONFIG_TEST_ENV_UNSET)%", true).unwrap(),
+            "%env(ROCKET_CONFIG_TEST_ENV_UNSET)%"
+        );
+        assert_eq!(
+            interpolate("%env(resolve:ROCKET_CONFIG_TEST_ENV_UNSET)%", false).unwrap(),
+            "%env(resolve:ROCKET_CONFIG_TEST_ENV_UNSET)%"
+        );
+        assert_eq!(
+            interpolate("%env(ROCKET_CONFIG_TEST_ENV_UNSET:-sqlite://)%", true).unwrap(),
+            "sqlite://"
+        );
+
+        let err = interpolate("%env(resolve:ROCKET_CONFIG_TEST_ENV_UNSET)%", true)
+            .expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::MissingValue);
+    }
+
+    #[test]
+    fn no_placeholder() {
+        assert_eq!(interpolate("plain text", true).unwrap(), "plain text");
+        assert_eq!(interpolate("%env(UNTERMINATED", true).unwrap(), "%env(UNTERMINATED");
+        assert_eq!(interpolate("env(DATABASE_URL)", true).unwrap(), "env(DATABASE_URL)");
+    }
+}
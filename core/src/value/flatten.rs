@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::map::{self, Map};
+use super::Value;
+use super::value::{join_path_with, split_path_with, unshare};
+use crate::error;
+use crate::result;
+
+/// Controls how [`Value::to_string_map_with`] flattens a value into strings.
+///
+/// The default options join the keys with dots, as understood by
+/// [`Value::get_path`], and skip the null leaves.
+///
+/// [`Value::get_path`]: enum.Value.html#method.get_path
+/// [`Value::to_string_map_with`]: enum.Value.html#method.to_string_map_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StringMapOptions {
+    /// The separator used to join the keys, ignored when
+    /// `uppercase_env_style` is set.
+    pub separator: char,
+    /// Whether null leaves give an empty string instead of being skipped.
+    pub null_as_empty: bool,
+    /// Whether the keys are written the way environment variables are:
+    /// uppercased, joined with underscores, and with every character which
+    /// is not alphanumeric replaced by an underscore.
+    ///
+    /// Distinct paths may then be written the same way, such as `a.b` and
+    /// `a_b`, which both give `A_B`: flattening fails instead of keeping only
+    /// one of their leaves.
+    pub uppercase_env_style: bool,
+}
+
+impl Default for StringMapOptions {
+    fn default() -> Self {
+        Self {
+            separator: '.',
+            null_as_empty: false,
+            uppercase_env_style: false,
+        }
+    }
+}
+
+/// Flattens `value` into a map of keys to stringified leaves, according to
+/// `options`.
+///
+/// Fails with an error of kind `FormatError` when two leaves are given the
+/// same key, which only happens with `uppercase_env_style`.
+pub(crate) fn to_string_map(
+    value: &Value,
+    options: StringMapOptions,
+) -> result::Result<BTreeMap<String, String>> {
+    let separator = if options.uppercase_env_style { '.' } else { options.separator };
+    let mut flattened = BTreeMap::new();
+    let mut map = BTreeMap::new();
+    // The path each key was written from, to tell which paths collide
+    let mut paths: BTreeMap<String, String> = BTreeMap::new();
+
+    flatten("", value, separator, &mut flattened);
+
+    for (path, leaf) in flattened {
+        let leaf = match leaf {
+            Value::Null if options.null_as_empty => String::new(),
+            Value::Null => continue,
+            Value::String(string) => string,
+            leaf => leaf.to_string(),
+        };
+        let key = if options.uppercase_env_style {
+            split_path_with(&path, separator).iter()
+                .map(|segment| segment.chars().map(|character| {
+                    if character.is_ascii_alphanumeric() {
+                        character.to_ascii_uppercase()
+                    }
+                    else {
+                        '_'
+                    }
+                }).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("_")
+        }
+        else {
+            path.clone()
+        };
+
+        if let Some(previous) = paths.insert(key.clone(), path.clone()) {
+            return Err(error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("'{}' and '{}' are both written as '{}'", previous, path, key)
+            ));
+        }
+        map.insert(key, leaf);
+    }
+
+    Ok(map)
+}
+
+/// Inserts in `flattened` the leaves of `value`, found at `path`.
+///
+/// The traversal keeps its own stack, so that deeply nested values do not
+/// overflow the call stack.
+pub(crate) fn flatten(
+    path: &str,
+    value: &Value,
+    separator: char,
+    flattened: &mut BTreeMap<String, Value>,
+) {
+    let mut stack = vec![(path.to_owned(), value)];
+
+    while let Some((path, node)) = stack.pop() {
+        match node {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, each) in map.iter() {
+                    stack.push((join_path_with(&path, key, separator), each));
+                }
+            },
+            Value::Array(list) if !list.is_empty() => {
+                for (index, each) in list.iter().enumerate() {
+                    stack.push((join_path_with(&path, &index.to_string(), separator), each));
+                }
+            },
+            _ => {
+                flattened.insert(path, node.clone());
+            },
+        }
+    }
+}
+
+/// Rebuilds the value whose leaves are in `flattened`.
+pub(crate) fn unflatten(flattened: &BTreeMap<String, Value>, separator: char) -> Value {
+    let mut root = Value::Null;
+
+    for (path, value) in flattened {
+        if path.is_empty() {
+            root = value.clone();
+            continue;
+        }
+
+        let target = split_path_with(path, separator).into_iter().fold(&mut root, |target, segment| {
+            if !target.is_object() {
+                *target = Value::Object(Arc::default());
+            }
+            match target {
+                Value::Object(map) => Arc::make_mut(map).entry(segment).or_insert(Value::Null),
+                _ => unreachable!(),
+            }
+        });
+        // A leaf never replaces the nested values set through a longer key
+        if !target.is_object() {
+            *target = value.clone();
+        }
+    }
+
+    into_arrays(root)
+}
+
+/// Turns the objects of `value` whose keys are the indices `0` to `n - 1`
+/// into arrays, recursively.
+fn into_arrays(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let is_array = !map.is_empty() && (0..map.len()).all(|index| map.contains_key(&index.to_string()));
+            let mut map: Map<String, Value> = unshare(map).into_iter()
+                .map(|(key, each)| (key, into_arrays(each)))
+                .collect();
+
+            if is_array {
+                Value::Array(Arc::new((0..map.len()).map(|index| {
+                    map::remove(&mut map, &index.to_string()).unwrap_or(Value::Null)
+                }).collect()))
+            }
+            else {
+                Value::Object(Arc::new(map))
+            }
+        },
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use crate::error;
+    use crate::value::{Map, StringMapOptions, Value};
+
+    #[test]
+    fn flatten() {
+        let diesel = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": ["mysql://first", {"url": "mysql://second"}],
+                },
+                "port": 3306,
+                "pool": {},
+                "options": [],
+            },
+            "a/b": 1,
+            "env.local": {"back\\slash": null},
+        }));
+
+        let flattened = diesel.flatten();
+
+        assert_eq!(flattened.into_iter().collect::<Vec<_>>(), vec![
+            ("a/b".to_owned(), Value::from(1)),
+            ("diesel.dbal.replicas.0".to_owned(), Value::from("mysql://first")),
+            ("diesel.dbal.replicas.1.url".to_owned(), Value::from("mysql://second")),
+            ("diesel.dbal.url".to_owned(), Value::from("%env(resolve:DATABASE_URL)%")),
+            ("diesel.options".to_owned(), Value::from(Vec::<Value>::new())),
+            ("diesel.pool".to_owned(), Value::from(&json!({}))),
+            ("diesel.port".to_owned(), Value::from(3306)),
+            ("env\\.local.back\\\\slash".to_owned(), Value::Null),
+        ]);
+
+        for (path, value) in diesel.flatten() {
+            assert_eq!(diesel.get_path(&path), Some(&value));
+        }
+
+        let flattened = diesel.flatten_with_separator('/');
+        assert_eq!(flattened.get("a\\/b"), Some(&Value::from(1)));
+        assert_eq!(flattened.get("diesel/dbal/replicas/1/url"), Some(&Value::from("mysql://second")));
+
+        assert_eq!(Value::from(true).flatten().into_iter().collect::<Vec<_>>(), vec![
+            (String::new(), Value::from(true)),
+        ]);
+    }
+
+    #[test]
+    fn unflatten() {
+        let diesel = Value::from(&json!({
+            "diesel": {
+                "dbal": {"replicas": ["mysql://first", {"url": "mysql://second"}]},
+                "pool": {},
+                "options": [],
+            },
+            "a/b": 1,
+            "env.local": {"back\\slash": null},
+        }));
+
+        assert_eq!(Value::unflatten(&diesel.flatten()), diesel);
+        assert_eq!(Value::unflatten_with_separator(&diesel.flatten_with_separator('/'), '/'), diesel);
+        assert_eq!(Value::unflatten_with_separator(&diesel.flatten_with_separator('_'), '_'), diesel);
+        assert_eq!(Value::unflatten(&Value::from("scalar").flatten()), Value::from("scalar"));
+        assert_eq!(Value::unflatten(&BTreeMap::new()), Value::Null);
+
+        let mut flattened = BTreeMap::new();
+        flattened.insert("rooms.1".to_owned(), Value::from("toilet"));
+        flattened.insert("rooms.0".to_owned(), Value::from("kitchen"));
+        flattened.insert("floors.1".to_owned(), Value::from(1));
+        flattened.insert("a".to_owned(), Value::from(1));
+        flattened.insert("a.b".to_owned(), Value::from(2));
+
+        assert_eq!(Value::unflatten(&flattened), Value::from(&json!({
+            "rooms": ["kitchen", "toilet"],
+            "floors": {"1": 1},
+            "a": {"b": 2},
+        })));
+    }
+
+    #[test]
+    fn to_string_map() {
+        let diesel = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": ["mysql://first", {"url": "mysql://second"}],
+                },
+                "port": 3306,
+                "pool": {},
+                "options": [],
+            },
+            "a/b": 1,
+            "env.local": {"back\\slash": null},
+        }));
+
+        let map = diesel.to_string_map();
+
+        assert_eq!(map.get("diesel.dbal.replicas.0").map(String::as_str), Some("mysql://first"));
+        assert_eq!(map.get("diesel.port").map(String::as_str), Some("3306"));
+        assert_eq!(map.get("diesel.options").map(String::as_str), Some("[]"));
+        assert_eq!(map.get("env\\.local.back\\\\slash"), None);
+
+        let map = diesel.to_string_map_with(StringMapOptions {
+            separator: '/',
+            null_as_empty: true,
+            ..StringMapOptions::default()
+        }).unwrap();
+
+        assert_eq!(map.get("diesel/dbal/replicas/1/url").map(String::as_str), Some("mysql://second"));
+        assert_eq!(map.get("env.local/back\\\\slash").map(String::as_str), Some(""));
+
+        let map = diesel.to_string_map_with(StringMapOptions {
+            uppercase_env_style: true,
+            ..StringMapOptions::default()
+        }).unwrap();
+
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![
+            ("A_B".to_owned(), "1".to_owned()),
+            ("DIESEL_DBAL_REPLICAS_0".to_owned(), "mysql://first".to_owned()),
+            ("DIESEL_DBAL_REPLICAS_1_URL".to_owned(), "mysql://second".to_owned()),
+            ("DIESEL_DBAL_URL".to_owned(), "%env(resolve:DATABASE_URL)%".to_owned()),
+            ("DIESEL_OPTIONS".to_owned(), "[]".to_owned()),
+            ("DIESEL_POOL".to_owned(), "{}".to_owned()),
+            ("DIESEL_PORT".to_owned(), "3306".to_owned()),
+        ]);
+
+        let err = Value::from(&json!({"a.b": 1, "a_b": 2}))
+            .to_string_map_with(StringMapOptions {
+                uppercase_env_style: true,
+                ..StringMapOptions::default()
+            })
+            .expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(err.to_string(), "'a\\.b' and 'a_b' are both written as 'A_B'");
+
+        let map = Value::from(&json!({"debug": true, "ratio": 0.5})).to_string_map();
+        assert_eq!(map.get("debug").map(String::as_str), Some("true"));
+        assert_eq!(map.get("ratio").map(String::as_str), Some("0.5"));
+    }
+    #[test]
+    fn deeply_nested() {
+        const DEPTH: usize = 10_000;
+
+        let mut value = Value::from("leaf");
+        for _ in 0..DEPTH {
+            let mut map = Map::new();
+            map.insert("a".to_owned(), value);
+            value = Value::from(map);
+        }
+
+        let flattened = value.flatten();
+        assert_eq!(flattened.len(), 1);
+        assert_eq!(flattened.get(&vec!["a"; DEPTH].join(".")), Some(&Value::from("leaf")));
+
+        // Dropping the value at once would recurse as deep as it is nested
+        while let Value::Object(mut map) = value {
+            value = Arc::make_mut(&mut map).remove("a").unwrap_or(Value::Null);
+        }
+    }
+}
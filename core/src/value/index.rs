@@ -1,15 +1,25 @@
-use std::collections::BTreeMap;
+use std::borrow::Cow;
 use std::fmt;
 use std::ops;
+use std::sync::Arc;
 
+use super::map;
 use super::Value;
 
 /// A type that can be used to index into a `rocket_config::Value`.
 ///
 /// The [`get`] and [`get_mut`] methods of `Value` accept any type that
 /// implements `Index`, as does the [square-bracket indexing operator]. This
-/// trait is implemented for strings which are used as the index into a JSON
-/// map, and for `usize` which is used as the index into a JSON array.
+/// trait is implemented for strings (`str`, `String` and `Cow<str>`) which
+/// are used as the index into a JSON map, for `usize` which is used as the
+/// index into a JSON array, and for slices and arrays of keys (`&str` or
+/// `String`) which walk through nested values, one key per level. Such a
+/// key indexes an array when it is a number, like `"0"`, and an empty slice
+/// gives the value itself.
+///
+/// Looking a key up in anything but an object, or an index in anything but
+/// an array, returns None, as does a slice of keys as soon as one of its
+/// levels is missing or cannot be indexed by its key.
 ///
 /// [`get`]: ../enum.Value.html#method.get
 /// [`get_mut`]: ../enum.Value.html#method.get_mut
@@ -32,6 +42,15 @@ pub trait Index: private::Sealed {
     /// object.
     #[doc(hidden)]
     fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value;
+
+    /// Same as `index_or_insert`, but return None instead of panicking.
+    #[doc(hidden)]
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value>;
+
+    /// Remove the element from the array, or the entry from the object, and
+    /// return its value. Return None if the key is not in the array or object.
+    #[doc(hidden)]
+    fn remove_from(&self, v: &mut Value) -> Option<Value>;
 }
 
 impl Index for usize {
@@ -43,7 +62,7 @@ impl Index for usize {
     }
     fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
         match *v {
-            Value::Array(ref mut vec) => vec.get_mut(*self),
+            Value::Array(ref mut vec) => Arc::make_mut(vec).get_mut(*self),
             _ => None,
         }
     }
@@ -51,7 +70,7 @@ impl Index for usize {
         match *v {
             Value::Array(ref mut vec) => {
                 let len = vec.len();
-                vec.get_mut(*self).unwrap_or_else(|| {
+                Arc::make_mut(vec).get_mut(*self).unwrap_or_else(|| {
                     panic!(
                         "cannot access index {} of JSON array of length {}",
                         self, len
@@ -61,6 +80,15 @@ impl Index for usize {
             _ => panic!("cannot access index {} of JSON {}", self, Type(v)),
         }
     }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self.index_into_mut(v)
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        match *v {
+            Value::Array(ref mut vec) if *self < vec.len() => Some(Arc::make_mut(vec).remove(*self)),
+            _ => None,
+        }
+    }
 }
 
 impl Index for str {
@@ -72,19 +100,31 @@ impl Index for str {
     }
     fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
         match *v {
-            Value::Object(ref mut map) => map.get_mut(self),
+            Value::Object(ref mut map) => Arc::make_mut(map).get_mut(self),
             _ => None,
         }
     }
     fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
         if let Value::Null = *v {
-            *v = Value::Object(BTreeMap::new());
+            *v = Value::Object(Arc::default());
         }
         match *v {
-            Value::Object(ref mut map) => map.entry(self.to_owned()).or_insert(Value::Null),
+            Value::Object(ref mut map) => Arc::make_mut(map).entry(self.to_owned()).or_insert(Value::Null),
             _ => panic!("cannot access key {:?} in JSON {}", self, Type(v)),
         }
     }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match *v {
+            Value::Null | Value::Object(_) => Some(self.index_or_insert(v)),
+            _ => None,
+        }
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        match *v {
+            Value::Object(ref mut map) => map::remove(Arc::make_mut(map), self),
+            _ => None,
+        }
+    }
 }
 
 impl Index for String {
@@ -97,6 +137,110 @@ impl Index for String {
     fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
         self[..].index_or_insert(v)
     }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self[..].try_index_or_insert(v)
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        self[..].remove_from(v)
+    }
+}
+
+impl<'a> Index for Cow<'a, str> {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(v)
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(v)
+    }
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        (**self).index_or_insert(v)
+    }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).try_index_or_insert(v)
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        (**self).remove_from(v)
+    }
+}
+
+/// One key of a slice of keys, which indexes an array when it is a number.
+struct Segment<'a>(&'a str);
+
+impl<'a> Segment<'a> {
+    fn position(&self, v: &Value) -> Option<usize> {
+        match *v {
+            Value::Array(_) => self.0.parse().ok(),
+            _ => None,
+        }
+    }
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        match self.position(v) {
+            Some(position) => position.index_into(v),
+            None => self.0.index_into(v),
+        }
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match self.position(v) {
+            Some(position) => position.index_into_mut(v),
+            None => self.0.index_into_mut(v),
+        }
+    }
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        match self.position(v) {
+            Some(position) => position.index_or_insert(v),
+            None => self.0.index_or_insert(v),
+        }
+    }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match self.position(v) {
+            Some(position) => position.try_index_or_insert(v),
+            None => self.0.try_index_or_insert(v),
+        }
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        match self.position(v) {
+            Some(position) => position.remove_from(v),
+            None => self.0.remove_from(v),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Index for [S] {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        self.iter().try_fold(v, |v, key| Segment(key.as_ref()).index_into(v))
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self.iter().try_fold(v, |v, key| Segment(key.as_ref()).index_into_mut(v))
+    }
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        self.iter().fold(v, |v, key| Segment(key.as_ref()).index_or_insert(v))
+    }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self.iter().try_fold(v, |v, key| Segment(key.as_ref()).try_index_or_insert(v))
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        let (last, parents) = self.split_last()?;
+
+        parents.index_into_mut(v).and_then(|parent| Segment(last.as_ref()).remove_from(parent))
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> Index for [S; N] {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        self[..].index_into(v)
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self[..].index_into_mut(v)
+    }
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        self[..].index_or_insert(v)
+    }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self[..].try_index_or_insert(v)
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        self[..].remove_from(v)
+    }
 }
 
 impl<'a, T: ?Sized> Index for &'a T
@@ -112,6 +256,12 @@ where
     fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
         (**self).index_or_insert(v)
     }
+    fn try_index_or_insert<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).try_index_or_insert(v)
+    }
+    fn remove_from(&self, v: &mut Value) -> Option<Value> {
+        (**self).remove_from(v)
+    }
 }
 
 // Prevent users from implementing the Index trait.
@@ -120,11 +270,14 @@ mod private {
     impl Sealed for usize {}
     impl Sealed for str {}
     impl Sealed for String {}
+    impl<'a> Sealed for super::Cow<'a, str> {}
+    impl<S: AsRef<str>> Sealed for [S] {}
+    impl<S: AsRef<str>, const N: usize> Sealed for [S; N] {}
     impl<'a, T: ?Sized> Sealed for &'a T where T: Sealed {}
 }
 
-/// Used in panic messages.
-struct Type<'a>(&'a Value);
+/// Used in panic and error messages.
+pub(super) struct Type<'a>(pub(super) &'a Value);
 
 impl<'a> fmt::Display for Type<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -199,3 +352,168 @@ where
         index.index_or_insert(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use super::Index;
+    use super::super::Value;
+
+    #[test]
+    fn index() {
+        let value = Value::from(&json!({
+            "house": {
+                "rooms": ["kitchen", "toilet"],
+            }
+        }));
+
+        assert_eq!(value["house"]["rooms"][0], Value::String("kitchen".to_owned()));
+        assert_eq!(value["house"]["rooms"][2], Value::Null);
+        assert_eq!(value["house"]["cars"], Value::Null);
+        assert_eq!(value["house"]["cars"]["model"], Value::Null);
+
+        // Wrong types
+        assert_eq!(value[0], Value::Null);
+        assert_eq!(value["house"]["rooms"]["kitchen"], Value::Null);
+        assert_eq!(Value::Bool(true)["key"], Value::Null);
+    }
+
+    #[test]
+    fn index_mut() {
+        let mut value = Value::Null;
+
+        // Null is treated as an empty object
+        value["house"]["rooms"] = Value::from(vec![Value::Null]);
+        value["house"]["rooms"][0] = Value::String("kitchen".to_owned());
+        value["house"]["inhabitants"] = Value::from(2);
+
+        assert_eq!(value, Value::from(&json!({
+            "house": {
+                "rooms": ["kitchen"],
+                "inhabitants": 2,
+            }
+        })));
+    }
+
+    #[test]
+    fn index_owned_strings() {
+        let mut value = Value::from(&json!({"house": {"rooms": 2}}));
+        let key = String::from("house");
+
+        assert_eq!(value.get(key.clone()), Some(&value["house"]));
+        assert_eq!(value.get(&key), Some(&value["house"]));
+        assert_eq!(value.get(Cow::Borrowed("house")), Some(&value["house"]));
+        assert_eq!(value.get(Cow::<str>::Owned(key.clone())), Some(&value["house"]));
+        assert_eq!(value.get(Cow::Borrowed("cars")), None);
+
+        assert_eq!(value[&key][Cow::Borrowed("rooms")], Value::from(2));
+        *value.get_mut(Cow::<str>::Owned(key.clone())).unwrap() = Value::from(true);
+        value[Cow::Borrowed("cars")] = Value::from(1);
+
+        assert_eq!(value, Value::from(&json!({"house": true, "cars": 1})));
+    }
+
+    #[test]
+    fn try_index_or_insert() {
+        let mut value = Value::Null;
+
+        *Index::try_index_or_insert(&["house", "rooms"][..], &mut value).unwrap() = Value::from(vec![Value::Null]);
+        *Index::try_index_or_insert(&0, &mut value["house"]["rooms"]).unwrap() = Value::String("kitchen".to_owned());
+
+        assert_eq!(value, Value::from(&json!({
+            "house": {
+                "rooms": ["kitchen"],
+            }
+        })));
+
+        // Out of bounds and wrong types
+        assert!(Index::try_index_or_insert(&1, &mut value["house"]["rooms"]).is_none());
+        assert!(Index::try_index_or_insert("key", &mut value["house"]["rooms"]).is_none());
+        assert!(Index::try_index_or_insert(&["house", "rooms", "1"][..], &mut value).is_none());
+        assert!(Index::try_index_or_insert(&["house", "rooms", "0", "name"][..], &mut value).is_none());
+        assert!(Index::try_index_or_insert(&["house", "rooms", "0"][..], &mut value).is_some());
+        assert!(Index::try_index_or_insert(&0, &mut Value::Null).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access index 1 of JSON array of length 1")]
+    fn index_mut_out_of_bounds() {
+        let mut value = Value::from(vec![Value::Null]);
+
+        value[1] = Value::Bool(true);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access key \"key\" in JSON boolean")]
+    fn index_mut_wrong_type() {
+        let mut value = Value::Bool(true);
+
+        value["key"] = Value::Null;
+    }
+
+    #[test]
+    fn index_path() {
+        let mut value = Value::from(&json!({
+            "house": {
+                "rooms": ["kitchen", "toilet"],
+                "owner": {"name": "John"},
+            }
+        }));
+        let key = "house".to_owned();
+
+        assert_eq!(value.get(&key), value.get("house"));
+        assert_eq!(
+            value.get(&["house", "owner", "name"][..]),
+            Some(&Value::String("John".to_owned()))
+        );
+        assert_eq!(value[&["house", "rooms"][..]][1], Value::String("toilet".to_owned()));
+        assert_eq!(value.get(&[][..] as &[&str]), Some(&value));
+
+        // Missing keys and wrong types
+        assert_eq!(value.get(&["house", "cars"][..]), None);
+        assert_eq!(value.get(&["house", "rooms", "kitchen"][..]), None);
+        assert_eq!(value.get(&["house", "owner", "name", "first"][..]), None);
+        assert_eq!(value[&["garage", "car"][..]], Value::Null);
+
+        value[&["house", "owner", "age"][..]] = Value::from(42);
+        value[&["garage", "car"][..]] = Value::from("sedan");
+        if let Some(name) = value.get_mut(&["house", "owner", "name"][..]) {
+            *name = Value::from("Jane");
+        }
+
+        assert_eq!(value.take_at(&["house", "rooms"][..]), Some(Value::from(&json!(["kitchen", "toilet"]))));
+        assert_eq!(value.take_at(&["house", "rooms"][..]), None);
+        assert_eq!(value.take_at(&[][..] as &[&str]), None);
+        assert_eq!(value, Value::from(&json!({
+            "house": {
+                "owner": {"name": "Jane", "age": 42},
+            },
+            "garage": {"car": "sedan"},
+        })));
+    }
+
+    #[test]
+    fn index_path_through_arrays() {
+        let mut value = Value::from(&json!({
+            "diesel": {
+                "replicas": [{"url": "mysql://first"}, {"url": "mysql://second"}],
+                "0": "key",
+            }
+        }));
+        let path = vec!["diesel".to_owned(), "replicas".to_owned(), "1".to_owned(), "url".to_owned()];
+
+        assert_eq!(value.get(&path[..]), Some(&Value::from("mysql://second")));
+        assert_eq!(value.get(&["diesel", "replicas", "0", "url"]), Some(&Value::from("mysql://first")));
+        assert_eq!(value.get(["diesel", "0"]), Some(&Value::from("key")));
+        assert_eq!(value.get(&[] as &[String; 0]), Some(&value));
+
+        // Missing positions and non numeric keys
+        assert_eq!(value.get(&["diesel", "replicas", "2"]), None);
+        assert_eq!(value.get(&["diesel", "replicas", "-1"]), None);
+        assert_eq!(value.get(&["diesel", "replicas", "first"]), None);
+
+        value[&["diesel", "replicas", "0", "weight"]] = Value::from(2);
+        assert_eq!(value.take_at(["diesel", "replicas", "1"]), Some(Value::from(&json!({"url": "mysql://second"}))));
+        assert_eq!(value["diesel"]["replicas"], Value::from(&json!([{"url": "mysql://first", "weight": 2}])));
+    }
+}
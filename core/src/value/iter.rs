@@ -0,0 +1,200 @@
+use std::slice;
+use std::sync::Arc;
+use super::Value;
+use super::map;
+
+/// An iterator over the elements of a `Value::Array` or the entries of a
+/// `Value::Object`.
+///
+/// Items are `(key, value)` pairs, where `key` is `None` for array elements.
+/// Iterating any other kind of `Value` yields nothing.
+///
+/// This struct is created by the [`iter`] method of `Value`.
+///
+/// [`iter`]: enum.Value.html#method.iter
+pub struct ValueIter<'a> {
+    inner: IterImpl<'a>,
+}
+
+enum IterImpl<'a> {
+    Array(slice::Iter<'a, Value>),
+    Object(map::Iter<'a>),
+    Empty,
+}
+
+impl<'a> ValueIter<'a> {
+    pub(crate) fn new(value: &'a Value) -> Self {
+        let inner = match *value {
+            Value::Array(ref vec) => IterImpl::Array(vec.iter()),
+            Value::Object(ref map) => IterImpl::Object(map.iter()),
+            _ => IterImpl::Empty,
+        };
+        ValueIter { inner }
+    }
+}
+
+impl<'a> Iterator for ValueIter<'a> {
+    type Item = (Option<&'a str>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            IterImpl::Array(ref mut iter) => iter.next().map(|each| (None, each)),
+            IterImpl::Object(ref mut iter) => {
+                iter.next().map(|(key, each)| (Some(key.as_str()), each))
+            },
+            IterImpl::Empty => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IterImpl::Array(ref iter) => iter.size_hint(),
+            IterImpl::Object(ref iter) => iter.size_hint(),
+            IterImpl::Empty => (0, Some(0)),
+        }
+    }
+}
+
+/// A mutable iterator over the elements of a `Value::Array` or the entries
+/// of a `Value::Object`.
+///
+/// This struct is created by the [`iter_mut`] method of `Value`. See
+/// [`ValueIter`] for more details.
+///
+/// [`iter_mut`]: enum.Value.html#method.iter_mut
+/// [`ValueIter`]: struct.ValueIter.html
+pub struct ValueIterMut<'a> {
+    inner: IterMutImpl<'a>,
+}
+
+enum IterMutImpl<'a> {
+    Array(slice::IterMut<'a, Value>),
+    Object(map::IterMut<'a>),
+    Empty,
+}
+
+impl<'a> ValueIterMut<'a> {
+    pub(crate) fn new(value: &'a mut Value) -> Self {
+        let inner = match *value {
+            Value::Array(ref mut vec) => IterMutImpl::Array(Arc::make_mut(vec).iter_mut()),
+            Value::Object(ref mut map) => IterMutImpl::Object(Arc::make_mut(map).iter_mut()),
+            _ => IterMutImpl::Empty,
+        };
+        ValueIterMut { inner }
+    }
+}
+
+impl<'a> Iterator for ValueIterMut<'a> {
+    type Item = (Option<&'a str>, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            IterMutImpl::Array(ref mut iter) => iter.next().map(|each| (None, each)),
+            IterMutImpl::Object(ref mut iter) => {
+                iter.next().map(|(key, each)| (Some(key.as_str()), each))
+            },
+            IterMutImpl::Empty => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IterMutImpl::Array(ref iter) => iter.size_hint(),
+            IterMutImpl::Object(ref iter) => iter.size_hint(),
+            IterMutImpl::Empty => (0, Some(0)),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Value {
+    type Item = (Option<&'a str>, &'a Value);
+    type IntoIter = ValueIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ValueIter::new(self)
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Value {
+    type Item = (Option<&'a str>, &'a mut Value);
+    type IntoIter = ValueIterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ValueIterMut::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::Value;
+
+    #[test]
+    fn iter() {
+        let value = value!({ "driver": "mysql", "servers": ["a", "b"] });
+
+        let entries = value.iter().collect::<Vec<_>>();
+        assert_eq!(entries, vec![
+            (Some("driver"), &value!("mysql")),
+            (Some("servers"), &value!(["a", "b"])),
+        ]);
+
+        let elements = value["servers"].iter().collect::<Vec<_>>();
+        assert_eq!(elements, vec![(None, &value!("a")), (None, &value!("b"))]);
+
+        assert_eq!(value["driver"].iter().count(), 0);
+        assert_eq!(Value::Null.iter().size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut value = value!({ "port": 3306, "servers": [1, 2] });
+
+        for (key, each) in value.iter_mut() {
+            if key == Some("port") {
+                *each = Value::from(5432);
+            }
+        }
+        for (key, each) in &mut value["servers"] {
+            assert_eq!(key, None);
+            *each = Value::from(each.as_u64().unwrap() * 10);
+        }
+
+        assert_eq!(value, value!({ "port": 5432, "servers": [10, 20] }));
+    }
+
+    #[test]
+    fn iter_array_and_object() {
+        let mut value = value!({ "parameters": { "a": 1, "b": 2 }, "list": [true] });
+
+        let keys = value["parameters"].iter_object()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["a", "b"]);
+        assert_eq!(value["parameters"].iter_array().count(), 0);
+
+        assert_eq!(value["list"].iter_array().collect::<Vec<_>>(), vec![&value!(true)]);
+        assert_eq!(value["list"].iter_object().count(), 0);
+
+        for (_, each) in value["parameters"].iter_object_mut() {
+            *each = Value::Null;
+        }
+        for each in value["list"].iter_array_mut() {
+            *each = Value::from(false);
+        }
+        assert_eq!(value.iter_array_mut().count(), 0);
+        assert_eq!(value, value!({ "parameters": { "a": null, "b": null }, "list": [false] }));
+    }
+
+    #[test]
+    fn into_iterator() {
+        let value = value!(["a", "b"]);
+        let mut count = 0;
+
+        for (key, each) in &value {
+            assert!(key.is_none());
+            assert!(each.is_string());
+            count += 1;
+        }
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,48 @@
+//! The map type representing the content of `Value::Object`.
+//!
+//! Objects are stored in a `BTreeMap` by default, which sorts their keys. The
+//! `preserve_order` feature switches to an `IndexMap`, which keeps the keys in
+//! the order they were inserted, that is the order of the configuration file.
+
+use super::Value;
+
+#[cfg(not(feature = "preserve_order"))]
+use std::collections::btree_map as imp;
+#[cfg(feature = "preserve_order")]
+use indexmap::map as imp;
+
+/// The map used by `Value::Object`.
+///
+/// This is a `BTreeMap` by default, and an `IndexMap` when the
+/// `preserve_order` feature is enabled.
+#[cfg(not(feature = "preserve_order"))]
+pub type Map<K, V> = imp::BTreeMap<K, V>;
+
+/// The map used by `Value::Object`.
+///
+/// This is a `BTreeMap` by default, and an `IndexMap` when the
+/// `preserve_order` feature is enabled.
+#[cfg(feature = "preserve_order")]
+pub type Map<K, V> = imp::IndexMap<K, V>;
+
+pub(crate) type Entry<'a> = imp::Entry<'a, String, Value>;
+pub(crate) type Iter<'a> = imp::Iter<'a, String, Value>;
+pub(crate) type IterMut<'a> = imp::IterMut<'a, String, Value>;
+
+/// Removes `key` from `map`, keeping the order of the remaining keys.
+pub(crate) fn remove(map: &mut Map<String, Value>, key: &str) -> Option<Value> {
+    #[cfg(not(feature = "preserve_order"))]
+    return map.remove(key);
+    #[cfg(feature = "preserve_order")]
+    return map.shift_remove(key);
+}
+
+/// Returns the entries of `map` sorted by key, whatever the map type.
+pub(crate) fn sorted(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    #[allow(unused_mut)]
+    let mut entries = map.iter().collect::<Vec<_>>();
+
+    #[cfg(feature = "preserve_order")]
+    entries.sort_by_key(|&(key, _)| key);
+    entries
+}
@@ -0,0 +1,317 @@
+use std::sync::Arc;
+use super::Value;
+use super::map::{self, Entry};
+
+/// How arrays found on both sides of a merge are combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArrayMergeStrategy {
+    /// The right-hand side array replaces the left-hand side one.
+    Replace,
+    /// The right-hand side elements are appended to the left-hand side array.
+    Concatenate,
+    /// Elements sharing the same index are merged, extra right-hand side
+    /// elements are appended.
+    MergeByIndex,
+}
+
+/// Controls how [`Value::merge_with`] combines two values.
+///
+/// The default strategy replaces arrays and keeps the keys whose right-hand
+/// side value is null, which is what [`Value::merge`] uses.
+///
+/// [`Value::merge`]: enum.Value.html#method.merge
+/// [`Value::merge_with`]: enum.Value.html#method.merge_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MergeStrategy {
+    /// How arrays found on both sides are combined.
+    pub arrays: ArrayMergeStrategy,
+    /// Whether a null on the right-hand side removes the matching key from
+    /// the left-hand side object instead of replacing its value.
+    pub remove_on_null: bool,
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        Self {
+            arrays: ArrayMergeStrategy::Replace,
+            remove_on_null: false,
+        }
+    }
+}
+
+/// Deep-merges `other` into `target` according to `strategy`.
+pub(crate) fn merge(target: &mut Value, other: &Value, strategy: MergeStrategy) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            let target = Arc::make_mut(target);
+
+            for (key, value) in other.iter() {
+                if value.is_null() && strategy.remove_on_null {
+                    map::remove(target, key);
+                    continue;
+                }
+
+                match target.entry(key.clone()) {
+                    Entry::Occupied(mut entry) => merge(entry.get_mut(), value, strategy),
+                    Entry::Vacant(entry) => {
+                        entry.insert(value.clone());
+                    },
+                }
+            }
+        },
+        (Value::Array(target), Value::Array(other)) => match strategy.arrays {
+            ArrayMergeStrategy::Replace => *target = other.clone(),
+            ArrayMergeStrategy::Concatenate => Arc::make_mut(target).extend(other.iter().cloned()),
+            ArrayMergeStrategy::MergeByIndex => {
+                let target = Arc::make_mut(target);
+
+                for (index, value) in other.iter().enumerate() {
+                    match target.get_mut(index) {
+                        Some(element) => merge(element, value, strategy),
+                        None => target.push(value.clone()),
+                    }
+                }
+            },
+        },
+        (target, other) => *target = other.clone(),
+    }
+}
+
+/// Applies `patch` to `target` as described by RFC 7386.
+pub(crate) fn merge_patch(target: &mut Value, patch: &Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        _ => {
+            *target = patch.clone();
+            return;
+        },
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Arc::default());
+    }
+
+    if let Value::Object(target) = target {
+        let target = Arc::make_mut(target);
+
+        for (key, value) in patch.iter() {
+            if value.is_null() {
+                map::remove(target, key);
+            }
+            else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayMergeStrategy, MergeStrategy};
+    use crate::value::Value;
+
+    #[test]
+    fn merge_objects() {
+        let mut value = value!({
+            "diesel": {
+                "driver": "mysql",
+                "port": 3306,
+                "options": { "charset": "utf8", "timeout": 5 }
+            },
+            "debug": false
+        });
+
+        value.merge(&value!({
+            "diesel": {
+                "driver": "pgsql",
+                "options": { "timeout": 10, "ssl": true }
+            },
+            "name": "app"
+        }));
+
+        assert_eq!(value, value!({
+            "diesel": {
+                "driver": "pgsql",
+                "port": 3306,
+                "options": { "charset": "utf8", "timeout": 10, "ssl": true }
+            },
+            "debug": false,
+            "name": "app"
+        }));
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        let mut value = value!({
+            "scalar": { "nested": true },
+            "object": 12,
+            "array": { "key": "value" },
+            "null": null
+        });
+
+        value.merge(&value!({
+            "scalar": "replaced",
+            "object": { "nested": 1 },
+            "array": [1, 2],
+            "null": { "key": null }
+        }));
+
+        assert_eq!(value, value!({
+            "scalar": "replaced",
+            "object": { "nested": 1 },
+            "array": [1, 2],
+            "null": { "key": null }
+        }));
+
+        let mut value = value!({ "key": "value" });
+        value.merge(&value!(["replaced"]));
+        assert_eq!(value, value!(["replaced"]));
+    }
+
+    #[test]
+    fn merge_arrays() {
+        let left = value!({ "hosts": [{ "name": "a", "port": 1 }, { "name": "b" }] });
+        let right = value!({ "hosts": [{ "port": 2 }, { "name": "c" }, { "name": "d" }] });
+
+        let mut value = left.clone();
+        value.merge(&right);
+        assert_eq!(value, right);
+
+        let mut value = left.clone();
+        value.merge_with(&right, MergeStrategy {
+            arrays: ArrayMergeStrategy::Concatenate,
+            ..MergeStrategy::default()
+        });
+        assert_eq!(value, value!({ "hosts": [
+            { "name": "a", "port": 1 }, { "name": "b" },
+            { "port": 2 }, { "name": "c" }, { "name": "d" }
+        ] }));
+
+        let mut value = left;
+        value.merge_with(&right, MergeStrategy {
+            arrays: ArrayMergeStrategy::MergeByIndex,
+            ..MergeStrategy::default()
+        });
+        assert_eq!(value, value!({ "hosts": [
+            { "name": "a", "port": 2 }, { "name": "c" }, { "name": "d" }
+        ] }));
+    }
+
+    #[test]
+    fn remove_on_null() {
+        let left = value!({
+            "diesel": { "driver": "mysql", "password": "secret" },
+            "debug": true
+        });
+        let right = value!({
+            "diesel": { "password": null },
+            "debug": null,
+            "missing": null
+        });
+
+        let mut value = left.clone();
+        value.merge(&right);
+        assert_eq!(value, value!({
+            "diesel": { "driver": "mysql", "password": null },
+            "debug": null,
+            "missing": null
+        }));
+
+        let mut value = left;
+        value.merge_with(&right, MergeStrategy {
+            remove_on_null: true,
+            ..MergeStrategy::default()
+        });
+        assert_eq!(value, value!({ "diesel": { "driver": "mysql" } }));
+    }
+
+    #[test]
+    fn layered() {
+        let defaults = value!({ "port": 80, "tls": { "enabled": false } });
+        let production = value!({ "host": "example.com", "tls": { "enabled": true } });
+        let development = value!({ "host": "localhost", "port": 8000 });
+
+        let mut value = Value::Null;
+        for layer in &[defaults, production, development] {
+            value.merge(layer);
+        }
+
+        assert_eq!(value, value!({
+            "host": "localhost",
+            "port": 8000,
+            "tls": { "enabled": true }
+        }));
+    }
+
+    #[test]
+    fn merge_patch() {
+        let mut value = value!({
+            "title": "Goodbye!",
+            "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+
+        value.merge_patch(&value!({
+            "title": "Hello!",
+            "phoneNumber": "+01-123-456-7890",
+            "author": { "familyName": null },
+            "tags": ["example"]
+        }));
+
+        assert_eq!(value, value!({
+            "title": "Hello!",
+            "author": { "givenName": "John" },
+            "tags": ["example"],
+            "content": "This will be unchanged",
+            "phoneNumber": "+01-123-456-7890"
+        }));
+    }
+
+    #[test]
+    fn merge_patch_rfc_examples() {
+        let examples = vec![
+            (value!({"a": "b"}), value!({"a": "c"}), value!({"a": "c"})),
+            (value!({"a": "b"}), value!({"b": "c"}), value!({"a": "b", "b": "c"})),
+            (value!({"a": "b"}), value!({"a": null}), value!({})),
+            (value!({"a": "b", "b": "c"}), value!({"a": null}), value!({"b": "c"})),
+            (value!({"a": ["b"]}), value!({"a": "c"}), value!({"a": "c"})),
+            (value!({"a": "c"}), value!({"a": ["b"]}), value!({"a": ["b"]})),
+            (
+                value!({"a": {"b": "c"}}),
+                value!({"a": {"b": "d", "c": null}}),
+                value!({"a": {"b": "d"}})
+            ),
+            (value!({"a": [{"b": "c"}]}), value!({"a": [1]}), value!({"a": [1]})),
+            (value!(["a", "b"]), value!(["c", "d"]), value!(["c", "d"])),
+            (value!({"a": "b"}), value!(["c"]), value!(["c"])),
+            (value!({"a": "foo"}), value!(null), value!(null)),
+            (value!({"a": "foo"}), value!("bar"), value!("bar")),
+            (value!({"e": null}), value!({"a": 1}), value!({"e": null, "a": 1})),
+            (value!([1, 2]), value!({"a": "b", "c": null}), value!({"a": "b"})),
+            (value!({}), value!({"a": {"bb": {"ccc": null}}}), value!({"a": {"bb": {}}})),
+        ];
+
+        for (target, patch, expected) in examples {
+            let mut value = target.clone();
+            value.merge_patch(&patch);
+            assert_eq!(value, expected, "{} patched by {}", target, patch);
+        }
+    }
+
+    #[test]
+    fn merge_scalars() {
+        let mut value = value!(12);
+        value.merge(&value!("twelve"));
+        assert_eq!(value, value!("twelve"));
+
+        let mut value = value!({ "port": 3306, "debug": true, "ratio": 0.5 });
+        value.merge(&value!({ "port": "3307", "debug": false, "ratio": null }));
+        assert_eq!(value, value!({ "port": "3307", "debug": false, "ratio": null }));
+
+        // Scalars never merge into objects or arrays, they replace them
+        let mut value = value!({ "servers": ["a"], "diesel": { "port": 3306 } });
+        value.merge(&value!({ "servers": "a", "diesel": 3306 }));
+        assert_eq!(value, value!({ "servers": "a", "diesel": 3306 }));
+    }
+}
\ No newline at end of file
@@ -1,7 +1,32 @@
+mod bytes;
+mod de;
+mod diff;
+mod dump;
+mod entry;
+mod env;
+mod flatten;
 mod index;
+mod iter;
+mod map;
+mod merge;
 mod number;
+mod option;
+mod placeholder;
+mod query;
+mod redact;
+mod ser;
+mod size;
 #[allow(clippy::module_inception)] mod value;
+mod walk;
 
+pub use diff::{ArrayDiffStrategy, DiffEntry, DiffKind};
+pub use entry::Entry;
+pub use flatten::StringMapOptions;
 pub use index::Index;
+pub use iter::{ValueIter, ValueIterMut};
+pub use map::Map;
+pub use merge::{ArrayMergeStrategy, MergeStrategy};
 pub use number::Number;
+pub use option::OptionValueExt;
+pub use redact::RedactedDebug;
 pub use value::Value;
\ No newline at end of file
@@ -1,15 +1,30 @@
 #![allow(dead_code)]
 
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use serde::ser::{Serialize, Serializer};
+use crate::error;
 
 /// Represents a number, whether integer or floating point.
-#[derive(Clone, PartialEq, PartialOrd)]
+///
+/// With the `arbitrary_precision` feature, the number keeps the digits it was
+/// written with, so that integers beyond the range of u64 and i64, and floats
+/// more precise than f64, are not rounded.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "arbitrary_precision"), derive(PartialEq))]
 pub struct Number {
+    #[cfg(not(feature = "arbitrary_precision"))]
     n: N,
+    /// Always an integer or a finite float.
+    #[cfg(feature = "arbitrary_precision")]
+    n: String,
 }
 
-#[cfg(not(feature = "arbitrary_precision"))]
-#[derive(Copy, Clone, PartialEq, PartialOrd)]
+/// The value of a number. With the `arbitrary_precision` feature, integers
+/// beyond the range of u64 and i64 are rounded to the nearest float.
+#[derive(Copy, Clone, PartialEq)]
 enum N {
     PosInt(u64),
     /// Always less than zero.
@@ -18,10 +33,13 @@ enum N {
     Float(f64),
 }
 
-#[cfg(feature = "arbitrary_precision")]
-type N = String;
-
 impl Debug for Number {
+    #[cfg(feature = "arbitrary_precision")]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "Number({})", self.n)
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut debug = formatter.debug_tuple("Number");
         match self.n {
@@ -39,6 +57,65 @@ impl Debug for Number {
     }
 }
 
+/// Formats the number as JSON text: integers are written without any
+/// fractional part, and floats with the shortest digits reading back as the
+/// same float, always keeping the float notation, e.g. `10.12`, `1.0` or
+/// `1e300`.
+///
+/// With the `arbitrary_precision` feature, floats and integers beyond the
+/// range of u64 and i64 are written with the digits they were parsed from.
+impl fmt::Display for Number {
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n {
+            N::PosInt(u) => write!(formatter, "{}", u),
+            N::NegInt(i) => write!(formatter, "{}", i),
+            // `Number` only holds finite floats, whose `Debug` form is valid
+            // JSON and always keeps the float notation
+            N::Float(f) => write!(formatter, "{:?}", f),
+        }
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n() {
+            N::PosInt(u) => write!(formatter, "{}", u),
+            N::NegInt(i) => write!(formatter, "{}", i),
+            // Big integers are rounded to a float, their digits are kept
+            N::Float(_) => formatter.write_str(&self.n),
+        }
+    }
+}
+
+impl Serialize for Number {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Digits f64 cannot hold are kept as serde_json keeps them, through
+        // its private number token, which other serializers do not
+        // understand: they need the number converted first, as
+        // `Value::to_yaml_string` does
+        #[cfg(feature = "arbitrary_precision")]
+        {
+            let exact = match self.n() {
+                N::PosInt(_) | N::NegInt(_) => true,
+                N::Float(f) => Self::from_f64(f).is_some_and(|rounded| rounded.n == self.n),
+            };
+            if !exact {
+                return serde_json::Number::from(self).serialize(serializer);
+            }
+        }
+
+        match self.n() {
+            N::PosInt(u) => serializer.serialize_u64(u),
+            N::NegInt(i) => serializer.serialize_i64(i),
+            N::Float(f) => serializer.serialize_f64(f),
+        }
+    }
+}
+
 impl Number {
     /// Returns true if the `Number` is an integer between `i64::MIN` and
     /// `i64::MAX`.
@@ -90,7 +167,7 @@ impl Number {
         {
             for c in self.n.chars() {
                 if c == '.' || c == 'e' || c == 'E' {
-                    return self.n.parse::<f64>().ok().map_or(false, |f| f.is_finite());
+                    return self.n.parse::<f64>().is_ok_and(f64::is_finite);
                 }
             }
             false
@@ -164,6 +241,138 @@ impl Number {
         }
     }
 
+    /// Returns true if both numbers have the same mathematical value, whether
+    /// they are integers or floats: `1` and `1.0` are numerically equal,
+    /// while `PartialEq` tells them apart.
+    ///
+    /// Integers and floats are compared exactly, without converting the
+    /// integer to a float. As floats beyond 2^53 cannot hold every integer,
+    /// such an integer only equals the float holding exactly its value: `2^63`
+    /// equals `9223372036854775808.0`, but `u64::MAX` equals no float, not
+    /// even `u64::MAX as f64`, which is rounded up to 2^64. `-0.0` equals `0`.
+    pub fn numeric_eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "arbitrary_precision")]
+        {
+            match (self.is_big_integer(), other.is_big_integer()) {
+                (true, true) => return self == other,
+                (true, false) => return cmp_big_integer(&self.n, other.n()) == Ordering::Equal,
+                (false, true) => return cmp_big_integer(&other.n, self.n()) == Ordering::Equal,
+                (false, false) => {},
+            }
+        }
+
+        match (self.n(), other.n()) {
+            (N::PosInt(self_u64), N::PosInt(other_u64)) => self_u64 == other_u64,
+            (N::NegInt(self_i64), N::NegInt(other_i64)) => self_i64 == other_i64,
+            (N::Float(self_f64), N::Float(other_f64)) => self_f64 == other_f64,
+            (N::PosInt(_), N::NegInt(_)) | (N::NegInt(_), N::PosInt(_)) => false,
+            (N::PosInt(u), N::Float(f)) | (N::Float(f), N::PosInt(u)) => {
+                cmp_u64_f64(u, f) == Ordering::Equal
+            },
+            (N::NegInt(i), N::Float(f)) | (N::Float(f), N::NegInt(i)) => {
+                cmp_i64_f64(i, f) == Ordering::Equal
+            },
+        }
+    }
+
+    /// Adds two numbers, returning None when the sum overflows.
+    ///
+    /// The sum of two integers stays an integer, and overflows when it does
+    /// not fit in an i64 or an u64. As soon as one of the numbers is a float,
+    /// the sum is a float, which overflows when it is infinite.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(self_i128), Some(other_i128)) => Self::from_i128(self_i128.checked_add(other_i128)?),
+            _ => Self::from_f64(self.as_f64()? + other.as_f64()?),
+        }
+    }
+
+    /// Multiplies two numbers, returning None when the product overflows.
+    ///
+    /// As with [`checked_add`], the product of two integers stays an integer,
+    /// and becomes a float only when one of the numbers is a float.
+    ///
+    /// [`checked_add`]: #method.checked_add
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(self_i128), Some(other_i128)) => Self::from_i128(self_i128.checked_mul(other_i128)?),
+            _ => Self::from_f64(self.as_f64()? * other.as_f64()?),
+        }
+    }
+
+    /// Represents the number as i64, clamping it to the bounds of i64. Floats
+    /// are truncated towards zero.
+    pub fn saturating_to_i64(&self) -> i64 {
+        match self.n() {
+            N::PosInt(u) => i64::try_from(u).unwrap_or(i64::MAX),
+            N::NegInt(i) => i,
+            N::Float(f) => f as i64,
+        }
+    }
+
+    /// If the `Number` is an integer, widens it to an i128, which holds any
+    /// of them.
+    fn as_i128(&self) -> Option<i128> {
+        match self.n() {
+            N::PosInt(u) => Some(i128::from(u)),
+            N::NegInt(i) => Some(i128::from(i)),
+            N::Float(_) => None,
+        }
+    }
+
+    /// Narrows an i128 into an integer `Number`, if it fits in an u64 or an
+    /// i64.
+    fn from_i128(i: i128) -> Option<Self> {
+        if let Ok(u) = u64::try_from(i) {
+            Some(Self::from(u))
+        }
+        else {
+            i64::try_from(i).ok().map(Self::from)
+        }
+    }
+
+    /// Returns the value of the number.
+    #[cfg(not(feature = "arbitrary_precision"))]
+    #[inline]
+    fn n(&self) -> N {
+        self.n
+    }
+
+    /// Returns the value of the number, parsed out of its digits.
+    #[cfg(feature = "arbitrary_precision")]
+    fn n(&self) -> N {
+        if let Ok(u) = self.n.parse() {
+            N::PosInt(u)
+        }
+        else {
+            match self.n.parse::<i64>() {
+                Ok(i) if i < 0 => N::NegInt(i),
+                Ok(i) => N::PosInt(i as u64),
+                Err(_) => N::Float(self.n.parse().unwrap_or_default()),
+            }
+        }
+    }
+
+    /// Returns the bytes the number uses on the heap.
+    #[cfg(not(feature = "arbitrary_precision"))]
+    #[inline]
+    pub(crate) fn heap_size(&self) -> usize {
+        0
+    }
+
+    /// Returns the bytes the digits of the number use on the heap.
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) fn heap_size(&self) -> usize {
+        self.n.capacity()
+    }
+
+    /// Returns true if the number is an integer beyond the range of u64 and
+    /// i64.
+    #[cfg(feature = "arbitrary_precision")]
+    fn is_big_integer(&self) -> bool {
+        !self.is_f64() && !self.is_u64() && !self.is_i64()
+    }
+
     #[cfg(feature = "arbitrary_precision")]
     /// Not public API. Only tests use this.
     #[doc(hidden)]
@@ -173,109 +382,387 @@ impl Number {
     }
 }
 
+/// Compares an unsigned integer with a finite float, without losing precision
+/// on either side.
+fn cmp_u64_f64(u: u64, f: f64) -> Ordering
+{
+    // 2^64, the first float too large to be truncated into an u64
+    const U64_UPPER_BOUND: f64 = 18_446_744_073_709_551_616.0;
+
+    if f < 0.0 {
+        Ordering::Greater
+    }
+    else if f >= U64_UPPER_BOUND {
+        Ordering::Less
+    }
+    else {
+        // The truncated float fits in an u64, its fractional part decides
+        // between equal integral parts
+        u.cmp(&(f.trunc() as u64)).then_with(|| {
+            if f.fract() > 0.0 { Ordering::Less } else { Ordering::Equal }
+        })
+    }
+}
+
+/// Compares a negative integer with a finite float, without losing precision
+/// on either side.
+fn cmp_i64_f64(i: i64, f: f64) -> Ordering
+{
+    // -2^63, the last float that can be truncated into an i64
+    const I64_LOWER_BOUND: f64 = -9_223_372_036_854_775_808.0;
+
+    if f >= 0.0 {
+        Ordering::Less
+    }
+    else if f < I64_LOWER_BOUND {
+        Ordering::Greater
+    }
+    else {
+        // The truncated float fits in an i64, its fractional part decides
+        // between equal integral parts
+        i.cmp(&(f.trunc() as i64)).then_with(|| {
+            if f.fract() < 0.0 { Ordering::Greater } else { Ordering::Equal }
+        })
+    }
+}
+
 impl Eq for Number {}
 
+impl PartialOrd for Number
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Number 
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn cmp(&self, other: &Self) -> Ordering {
         self.n.cmp(&other.n)
     }
+
+    /// Integers beyond the range of u64 and i64 are compared digit by digit,
+    /// with each other as with the other numbers, and ordered before a float
+    /// of the same value. Floats are compared once rounded to f64.
+    #[cfg(feature = "arbitrary_precision")]
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_big_integer(), other.is_big_integer()) {
+            (true, true) => cmp_integer_digits(&self.n, &other.n),
+            (true, false) => cmp_big_integer(&self.n, other.n()).then(Ordering::Less),
+            (false, true) => cmp_big_integer(&other.n, self.n()).reverse().then(Ordering::Greater),
+            (false, false) => self.n().cmp(&other.n()),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl PartialEq for Number
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+/// Compares the digits of an integer beyond the range of u64 and i64 with
+/// a number which is not, without rounding either of them.
+#[cfg(feature = "arbitrary_precision")]
+fn cmp_big_integer(big: &str, n: N) -> Ordering
+{
+    match n {
+        N::PosInt(u) => cmp_integer_digits(big, &u.to_string()),
+        N::NegInt(i) => cmp_integer_digits(big, &i.to_string()),
+        // Floats with a fractional part are within the range of u64 and i64,
+        // so their integral part decides. Adding 0.0 turns -0.0 into 0.0
+        N::Float(f) => cmp_integer_digits(big, &format!("{:.0}", f.trunc() + 0.0)),
+    }
+}
+
+/// Compares two integers written without leading zeros.
+#[cfg(feature = "arbitrary_precision")]
+fn cmp_integer_digits(a: &str, b: &str) -> Ordering
+{
+    let cmp_magnitudes = |a: &str, b: &str| a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+
+    match (a.strip_prefix('-'), b.strip_prefix('-')) {
+        (Some(a), Some(b)) => cmp_magnitudes(b, a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => cmp_magnitudes(a, b),
+    }
+}
+
+/// Numbers equal the integers of the same value, whether they are integers
+/// or floats, as with [`Number::numeric_eq`], so that `number == 5` holds for
+/// both `5` and `5.0`.
+///
+/// [`Number::numeric_eq`]: struct.Number.html#method.numeric_eq
+impl PartialEq<i64> for Number
+{
+    fn eq(&self, other: &i64) -> bool {
+        self.numeric_eq(&Self::from(*other))
+    }
+}
+
+impl PartialEq<Number> for i64
+{
+    fn eq(&self, other: &Number) -> bool {
+        other == self
+    }
+}
+
+/// Numbers are compared with integers by their numerical value.
+impl PartialOrd<i64> for Number
+{
+    fn partial_cmp(&self, other: &i64) -> Option<Ordering> {
+        let other = Self::from(*other);
+
+        if self.numeric_eq(&other) { Some(Ordering::Equal) } else { self.partial_cmp(&other) }
+    }
+}
+
+impl PartialOrd<Number> for i64
+{
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+/// Numbers equal the floats of the same value, whether they are integers or
+/// floats, as with [`Number::numeric_eq`]. They never equal NaN nor infinite
+/// floats.
+///
+/// [`Number::numeric_eq`]: struct.Number.html#method.numeric_eq
+impl PartialEq<f64> for Number
+{
+    fn eq(&self, other: &f64) -> bool {
+        Self::from_f64(*other).is_some_and(|other| self.numeric_eq(&other))
+    }
+}
+
+impl PartialEq<Number> for f64
+{
+    fn eq(&self, other: &Number) -> bool {
+        other == self
+    }
+}
+
+/// Numbers are compared with floats by their numerical value. They are not
+/// comparable with NaN nor infinite floats.
+impl PartialOrd<f64> for Number
+{
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        let other = Self::from_f64(*other)?;
+
+        if self.numeric_eq(&other) { Some(Ordering::Equal) } else { self.partial_cmp(&other) }
+    }
+}
+
+impl PartialOrd<Number> for f64
+{
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+/// Numbers are hashed consistently with their equality, so that integers
+/// and floats are hashed apart: `1` and `1.0` are not equal. Floats are
+/// hashed by their bits, `-0.0` as `0.0` since both are equal.
+///
+/// With the `arbitrary_precision` feature, numbers are hashed by their value:
+/// floats written with different digits but rounded to the same f64 are
+/// equal, while integers beyond the range of u64 and i64 are hashed by their
+/// digits.
+impl Hash for Number
+{
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.n.hash(state);
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.is_big_integer() {
+            state.write_u8(3);
+            self.n.hash(state);
+        }
+        else {
+            self.n().hash(state);
+        }
+    }
 }
 
 impl Eq for N {}
 
-impl Ord for N 
+impl Hash for N
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match (self, other) {
-            // PosInts
-            (Self::PosInt(self_u64), Self::PosInt(other_u64))   => {
-                // Real comparison
-                self_u64.cmp(&other_u64)
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Self::PosInt(u) => {
+                state.write_u8(0);
+                u.hash(state);
             },
-            (Self::PosInt(_self_u64), Self::NegInt(_other_i64))   => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Greater
+            Self::NegInt(i) => {
+                state.write_u8(1);
+                i.hash(state);
             },
-            (Self::PosInt(_self_u64), Self::Float(_other_f64))   => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Greater
+            Self::Float(f) => {
+                state.write_u8(2);
+                // Adding 0.0 turns -0.0 into 0.0, and leaves any other float
+                (f + 0.0).to_bits().hash(state);
             },
+        }
+    }
+}
 
-            // NegInts
-            (Self::NegInt(self_i64), Self::NegInt(other_i64))   => {
-                // Real comparison
-                self_i64.cmp(&other_i64)
-            },
-            (Self::NegInt(_self_i64), Self::PosInt(_other_u64))   => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Less
-            },
-            (Self::NegInt(_self_i64), Self::Float(_other_f64))   => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Greater
-            },
+impl PartialOrd for N
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-            // Floats
-            (Self::Float(self_f64), Self::Float(other_f64))     => {
-                // Pseudo-real comparison
-                format!("{:e}", self_f64).cmp(&format!("{:e}", other_f64))
+/// Numbers are ordered by their numerical value. An integer and a float of
+/// the same value are not equal, the integer is ordered first.
+impl Ord for N 
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (*self, *other) {
+            (Self::PosInt(self_u64), Self::PosInt(other_u64)) => self_u64.cmp(&other_u64),
+            (Self::NegInt(self_i64), Self::NegInt(other_i64)) => self_i64.cmp(&other_i64),
+            (Self::Float(self_f64), Self::Float(other_f64)) => {
+                // Floats are always finite, so they are always comparable
+                self_f64.partial_cmp(&other_f64).unwrap_or(Ordering::Equal)
             },
 
-            (Self::Float(_self_f64), Self::PosInt(_other_u64))     => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Less
+            // NegInts are always less than zero
+            (Self::PosInt(_), Self::NegInt(_)) => Ordering::Greater,
+            (Self::NegInt(_), Self::PosInt(_)) => Ordering::Less,
+
+            (Self::PosInt(self_u64), Self::Float(other_f64)) => {
+                cmp_u64_f64(self_u64, other_f64).then(Ordering::Less)
+            },
+            (Self::Float(self_f64), Self::PosInt(other_u64)) => {
+                cmp_u64_f64(other_u64, self_f64).reverse().then(Ordering::Greater)
             },
-            (Self::Float(_self_f64), Self::NegInt(_other_i64))     => {
-                // Faking by the order in enum (PosInt > NegInt > Float)
-                std::cmp::Ordering::Less
+            (Self::NegInt(self_i64), Self::Float(other_f64)) => {
+                cmp_i64_f64(self_i64, other_f64).then(Ordering::Less)
+            },
+            (Self::Float(self_f64), Self::NegInt(other_i64)) => {
+                cmp_i64_f64(other_i64, self_f64).reverse().then(Ordering::Greater)
             },
         }
     }
 }
 
-impl From<&serde_json::Number> for Number
+/// Converts a [serde_json] number into a [`Number`].
+///
+/// Fails with an error of kind `FormatError` if the number is neither an
+/// integer nor a finite float.
+///
+/// [serde_json]: https://docs.serde.rs/serde_json/index.html
+/// [`Number`]: struct.Number.html
+impl TryFrom<&serde_json::Number> for Number
 {
-    fn from(json: &serde_json::Number) -> Self
+    type Error = error::Error;
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn try_from(json: &serde_json::Number) -> Result<Self, Self::Error>
     {
-        match json {
-            u64_val if u64_val.is_u64() => {
-                Self { n: N::PosInt(u64_val.as_u64().unwrap()) }
-            },
-            i64_val if i64_val.is_i64() => {
-                Self { n: N::NegInt(i64_val.as_i64().unwrap()) }
-            },
-            f64_val if f64_val.is_f64() => {
-                Self { n: N::Float(f64_val.as_f64().unwrap()) }
-            },
-            _                           => {
-                unimplemented!()
-            }
+        // The digits are kept as they are, once they are known to be a number
+        let n = json.to_string();
+
+        match n.parse::<f64>() {
+            Ok(f) if f.is_finite() => Ok(Number { n }),
+            _ => Err(error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("'{}' is not a valid number", json)
+            )),
+        }
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn try_from(json: &serde_json::Number) -> Result<Self, Self::Error>
+    {
+        if let Some(u) = json.as_u64() {
+            Ok(Self::from(u))
+        }
+        else if let Some(i) = json.as_i64() {
+            Ok(Self::from(i))
+        }
+        else {
+            json.as_f64().and_then(Self::from_f64).ok_or_else(|| error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("'{}' is not a valid number", json)
+            ))
         }
     }
 }
 
-impl From<&serde_yaml::Number> for Number
+/// Converts a [serde_yaml] number into a [`Number`].
+///
+/// Fails with an error of kind `FormatError` if the number is neither an
+/// integer nor a finite float, such as YAML's `.nan` and `.inf`.
+///
+/// [serde_yaml]: https://docs.serde.rs/serde_yaml/index.html
+/// [`Number`]: struct.Number.html
+impl TryFrom<&serde_yaml::Number> for Number
 {
-    fn from(yaml: &serde_yaml::Number) -> Self
+    type Error = error::Error;
+
+    fn try_from(yaml: &serde_yaml::Number) -> Result<Self, Self::Error>
     {
-        match yaml {
-            u64_val if u64_val.is_u64() => {
-                Self { n: N::PosInt(u64_val.as_u64().unwrap()) }
-            },
-            i64_val if i64_val.is_i64() => {
-                Self { n: N::NegInt(i64_val.as_i64().unwrap()) }
-            },
-            f64_val if f64_val.is_f64() => {
-                Self { n: N::Float(f64_val.as_f64().unwrap()) }
-            },
-            _                           => {
-                unimplemented!()
+        if let Some(u) = yaml.as_u64() {
+            Ok(Self::from(u))
+        }
+        else if let Some(i) = yaml.as_i64() {
+            Ok(Self::from(i))
+        }
+        else {
+            yaml.as_f64().and_then(Self::from_f64).ok_or_else(|| error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("'{}' is not a valid number", yaml)
+            ))
+        }
+    }
+}
+
+impl From<&Number> for serde_json::Number
+{
+    #[cfg(feature = "arbitrary_precision")]
+    fn from(number: &Number) -> Self
+    {
+        // The digits of a `Number` always make a valid JSON number
+        number.n.parse().expect("expected a valid number")
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn from(number: &Number) -> Self
+    {
+        match number.n {
+            N::PosInt(u) => Self::from(u),
+            N::NegInt(i) => Self::from(i),
+            N::Float(f) => {
+                // `Number::from_f64` rejects non-finite floats, so this
+                // conversion cannot fail.
+                Self::from_f64(f).expect("expected a finite float")
             }
         }
     }
 }
 
+impl From<&Number> for serde_yaml::Number
+{
+    fn from(number: &Number) -> Self
+    {
+        match number.n() {
+            N::PosInt(u) => Self::from(u),
+            N::NegInt(i) => Self::from(i),
+            N::Float(f) => Self::from(f),
+        }
+    }
+}
+
 macro_rules! impl_from_unsigned {
     (
         $($ty:ty),*
@@ -292,7 +779,7 @@ macro_rules! impl_from_unsigned {
                             itoa::Buffer::new().format(u).to_owned()
                         }
                     };
-                    Number { n: n }
+                    Number { n }
                 }
             }
         )*
@@ -321,7 +808,7 @@ macro_rules! impl_from_signed {
                             itoa::Buffer::new().format(i).to_owned()
                         }
                     };
-                    Number { n: n }
+                    Number { n }
                 }
             }
         )*
@@ -329,4 +816,386 @@ macro_rules! impl_from_signed {
 }
 
 impl_from_unsigned!(u8, u16, u32, u64, usize);
-impl_from_signed!(i8, i16, i32, i64, isize);
\ No newline at end of file
+impl_from_signed!(i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+    use super::Number;
+    use crate::error;
+
+    fn float(f: f64) -> Number
+    {
+        Number::from_f64(f).expect("failed to create number from float")
+    }
+
+    #[test]
+    fn float_ordering() {
+        assert!(float(2.0) < float(10.0));
+        assert!(float(-10.0) < float(-2.0));
+        assert!(float(-1.5) < float(0.5));
+        assert!(float(1e-3) < float(1e3));
+        assert_eq!(float(0.0).cmp(&float(-0.0)), Ordering::Equal);
+        assert_eq!(float(3.25).cmp(&float(3.25)), Ordering::Equal);
+    }
+
+    #[test]
+    fn numeric_eq() {
+        assert!(Number::from(1u8).numeric_eq(&float(1.0)));
+        assert!(float(1.0).numeric_eq(&Number::from(1u8)));
+        assert!(Number::from(-3).numeric_eq(&float(-3.0)));
+        assert!(float(-0.0).numeric_eq(&Number::from(0)));
+        assert!(float(-0.0).numeric_eq(&float(0.0)));
+        assert!(Number::from(1u64 << 63).numeric_eq(&float(9_223_372_036_854_775_808.0)));
+        assert!(Number::from(i64::MIN).numeric_eq(&float(-9_223_372_036_854_775_808.0)));
+
+        assert!(!Number::from(1).numeric_eq(&float(1.5)));
+        assert!(!Number::from(1).numeric_eq(&Number::from(-1)));
+        assert!(!Number::from(u64::MAX).numeric_eq(&float(u64::MAX as f64)));
+        assert!(!Number::from((1u64 << 53) + 1).numeric_eq(&float(((1u64 << 53) + 1) as f64)));
+
+        // The derived equality still tells the representations apart
+        assert_ne!(Number::from(1u8), float(1.0));
+    }
+
+    #[test]
+    fn checked_add() {
+        assert_eq!(Number::from(2).checked_add(&Number::from(3)), Some(Number::from(5)));
+        assert_eq!(Number::from(-2).checked_add(&Number::from(3u64)), Some(Number::from(1)));
+        assert_eq!(Number::from(2).checked_add(&Number::from(-3)), Some(Number::from(-1)));
+        assert_eq!(Number::from(i64::MIN).checked_add(&Number::from(u64::MAX)), Some(Number::from(i64::MAX as u64)));
+
+        // Floats are only used when needed
+        assert_eq!(Number::from(2).checked_add(&float(0.5)), Some(float(2.5)));
+        assert_eq!(float(0.5).checked_add(&float(0.25)), Some(float(0.75)));
+        assert_eq!(float(1.5).checked_add(&float(1.5)), Some(float(3.0)));
+
+        // Overflows
+        assert_eq!(Number::from(u64::MAX).checked_add(&Number::from(1)), None);
+        assert_eq!(Number::from(i64::MIN).checked_add(&Number::from(-1)), None);
+        assert_eq!(float(f64::MAX).checked_add(&float(f64::MAX)), None);
+    }
+
+    #[test]
+    fn checked_mul() {
+        assert_eq!(Number::from(16).checked_mul(&Number::from(2)), Some(Number::from(32)));
+        assert_eq!(Number::from(-4).checked_mul(&Number::from(-4)), Some(Number::from(16)));
+        assert_eq!(Number::from(-4).checked_mul(&Number::from(4)), Some(Number::from(-16)));
+        assert_eq!(Number::from(0).checked_mul(&Number::from(i64::MIN)), Some(Number::from(0)));
+        assert_eq!(Number::from(1u64 << 32).checked_mul(&Number::from((1u64 << 32) - 1)),
+            Some(Number::from(u64::MAX - ((1u64 << 32) - 1))));
+        assert_eq!(Number::from(3).checked_mul(&float(0.5)), Some(float(1.5)));
+
+        // Overflows
+        assert_eq!(Number::from(1u64 << 32).checked_mul(&Number::from(1u64 << 32)), None);
+        assert_eq!(Number::from(i64::MIN).checked_mul(&Number::from(2)), None);
+        assert_eq!(Number::from(u64::MAX).checked_mul(&Number::from(-1)), None);
+        assert_eq!(float(1e300).checked_mul(&Number::from(1u64 << 40)), None);
+    }
+
+    #[test]
+    fn saturating_to_i64() {
+        assert_eq!(Number::from(42).saturating_to_i64(), 42);
+        assert_eq!(Number::from(-42).saturating_to_i64(), -42);
+        assert_eq!(Number::from(u64::MAX).saturating_to_i64(), i64::MAX);
+        assert_eq!(float(-2.75).saturating_to_i64(), -2);
+        assert_eq!(float(1e30).saturating_to_i64(), i64::MAX);
+        assert_eq!(float(-1e30).saturating_to_i64(), i64::MIN);
+    }
+
+    #[test]
+    fn eq_primitives() {
+        assert!(Number::from(5) == 5);
+        assert!(5 == Number::from(5u8));
+        assert!(Number::from(-5) == -5);
+        assert!(float(5.0) == 5);
+        assert!(Number::from(u64::MAX) != -1);
+        assert!(Number::from(5) != 6);
+
+        assert!(float(0.5) == 0.5);
+        assert!(Number::from(5) == 5.0);
+        assert!(0.5 == float(0.5));
+        assert!(float(0.1).checked_add(&float(0.2)).unwrap() != 0.3);
+        assert!(Number::from(u64::MAX) != u64::MAX as f64);
+        assert!(float(0.0) == -0.0);
+        assert_ne!(float(0.0), f64::NAN);
+        assert!(Number::from(u64::MAX) != f64::INFINITY);
+    }
+
+    #[test]
+    fn cmp_primitives() {
+        assert!(Number::from(5) > 4);
+        assert!(Number::from(5) <= 5);
+        assert!(float(5.0) >= 5);
+        assert!(float(4.5) < 5);
+        assert!(-6 < Number::from(-5));
+        assert!(Number::from(u64::MAX) > i64::MAX);
+
+        assert!(Number::from(5) > 4.5);
+        assert!(Number::from(5) <= 5.0);
+        assert!(0.25 < float(0.5));
+        assert_eq!(Number::from(5).partial_cmp(&5.0), Some(Ordering::Equal));
+        assert_eq!(Number::from(5).partial_cmp(&f64::NAN), None);
+        assert_eq!(Number::from(5).partial_cmp(&f64::INFINITY), None);
+    }
+
+    #[test]
+    fn mixed_ordering() {
+        assert!(Number::from(-5) < Number::from(3));
+        assert!(Number::from(-5) < float(0.0));
+        assert!(Number::from(-5) < float(-4.5));
+        assert!(float(-5.5) < Number::from(-5));
+        assert!(float(2.5) < Number::from(3u64));
+        assert!(Number::from(3u64) < float(3.5));
+        assert!(Number::from(u64::MAX) < float(1e20));
+        assert!(float(-1e19) < Number::from(i64::MIN));
+
+        // Equal values are ordered integers first
+        assert!(Number::from(3) < float(3.0));
+        assert!(float(-3.0) > Number::from(-3));
+        assert_ne!(Number::from(3), float(3.0));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Number::from(0u8).to_string(), "0");
+        assert_eq!(Number::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Number::from(i64::MIN).to_string(), "-9223372036854775808");
+        assert_eq!(Number::from(-1).to_string(), "-1");
+        assert_eq!(float(10.12).to_string(), "10.12");
+        assert_eq!(float(1.0).to_string(), "1.0");
+        assert_eq!(float(-0.5).to_string(), "-0.5");
+        assert_eq!(float(1e300).to_string(), "1e300");
+        assert_eq!(format!("port {}", Number::from(8000u16)), "port 8000");
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |number: &Number| {
+            let mut hasher = DefaultHasher::new();
+            number.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(hash(&Number::from(1u8)), hash(&Number::from(1i64)));
+        assert_eq!(hash(&Number::from(-1)), hash(&Number::from(-1i8)));
+        assert_eq!(hash(&float(0.5)), hash(&float(0.5)));
+        assert_eq!(float(-0.0), float(0.0));
+        assert_eq!(hash(&float(-0.0)), hash(&float(0.0)));
+
+        // Integers and floats are never equal
+        assert_ne!(hash(&Number::from(1u8)), hash(&float(1.0)));
+        assert_ne!(hash(&Number::from(-1)), hash(&float(-1.0)));
+        assert_ne!(hash(&Number::from(1u8)), hash(&Number::from(2u8)));
+    }
+
+    #[test]
+    fn ordering_across_variants() {
+        // 2^53, from which floats no longer hold every integer
+        const EXACT: u64 = 1 << 53;
+
+        let cases = vec![
+            // PosInt and PosInt, NegInt and NegInt, Float and Float
+            (Number::from(1u8), Number::from(2u64), Ordering::Less),
+            (Number::from(-2), Number::from(-1), Ordering::Less),
+            (float(2.0), float(10.0), Ordering::Less),
+            (float(-10.0), float(-2.0), Ordering::Less),
+            (float(0.5), float(0.25), Ordering::Greater),
+
+            // PosInt and NegInt
+            (Number::from(0u64), Number::from(-1), Ordering::Greater),
+            (Number::from(u64::MAX), Number::from(i64::MIN), Ordering::Greater),
+
+            // PosInt and Float
+            (Number::from(1u8), float(1e9), Ordering::Less),
+            (Number::from(1u8), float(0.5), Ordering::Greater),
+            (Number::from(0u64), float(-0.5), Ordering::Greater),
+            (Number::from(EXACT + 1), float(EXACT as f64), Ordering::Greater),
+            (Number::from(EXACT - 1), float(EXACT as f64), Ordering::Less),
+            (Number::from(u64::MAX), float(18_446_744_073_709_551_616.0), Ordering::Less),
+            (Number::from(u64::MAX), float(1.8e19), Ordering::Greater),
+
+            // NegInt and Float
+            (Number::from(-1), float(-0.5), Ordering::Less),
+            (Number::from(-1), float(0.0), Ordering::Less),
+            (Number::from(-1), float(-1.5), Ordering::Greater),
+            (Number::from(-(EXACT as i64) - 1), float(-(EXACT as f64)), Ordering::Less),
+            (Number::from(i64::MIN), float(-1e19), Ordering::Greater),
+            (Number::from(i64::MIN + 1), float(-9_223_372_036_854_775_808.0), Ordering::Greater),
+
+            // Equal values, integers first
+            (Number::from(0u64), float(0.0), Ordering::Less),
+            (Number::from(EXACT), float(EXACT as f64), Ordering::Less),
+            (Number::from(i64::MIN), float(-9_223_372_036_854_775_808.0), Ordering::Less),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(a.cmp(&b), expected, "{:?} <=> {:?}", a, b);
+            assert_eq!(b.cmp(&a), expected.reverse(), "{:?} <=> {:?}", b, a);
+            assert_eq!(a.cmp(&a), Ordering::Equal, "{:?} <=> {:?}", a, a);
+        }
+
+        // Maps keyed by numbers keep them in numerical order
+        let set = vec![float(10.0), Number::from(-1), float(2.0), Number::from(1u8), float(1e9)]
+            .into_iter()
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![
+            Number::from(-1), Number::from(1u8), float(2.0), float(10.0), float(1e9),
+        ]);
+    }
+
+    #[test]
+    fn sorted() {
+        let numbers = vec![
+            float(10.0), Number::from(-5), float(0.0), Number::from(3u64),
+            float(-7.25), Number::from(i64::MIN), float(2.0),
+            Number::from(u64::MAX), float(1e1), Number::from(10),
+        ];
+
+        // Every pair must agree with the numerical order
+        for a in &numbers {
+            for b in &numbers {
+                let expected = a.as_f64().unwrap().partial_cmp(&b.as_f64().unwrap()).unwrap();
+
+                if expected != Ordering::Equal {
+                    assert_eq!(a.cmp(b), expected, "{:?} <=> {:?}", a, b);
+                }
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} <=> {:?}", a, b);
+            }
+        }
+
+        let mut sorted = numbers.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![
+            Number::from(i64::MIN), float(-7.25), Number::from(-5), float(0.0),
+            float(2.0), Number::from(3u64), Number::from(10), float(10.0), float(1e1),
+            Number::from(u64::MAX),
+        ]);
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn arbitrary_precision() {
+        let digits = "1234567890123456789012345678901234567890";
+        let big = Number::try_from(&digits.parse::<serde_json::Number>().unwrap()).unwrap();
+        let negative = Number::try_from(&format!("-{}", digits).parse::<serde_json::Number>().unwrap()).unwrap();
+        let next = Number::try_from(&"1234567890123456789012345678901234567891".parse::<serde_json::Number>().unwrap()).unwrap();
+
+        // The digits are kept
+        assert_eq!(serde_json::Number::from(&big).to_string(), digits);
+        assert_eq!(format!("{:?}", big), format!("Number({})", digits));
+        assert!(!big.is_u64() && !big.is_i64() && !big.is_f64());
+        assert_eq!(big.as_u64(), None);
+        assert_eq!(big.as_f64(), Some(1.2345678901234568e39));
+
+        // Big integers are compared exactly
+        assert!(big.numeric_eq(&big.clone()));
+        assert!(!big.numeric_eq(&next));
+        assert_ne!(big, next);
+        assert!(big < next);
+        assert!(negative < i64::MIN);
+        assert!(Number::from(u64::MAX) < big);
+        assert!(big < float(1e40));
+        assert!(big < float(1.2345678901234568e39));
+        assert!(!big.numeric_eq(&float(1.2345678901234568e39)));
+
+        let number = Number::try_from(&"0.1000000000000000000001".parse::<serde_json::Number>().unwrap()).unwrap();
+        assert_eq!(serde_json::Number::from(&number).to_string(), "0.1000000000000000000001");
+        assert_eq!(number.as_f64(), Some(0.1));
+        assert!(Number::try_from(&"1e400".parse::<serde_json::Number>().unwrap()).is_err());
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn serialize_arbitrary_precision() {
+        let digits = "1234567890123456789012345678901234567890";
+        let big = Number::try_from(&digits.parse::<serde_json::Number>().unwrap()).unwrap();
+
+        // serde_json keeps the digits
+        assert_eq!(serde_json::to_string(&big).unwrap(), digits);
+        assert_eq!(serde_json::to_value(&big).unwrap().to_string(), digits);
+
+        // Other serializers get the rounded float once converted
+        let yaml = serde_yaml::to_string(&serde_yaml::Number::from(&big)).unwrap();
+        assert_eq!(serde_yaml::from_str::<f64>(&yaml).unwrap(), 1.2345678901234568e39);
+        assert_eq!(serde_yaml::to_string(&Number::from(3306)).unwrap(), serde_yaml::to_string(&3306).unwrap());
+        assert_eq!(serde_yaml::to_string(&float(0.5)).unwrap(), serde_yaml::to_string(&0.5).unwrap());
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn big_integers_and_floats() {
+        let big = |digits: &str| Number::try_from(&digits.parse::<serde_json::Number>().unwrap()).unwrap();
+        // 2^70, which f64 holds exactly, and its neighbours, rounded to it
+        let power = float(2f64.powi(70));
+        let below = big("1180591620717411303423");
+        let exact = big("1180591620717411303424");
+        let above = big("1180591620717411303425");
+
+        assert_eq!(below.as_f64(), power.as_f64());
+        assert_eq!(above.as_f64(), power.as_f64());
+        assert!(below < power);
+        assert!(power > below);
+        assert!(exact < power);
+        assert!(power > exact);
+        assert!(above > power);
+        assert!(power < above);
+        assert!(!below.numeric_eq(&power));
+        assert!(exact.numeric_eq(&power));
+        assert!(power.numeric_eq(&exact));
+        assert!(!above.numeric_eq(&power));
+        assert!(!power.numeric_eq(&above));
+
+        let power = float(-(2f64.powi(70)));
+        let below = big("-1180591620717411303425");
+        let exact = big("-1180591620717411303424");
+        let above = big("-1180591620717411303423");
+
+        assert!(below < power);
+        assert!(exact < power);
+        assert!(above > power);
+        assert!(exact.numeric_eq(&power));
+        assert!(!above.numeric_eq(&power));
+
+        // Beyond i64, next to the bounds, and next to floats with a fraction
+        let min = big("-9223372036854775809");
+        assert_eq!(min.as_f64(), Some(-9_223_372_036_854_775_808.0));
+        assert!(min < i64::MIN);
+        assert!(min < float(-9_223_372_036_854_775_808.0));
+        assert!(!min.numeric_eq(&float(-9_223_372_036_854_775_808.0)));
+        assert!(min < float(-0.5));
+        assert!(big("18446744073709551616") > float(0.5));
+        assert!(big("18446744073709551616").numeric_eq(&float(18_446_744_073_709_551_616.0)));
+    }
+
+    #[test]
+    fn try_from_json() {
+        let number = serde_json::Number::from(u64::MAX);
+        assert_eq!(Number::try_from(&number).unwrap(), Number::from(u64::MAX));
+
+        let number = serde_json::Number::from(i64::MIN);
+        assert_eq!(Number::try_from(&number).unwrap(), Number::from(i64::MIN));
+
+        let number = serde_json::Number::from_f64(-0.5).expect("failed to create json number");
+        assert_eq!(Number::try_from(&number).unwrap(), float(-0.5));
+    }
+
+    #[test]
+    fn try_from_yaml() {
+        let number = serde_yaml::Number::from(u64::MAX);
+        assert_eq!(Number::try_from(&number).unwrap(), Number::from(u64::MAX));
+
+        let number = serde_yaml::Number::from(i64::MIN);
+        assert_eq!(Number::try_from(&number).unwrap(), Number::from(i64::MIN));
+
+        for f in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = Number::try_from(&serde_yaml::Number::from(*f))
+                .expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        }
+    }
+}
\ No newline at end of file
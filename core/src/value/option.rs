@@ -0,0 +1,115 @@
+use std::borrow::Borrow;
+
+use super::Value;
+
+/// Array and object helpers for an optional `Value`, such as the one returned
+/// by [`Configuration::get`], so that they can be called without unwrapping
+/// it first.
+///
+/// A missing value behaves as a scalar: it is empty, and contains nothing.
+///
+/// ```rust
+/// use rocket_config::{OptionValueExt, Value};
+///
+/// let hosts = Some(Value::from(vec!["first", "second"]));
+/// assert_eq!(hosts.len(), 2);
+/// assert_eq!(hosts.first(), Some(&Value::from("first")));
+/// assert!(hosts.contains(&Value::from("second")));
+///
+/// let missing: Option<Value> = None;
+/// assert!(missing.is_empty());
+/// ```
+///
+/// [`Configuration::get`]: struct.Configuration.html#method.get
+pub trait OptionValueExt {
+    /// See [`Value::len`](enum.Value.html#method.len).
+    fn len(&self) -> usize;
+
+    /// See [`Value::is_empty`](enum.Value.html#method.is_empty).
+    fn is_empty(&self) -> bool;
+
+    /// See [`Value::first`](enum.Value.html#method.first).
+    fn first(&self) -> Option<&Value>;
+
+    /// See [`Value::last`](enum.Value.html#method.last).
+    fn last(&self) -> Option<&Value>;
+
+    /// See [`Value::contains`](enum.Value.html#method.contains).
+    fn contains(&self, needle: &Value) -> bool;
+
+    /// See [`Value::array_contains`](enum.Value.html#method.array_contains).
+    fn array_contains(&self, needle: &Value) -> bool;
+
+    /// See [`Value::array_contains_str`](enum.Value.html#method.array_contains_str).
+    fn array_contains_str(&self, s: &str) -> bool;
+
+    /// See [`Value::has_key`](enum.Value.html#method.has_key).
+    fn has_key(&self, key: &str) -> bool;
+}
+
+impl<V: Borrow<Value>> OptionValueExt for Option<V> {
+    fn len(&self) -> usize {
+        self.as_ref().map_or(0, |value| value.borrow().len())
+    }
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn first(&self) -> Option<&Value> {
+        self.as_ref().and_then(|value| value.borrow().first())
+    }
+    fn last(&self) -> Option<&Value> {
+        self.as_ref().and_then(|value| value.borrow().last())
+    }
+    fn contains(&self, needle: &Value) -> bool {
+        self.as_ref().is_some_and(|value| value.borrow().contains(needle))
+    }
+    fn array_contains(&self, needle: &Value) -> bool {
+        self.as_ref().is_some_and(|value| value.borrow().array_contains(needle))
+    }
+    fn array_contains_str(&self, s: &str) -> bool {
+        self.as_ref().is_some_and(|value| value.borrow().array_contains_str(s))
+    }
+    fn has_key(&self, key: &str) -> bool {
+        self.as_ref().is_some_and(|value| value.borrow().has_key(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OptionValueExt;
+    use crate::value::Value;
+
+    #[test]
+    fn option_value_ext() {
+        let value = Value::from(&json!({"hosts": ["first", "second"], "port": 3306}));
+
+        let hosts = value.get("hosts").cloned();
+        assert_eq!(hosts.len(), 2);
+        assert!(!hosts.is_empty());
+        assert_eq!(hosts.first(), Some(&Value::from("first")));
+        assert_eq!(hosts.last(), Some(&Value::from("second")));
+        assert!(hosts.contains(&Value::from("second")));
+        assert!(hosts.array_contains(&Value::from("first")));
+        assert!(hosts.array_contains_str("second"));
+        assert!(!hosts.has_key("first"));
+
+        // Borrowed values
+        assert_eq!(value.get("port").len(), 0);
+        assert!(Some(&value).contains(&Value::from("port")));
+
+        // Missing values
+        let missing = value.get("users");
+        assert_eq!(missing.len(), 0);
+        assert!(missing.is_empty());
+        assert_eq!(missing.first(), None);
+        assert_eq!(missing.last(), None);
+        assert!(!missing.contains(&Value::Null));
+        assert!(!missing.array_contains(&Value::Null));
+        assert!(!missing.array_contains_str(""));
+        assert!(!missing.has_key(""));
+
+        // Nested values
+        assert!(value.get_path("hosts").array_contains_str("first"));
+        assert!(Some(&value).has_key("port"));
+    }
+}
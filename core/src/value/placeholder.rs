@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use super::Value;
+use super::value::join_path;
+use crate::error;
+use crate::result;
+
+/// How many placeholders may be nested in the values the resolver returns,
+/// which stops references that end up referring to themselves.
+const MAX_DEPTH: usize = 16;
+
+/// Starts the references of environment placeholders, such as
+/// `%env(DATABASE_URL)%`, which are left to [`Value::resolve_env`].
+///
+/// [`Value::resolve_env`]: enum.Value.html#method.resolve_env
+const ENV_PREFIX: &str = "env(";
+
+/// Why a string could not be resolved.
+#[derive(Debug, PartialEq)]
+enum Failure {
+    /// The resolver knows nothing about this reference.
+    Missing(String),
+    /// The placeholders are nested deeper than `MAX_DEPTH`.
+    TooDeep,
+}
+
+/// Replaces the placeholders of every string found in `value`, which is
+/// located at the dotted `path`.
+pub(crate) fn resolve(value: &mut Value, path: &str, resolver: &dyn Fn(&str) -> Option<String>)
+    -> result::Result<()>
+{
+    match value {
+        Value::String(s) => {
+            *s = substitute(s, resolver, 0).map_err(|failure| match failure {
+                Failure::Missing(reference) => error::Error::new(
+                    error::ErrorKind::MissingValue,
+                    format!("unresolved placeholder '%{}%' in '{}'", reference, path)
+                ),
+                Failure::TooDeep => error::Error::new(
+                    error::ErrorKind::FormatError,
+                    format!("placeholders nested deeper than {} levels in '{}'", MAX_DEPTH, path)
+                ),
+            })?;
+        },
+        Value::Array(array) => {
+            for (index, each) in Arc::make_mut(array).iter_mut().enumerate() {
+                resolve(each, &join_path(path, &index.to_string()), resolver)?;
+            }
+        },
+        Value::Object(map) => {
+            for (key, each) in Arc::make_mut(map).iter_mut() {
+                resolve(each, &join_path(path, key), resolver)?;
+            }
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+/// Replaces the `%reference%` placeholders of `input` by what `resolver`
+/// returns for them, whose own placeholders are replaced in turn.
+///
+/// `%%` is a literal percent sign, as is a `%` that does not start a
+/// placeholder: references are not empty and hold no whitespace.
+/// Environment placeholders, such as `%env(DATABASE_URL)%`, are kept as they
+/// are.
+fn substitute(input: &str, resolver: &dyn Fn(&str) -> Option<String>, depth: usize)
+    -> Result<String, Failure>
+{
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('%') {
+        output.push_str(&rest[..start]);
+
+        let after_start = &rest[start + 1..];
+        if let Some(after_escape) = after_start.strip_prefix('%') {
+            output.push('%');
+            rest = after_escape;
+            continue;
+        }
+
+        match after_start.find('%') {
+            Some(end) if end > 0 && !after_start[..end].contains(char::is_whitespace) => {
+                if depth >= MAX_DEPTH {
+                    return Err(Failure::TooDeep);
+                }
+
+                let reference = &after_start[..end];
+                if reference.starts_with(ENV_PREFIX) && reference.ends_with(')') {
+                    output.push_str(&rest[start..start + end + 2]);
+                    rest = &after_start[end + 1..];
+                    continue;
+                }
+
+                let resolved = resolver(reference)
+                    .ok_or_else(|| Failure::Missing(reference.to_owned()))?;
+
+                output.push_str(&substitute(&resolved, resolver, depth + 1)?);
+                rest = &after_start[end + 1..];
+            },
+            _ => {
+                output.push('%');
+                rest = after_start;
+            },
+        }
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{substitute, Failure, MAX_DEPTH};
+
+    fn resolver(reference: &str) -> Option<String> {
+        match reference {
+            "database.host" => Some("localhost".to_owned()),
+            "database.url" => Some("mysql://%database.host%:3306".to_owned()),
+            "loop" => Some("%loop%".to_owned()),
+            "percent" => Some("100%%".to_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn substitute_references() {
+        assert_eq!(substitute("%database.host%", &resolver, 0), Ok("localhost".to_owned()));
+        assert_eq!(
+            substitute("url=%database.url%/app", &resolver, 0),
+            Ok("url=mysql://localhost:3306/app".to_owned())
+        );
+        assert_eq!(
+            substitute("%database.host%%database.host%", &resolver, 0),
+            Ok("localhostlocalhost".to_owned())
+        );
+        assert_eq!(substitute("%percent%", &resolver, 0), Ok("100%".to_owned()));
+    }
+
+    #[test]
+    fn env_placeholders() {
+        assert_eq!(
+            substitute("%env(resolve:DATABASE_URL)%", &resolver, 0),
+            Ok("%env(resolve:DATABASE_URL)%".to_owned())
+        );
+        assert_eq!(
+            substitute("%env(DATABASE_URL:-sqlite://)%/%database.host%", &resolver, 0),
+            Ok("%env(DATABASE_URL:-sqlite://)%/localhost".to_owned())
+        );
+        assert_eq!(
+            substitute("%env%", &resolver, 0),
+            Err(Failure::Missing("env".to_owned()))
+        );
+    }
+
+    #[test]
+    fn literal_percents() {
+        assert_eq!(substitute("100%%", &resolver, 0), Ok("100%".to_owned()));
+        assert_eq!(substitute("%%database.host%%", &resolver, 0), Ok("%database.host%".to_owned()));
+        assert_eq!(substitute("50% of 80% load", &resolver, 0), Ok("50% of 80% load".to_owned()));
+        assert_eq!(substitute("100%", &resolver, 0), Ok("100%".to_owned()));
+        assert_eq!(substitute("plain text", &resolver, 0), Ok("plain text".to_owned()));
+    }
+
+    #[test]
+    fn failures() {
+        assert_eq!(
+            substitute("%database.port%", &resolver, 0),
+            Err(Failure::Missing("database.port".to_owned()))
+        );
+        assert_eq!(substitute("%loop%", &resolver, 0), Err(Failure::TooDeep));
+        assert_eq!(substitute("%database.host%", &resolver, MAX_DEPTH), Err(Failure::TooDeep));
+    }
+}
@@ -0,0 +1,394 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use super::Value;
+use super::index::Type;
+use crate::error;
+use crate::result;
+
+/// A function applied to every value reaching it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Keys,
+    Length,
+    First,
+    Type,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "keys" => Some(Function::Keys),
+            "length" => Some(Function::Length),
+            "first" => Some(Function::First),
+            "type" => Some(Function::Type),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Function::Keys => "keys",
+            Function::Length => "length",
+            Function::First => "first",
+            Function::Type => "type",
+        }
+    }
+
+    /// Applies the function to `value`, or returns `None` when it does not
+    /// apply to values of its type.
+    fn apply(self, value: &Value) -> Option<Value> {
+        match (self, value) {
+            (Function::Keys, Value::Object(map)) => {
+                let mut keys: Vec<_> = map.keys().collect();
+                keys.sort();
+                Some(Value::Array(Arc::new(keys.into_iter().map(|key| Value::from(key.as_str())).collect())))
+            },
+            (Function::Keys, Value::Array(list)) => Some(Value::Array(Arc::new((0..list.len()).map(Value::from).collect()))),
+            (Function::Length, Value::Null) => Some(Value::from(0usize)),
+            (Function::Length, Value::String(s)) => Some(Value::from(s.chars().count())),
+            (Function::Length, Value::Array(list)) => Some(Value::from(list.len())),
+            (Function::Length, Value::Object(map)) => Some(Value::from(map.len())),
+            (Function::First, Value::Array(list)) => Some(list.first().cloned().unwrap_or(Value::Null)),
+            (Function::Type, value) => Some(Value::from(Type(value).to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// A step of a query, applied in turn to every value the previous steps
+/// yielded.
+#[derive(Debug, PartialEq)]
+enum Step {
+    /// Looks a key up in objects, or an index up in arrays when it is one.
+    Key(String),
+    /// Looks an index up in arrays.
+    Index(usize),
+    /// Yields the elements of arrays and the values of objects.
+    Wildcard,
+    /// Applies a function, found at the given offset of the query.
+    Function(Function, usize),
+}
+
+impl Step {
+    /// Returns the values `node` leads to through the step, which is not a
+    /// function.
+    fn children<'a>(&self, node: &'a Value) -> Vec<&'a Value> {
+        match (self, node) {
+            (Step::Key(key), Value::Array(list)) => key.parse::<usize>().ok()
+                .and_then(|index| list.get(index))
+                .into_iter()
+                .collect(),
+            (Step::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+            (Step::Index(index), Value::Array(list)) => list.get(*index).into_iter().collect(),
+            (Step::Wildcard, Value::Array(list)) => list.iter().collect(),
+            (Step::Wildcard, Value::Object(map)) => map.values().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Reads a query, tracking the byte offset reached for error messages.
+struct Parser<'a> {
+    expression: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.expression[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.offset += character.len_utf8();
+        Some(character)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn error(&self, offset: usize, message: &str) -> error::Error {
+        error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("{} at offset {} in query '{}'", message, offset, self.expression)
+        )
+    }
+
+    /// Fails on the character found at the current offset.
+    fn unexpected(&self) -> error::Error {
+        match self.peek() {
+            Some(character) => self.error(self.offset, &format!("unexpected '{}'", character)),
+            None => self.error(self.offset, "unexpected end"),
+        }
+    }
+
+    /// Reads a key, honoring backslash escapes. Also returns whether the key
+    /// was written without escapes, as only those name functions.
+    fn key(&mut self) -> result::Result<(String, bool)> {
+        let mut key = String::new();
+        let mut plain = true;
+
+        while let Some(character) = self.peek() {
+            match character {
+                '.' | '[' | ']' | '|' => break,
+                _ if character.is_whitespace() => break,
+                '\\' => {
+                    self.bump();
+                    match self.bump() {
+                        Some(escaped) => key.push(escaped),
+                        None => return Err(self.error(self.offset, "unfinished escape")),
+                    }
+                    plain = false;
+                },
+                _ => {
+                    key.push(character);
+                    self.bump();
+                },
+            }
+        }
+
+        if key.is_empty() && plain {
+            return Err(self.unexpected());
+        }
+        Ok((key, plain))
+    }
+
+    /// Reads a `[index]` or `[*]` subscript.
+    fn subscript(&mut self) -> result::Result<Step> {
+        self.bump();
+        self.skip_whitespace();
+
+        let start = self.offset;
+        let step = match self.peek() {
+            Some('*') => {
+                self.bump();
+                Step::Wildcard
+            },
+            Some(character) if character.is_ascii_digit() => {
+                while self.peek().is_some_and(|character| character.is_ascii_digit()) {
+                    self.bump();
+                }
+                self.expression[start..self.offset].parse()
+                    .map(Step::Index)
+                    .map_err(|_| self.error(start, "index out of range"))?
+            },
+            _ => return Err(self.error(start, "expected an index or '*'")),
+        };
+
+        self.skip_whitespace();
+        match self.peek() {
+            Some(']') => {
+                self.bump();
+                Ok(step)
+            },
+            _ => Err(self.error(self.offset, "expected ']'")),
+        }
+    }
+
+    /// Reads a stage, a path or a function, into `steps`.
+    fn stage(&mut self, steps: &mut Vec<Step>) -> result::Result<()> {
+        let start = self.offset;
+
+        match self.peek() {
+            Some('.') => {
+                self.bump();
+                // A lone `.` is the value itself
+                if self.peek().map_or(true, |character| character == '|' || character.is_whitespace()) {
+                    return Ok(());
+                }
+                if self.peek() != Some('[') {
+                    steps.push(Step::Key(self.key()?.0));
+                }
+            },
+            Some('[') => {},
+            _ => {
+                let (key, plain) = self.key()?;
+
+                let function = Function::from_name(&key).filter(|_| plain);
+                match function {
+                    Some(function) if !matches!(self.peek(), Some('.') | Some('[')) => {
+                        steps.push(Step::Function(function, start));
+                        return Ok(());
+                    },
+                    _ => steps.push(Step::Key(key)),
+                }
+            },
+        }
+
+        loop {
+            match self.peek() {
+                Some('.') => {
+                    self.bump();
+                    steps.push(Step::Key(self.key()?.0));
+                },
+                Some('[') => steps.push(self.subscript()?),
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Parses `expression` into the steps it is made of, in order.
+fn parse(expression: &str) -> result::Result<Vec<Step>> {
+    let mut parser = Parser { expression, offset: 0 };
+    let mut steps = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        parser.stage(&mut steps)?;
+        parser.skip_whitespace();
+
+        match parser.peek() {
+            Some('|') => parser.bump(),
+            None => return Ok(steps),
+            Some(_) => return Err(parser.unexpected()),
+        };
+    }
+}
+
+/// Evaluates the query `expression` against `value`, as described by
+/// [`Value::query`].
+///
+/// [`Value::query`]: enum.Value.html#method.query
+pub(crate) fn query(value: &Value, expression: &str) -> result::Result<Vec<Value>> {
+    let steps = parse(expression)?;
+    let mut nodes = vec![Cow::Borrowed(value)];
+
+    for step in &steps {
+        let mut next = Vec::with_capacity(nodes.len());
+
+        for node in nodes {
+            match step {
+                Step::Function(function, offset) => {
+                    let applied = function.apply(&node).ok_or_else(|| error::Error::new(
+                        error::ErrorKind::FormatError,
+                        format!(
+                            "'{}' at offset {} in query '{}' cannot be applied to a {} value",
+                            function.name(), offset, expression, Type(&node)
+                        )
+                    ))?;
+                    next.push(Cow::Owned(applied));
+                },
+                step => match node {
+                    Cow::Borrowed(node) => next.extend(step.children(node).into_iter().map(Cow::Borrowed)),
+                    Cow::Owned(node) => next.extend(step.children(&node).into_iter().cloned().map(Cow::Owned)),
+                },
+            }
+        }
+        nodes = next;
+    }
+
+    Ok(nodes.into_iter().map(Cow::into_owned).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error;
+    use crate::value::Value;
+    use super::{parse, Function, Step};
+
+    #[test]
+    fn parse_steps() {
+        assert_eq!(parse("diesel.servers[*].host").unwrap(), vec![
+            Step::Key("diesel".to_owned()),
+            Step::Key("servers".to_owned()),
+            Step::Wildcard,
+            Step::Key("host".to_owned()),
+        ]);
+        assert_eq!(parse(" .servers[ 1 ] | keys ").unwrap(), vec![
+            Step::Key("servers".to_owned()),
+            Step::Index(1),
+            Step::Function(Function::Keys, 17),
+        ]);
+        assert_eq!(parse("env\\.local.keys").unwrap(), vec![
+            Step::Key("env.local".to_owned()),
+            Step::Key("keys".to_owned()),
+        ]);
+        assert_eq!(parse(".").unwrap(), vec![]);
+        assert_eq!(parse(".[0]").unwrap(), vec![Step::Index(0)]);
+    }
+
+    #[test]
+    fn query() {
+        let value = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "driver": "mysql",
+                    "default_table_options": {"collate": "utf8_unicode_ci", "charset": "utf8"},
+                },
+                "servers": [
+                    {"host": "first", "port": 3306},
+                    {"host": "second"},
+                    "third",
+                ],
+                "env.local": {"debug": true},
+                "keys": [],
+            },
+        }));
+        let examples = vec![
+            (".", vec![value.clone()]),
+            ("diesel.dbal.driver", vec![Value::from("mysql")]),
+            (".diesel.dbal.driver", vec![Value::from("mysql")]),
+            ("diesel.dbal.default_table_options | keys", vec![Value::from(&json!(["charset", "collate"]))]),
+            ("diesel.servers[*].host", vec![Value::from("first"), Value::from("second")]),
+            ("diesel.servers[0].port", vec![Value::from(3306)]),
+            ("diesel.servers.1.host", vec![Value::from("second")]),
+            ("diesel.servers[*] | type", vec![Value::from("object"), Value::from("object"), Value::from("string")]),
+            ("diesel.servers | length", vec![Value::from(3)]),
+            ("diesel.servers | first | keys | length", vec![Value::from(2)]),
+            ("diesel.servers[2] | length", vec![Value::from(5)]),
+            ("diesel.servers | keys", vec![Value::from(&json!([0, 1, 2]))]),
+            ("diesel.env\\.local[*]", vec![Value::from(true)]),
+            ("diesel | keys | first", vec![Value::from("dbal")]),
+            ("diesel.keys | first", vec![Value::Null]),
+            ("diesel.env\\.local.debug", vec![Value::from(true)]),
+            ("diesel | servers[1] | host", vec![Value::from("second")]),
+            ("diesel.missing | length", vec![]),
+            ("diesel.dbal.driver.name", vec![]),
+            ("diesel.servers[3]", vec![]),
+            ("type", vec![Value::from("object")]),
+        ];
+
+        for (expression, expected) in examples {
+            assert_eq!(value.query(expression).unwrap(), expected, "query '{}'", expression);
+        }
+    }
+
+    #[test]
+    fn query_errors() {
+        let value = Value::from(&json!({
+            "diesel": {
+                "dbal": {"driver": "mysql"},
+                "servers": [{"host": "first", "port": 3306}],
+            },
+        }));
+        let examples = vec![
+            ("", "unexpected end at offset 0 in query ''"),
+            ("diesel..dbal", "unexpected '.' at offset 7 in query 'diesel..dbal'"),
+            ("diesel.servers[", "expected an index or '*' at offset 15 in query 'diesel.servers['"),
+            ("diesel.servers[1", "expected ']' at offset 16 in query 'diesel.servers[1'"),
+            ("diesel.servers[x]", "expected an index or '*' at offset 15 in query 'diesel.servers[x]'"),
+            ("diesel |", "unexpected end at offset 8 in query 'diesel |'"),
+            ("diesel keys", "unexpected 'k' at offset 7 in query 'diesel keys'"),
+            ("diesel]", "unexpected ']' at offset 6 in query 'diesel]'"),
+            ("env\\", "unfinished escape at offset 4 in query 'env\\'"),
+            (
+                "diesel.dbal.driver | keys",
+                "'keys' at offset 21 in query 'diesel.dbal.driver | keys' cannot be applied to a string value",
+            ),
+            (
+                "diesel.servers[0].port | length",
+                "'length' at offset 25 in query 'diesel.servers[0].port | length' cannot be applied to a number value",
+            ),
+        ];
+
+        for (expression, message) in examples {
+            let err = value.query(expression).expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), error::ErrorKind::FormatError);
+            assert_eq!(err.to_string(), message, "query '{}'", expression);
+        }
+    }
+}
@@ -0,0 +1,624 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+use serde::ser::{self, Impossible, Serialize};
+use super::{Map, Value};
+use crate::error;
+
+impl ser::Error for error::Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        error::Error::new(error::ErrorKind::SerializeError, msg.to_string())
+    }
+}
+
+/// Serializer whose output is a `Value`.
+pub(crate) struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = error::Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, value: bool) -> Result<Value, Self::Error> {
+        Ok(Value::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Value, Self::Error> {
+        if let Ok(value) = u64::try_from(value) {
+            Ok(Value::from(value))
+        } else if let Ok(value) = i64::try_from(value) {
+            Ok(Value::from(value))
+        } else {
+            Err(ser::Error::custom(format!("number {} is out of range", value)))
+        }
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Value, Self::Error> {
+        u64::try_from(value)
+            .map(Value::from)
+            .map_err(|_| ser::Error::custom(format!("number {} is out of range", value)))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Value, Self::Error> {
+        self.serialize_f64(f64::from(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Value, Self::Error> {
+        // Non-finite floats are serialized as null
+        Ok(Value::from(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Value, Self::Error> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Value, Self::Error> {
+        Ok(Value::from(value))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value, Self::Error> {
+        Ok(Value::Array(Arc::new(value.iter().map(|&byte| Value::from(byte)).collect())))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut map = Map::new();
+        map.insert(variant.to_owned(), value.serialize(Serializer)?);
+        Ok(Value::Object(Arc::new(map)))
+    }
+
+    fn serialize_none(self) -> Result<Value, Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeVec { vec: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTupleVariant {
+            name: variant.to_owned(),
+            vec: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeMap { map: Map::new(), next_key: None })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeStructVariant {
+            name: variant.to_owned(),
+            map: Map::new(),
+        })
+    }
+}
+
+pub(crate) struct SerializeVec {
+    vec: Vec<Value>,
+}
+
+pub(crate) struct SerializeTupleVariant {
+    name: String,
+    vec: Vec<Value>,
+}
+
+pub(crate) struct SerializeMap {
+    map: Map<String, Value>,
+    next_key: Option<String>,
+}
+
+pub(crate) struct SerializeStructVariant {
+    name: String,
+    map: Map<String, Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Array(Arc::new(self.vec)))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        let mut object = Map::new();
+        object.insert(self.name, Value::Array(Arc::new(self.vec)));
+        Ok(Value::Object(Arc::new(object)))
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.next_key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.next_key.take().ok_or_else(|| {
+            <error::Error as ser::Error>::custom("serialize_value called before serialize_key")
+        })?;
+        self.map.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Object(Arc::new(self.map)))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Value;
+    type Error = error::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.map.insert(key.to_owned(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        let mut object = Map::new();
+        object.insert(self.name, Value::Object(Arc::new(self.map)));
+        Ok(Value::Object(Arc::new(object)))
+    }
+}
+
+/// Serializer of object keys, which only accepts strings.
+struct MapKeySerializer;
+
+fn key_must_be_a_string(found: &str) -> error::Error {
+    error::Error::new(
+        error::ErrorKind::SerializeError,
+        format!("map keys must be strings, found {}", found)
+    )
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = error::Error;
+
+    type SerializeSeq = Impossible<String, error::Error>;
+    type SerializeTuple = Impossible<String, error::Error>;
+    type SerializeTupleStruct = Impossible<String, error::Error>;
+    type SerializeTupleVariant = Impossible<String, error::Error>;
+    type SerializeMap = Impossible<String, error::Error>;
+    type SerializeStruct = Impossible<String, error::Error>;
+    type SerializeStructVariant = Impossible<String, error::Error>;
+
+    fn serialize_str(self, value: &str) -> Result<String, Self::Error> {
+        Ok(value.to_owned())
+    }
+
+    fn serialize_char(self, value: char) -> Result<String, Self::Error> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Self::Error> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _value: bool) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("a boolean"))
+    }
+
+    fn serialize_i8(self, _value: i8) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_i16(self, _value: i16) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_i32(self, _value: i32) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_i64(self, _value: i64) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_u8(self, _value: u8) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_u16(self, _value: u16) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_u32(self, _value: u32) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_u64(self, _value: u64) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an integer"))
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("a float"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("a float"))
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("bytes"))
+    }
+
+    fn serialize_unit(self) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("a unit"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("a unit struct"))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string("an enum variant"))
+    }
+
+    fn serialize_none(self) -> Result<String, Self::Error> {
+        Err(key_must_be_a_string("an option"))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<String, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string("an option"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(key_must_be_a_string("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(key_must_be_a_string("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(key_must_be_a_string("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(key_must_be_a_string("an enum variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(key_must_be_a_string("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(key_must_be_a_string("a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(key_must_be_a_string("an enum variant"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use serde::{Deserialize, Serialize};
+    use crate::error;
+    use crate::value::Value;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum Driver {
+        Sqlite,
+        Mysql { host: String },
+        Pgsql(u16),
+        Replicated(String, String),
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Dbal {
+        driver: Driver,
+        server_version: f64,
+        port: u16,
+        offset: i32,
+        password: Option<String>,
+        options: Vec<String>,
+        tags: BTreeMap<String, bool>,
+    }
+
+    #[test]
+    fn from_serialize() {
+        let dbal = Dbal {
+            driver: Driver::Pgsql(5432),
+            server_version: 5.7,
+            port: 3306,
+            offset: -2,
+            password: None,
+            options: vec!["charset=utf8".to_owned()],
+            tags: vec![("primary".to_owned(), true)].into_iter().collect(),
+        };
+
+        assert_eq!(Value::from_serialize(&dbal).unwrap(), value!({
+            "driver": { "Pgsql": 5432 },
+            "server_version": 5.7,
+            "port": 3306,
+            "offset": -2,
+            "password": null,
+            "options": ["charset=utf8"],
+            "tags": { "primary": true }
+        }));
+    }
+
+    #[test]
+    fn round_trip() {
+        let drivers = vec![
+            Driver::Sqlite,
+            Driver::Mysql { host: "localhost".to_owned() },
+            Driver::Pgsql(5432),
+            Driver::Replicated("primary".to_owned(), "replica".to_owned()),
+        ];
+
+        for driver in drivers {
+            let dbal = Dbal {
+                driver,
+                server_version: 10.12,
+                port: 3306,
+                offset: i32::MIN,
+                password: Some("secret".to_owned()),
+                options: Vec::new(),
+                tags: BTreeMap::new(),
+            };
+
+            let value = Value::from_serialize(&dbal).expect("failed to serialize dbal");
+            assert_eq!(value.deserialize_into::<Dbal>().unwrap(), dbal);
+        }
+    }
+
+    #[test]
+    fn non_string_keys() {
+        let mut map = HashMap::new();
+        map.insert(42, "answer");
+
+        let err = Value::from_serialize(&map).expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::SerializeError);
+        assert_eq!(err.to_string(), "map keys must be strings, found an integer");
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Value::from_serialize(&u128::from(u64::MAX)).unwrap(), value!(u64::MAX));
+        assert_eq!(Value::from_serialize(&-1i128).unwrap(), value!(-1));
+        assert!(Value::from_serialize(&u128::MAX).is_err());
+        assert_eq!(Value::from_serialize(&f64::NAN).unwrap(), Value::Null);
+        assert_eq!(Value::from_serialize(&1.5f32).unwrap(), value!(1.5));
+    }
+}
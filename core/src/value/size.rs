@@ -0,0 +1,109 @@
+use std::mem::size_of;
+use super::{Map, Value};
+
+/// Approximates the bookkeeping of a map entry, on top of its key and value:
+/// the share of node pointers and lengths of a BTreeMap, or the hash and
+/// index of an IndexMap.
+const MAP_ENTRY_OVERHEAD: usize = 2 * size_of::<usize>();
+
+/// The reference counts heading the shared allocation of an array or object.
+const SHARED_OVERHEAD: usize = 2 * size_of::<usize>();
+
+/// Estimates the bytes used by `value`, itself included, as described by
+/// [`Value::estimated_size`].
+///
+/// The traversal keeps its own stack, so that deeply nested values do not
+/// overflow the call stack.
+///
+/// [`Value::estimated_size`]: enum.Value.html#method.estimated_size
+pub(crate) fn estimated_size(value: &Value) -> usize {
+    let mut size = size_of::<Value>();
+    let mut stack = vec![value];
+
+    while let Some(node) = stack.pop() {
+        size += match node {
+            Value::Number(number) => number.heap_size(),
+            Value::String(s) => s.capacity(),
+            Value::Array(list) => {
+                stack.extend(list.iter());
+                SHARED_OVERHEAD + size_of::<Vec<Value>>() + list.capacity() * size_of::<Value>()
+            },
+            Value::Object(map) => {
+                stack.extend(map.values());
+                SHARED_OVERHEAD + size_of::<Map<String, Value>>() + map.keys()
+                    .map(|key| size_of::<String>() + key.capacity() + size_of::<Value>() + MAP_ENTRY_OVERHEAD)
+                    .sum::<usize>()
+            },
+            Value::Null | Value::Bool(_) => 0,
+        };
+    }
+
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+    use std::sync::Arc;
+    use crate::value::Value;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(Value::Null.estimated_size(), size_of::<Value>());
+        assert_eq!(Value::from(true).estimated_size(), size_of::<Value>());
+        #[cfg(not(feature = "arbitrary_precision"))]
+        assert_eq!(Value::from(3306).estimated_size(), size_of::<Value>());
+        assert_eq!(
+            Value::String(String::with_capacity(64)).estimated_size(),
+            size_of::<Value>() + 64
+        );
+        assert_eq!(
+            Value::Array(Arc::new(Vec::with_capacity(4))).estimated_size(),
+            size_of::<Value>() * 5 + super::SHARED_OVERHEAD + size_of::<Vec<Value>>()
+        );
+    }
+
+    #[test]
+    fn grows_with_the_document() {
+        let sizes: Vec<_> = [
+            json!({}),
+            json!({"servers": []}),
+            json!({"servers": [{"host": "first", "port": 3306}]}),
+            json!({"servers": [{"host": "first", "port": 3306}, {"host": "second", "port": 3306}]}),
+            json!({"servers": [
+                {"host": "first", "port": 3306, "replicas": ["first", "second"]},
+                {"host": "second", "port": 3306, "replicas": ["first", "second"]},
+            ]}),
+        ].iter()
+            .map(|json| Value::from(json).estimated_size())
+            .collect();
+
+        for pair in sizes.windows(2) {
+            assert!(pair[0] < pair[1], "sizes are not increasing: {:?}", sizes);
+        }
+
+        // Longer strings and more keys weigh more
+        let small = Value::from(&json!({"driver": "mysql"}));
+        let longer = Value::from(&json!({"driver": "mysql-with-a-longer-name"}));
+        let wider = Value::from(&json!({"driver": "mysql", "charset": "utf8"}));
+        assert!(small.estimated_size() < longer.estimated_size());
+        assert!(small.estimated_size() < wider.estimated_size());
+    }
+
+    #[test]
+    fn deeply_nested() {
+        const DEPTH: usize = 10_000;
+
+        let mut value = Value::Null;
+        for _ in 0..DEPTH {
+            value = Value::from(vec![value]);
+        }
+
+        assert!(value.estimated_size() >= (DEPTH + 1) * size_of::<Value>());
+
+        // Dropping the value at once would recurse as deep as it is nested
+        while let Value::Array(mut list) = value {
+            value = Arc::make_mut(&mut list).pop().unwrap_or(Value::Null);
+        }
+    }
+}
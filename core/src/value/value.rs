@@ -1,17 +1,54 @@
 #![allow(dead_code)]
 
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::{Bound, RangeBounds};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use super::bytes;
+use super::diff::{self, ArrayDiffStrategy, DiffEntry};
+use super::dump;
+use super::entry::Entry;
+use super::env;
+use super::flatten::{self, StringMapOptions};
+use super::merge::{self, MergeStrategy};
 use super::number::Number;
-use super::index::Index;
+use super::placeholder;
+use super::query;
+use super::redact;
+use super::ser;
+use super::size;
+use super::walk;
+use super::index::{Index, Type};
+use super::iter::{ValueIter, ValueIterMut};
+use super::map::{self, Map};
+use crate::constants;
+use crate::error;
+use crate::result;
 
 /// The Value enum, a loosely typed way of representing any valid value.
 ///
 /// It is used to contains the parsing result of [serde_json] or [serde_yaml].
 ///
+/// Arrays and objects are shared behind an `Arc`, so that cloning a `Value`
+/// is O(1) whatever its size. They are copied on write: the methods giving
+/// mutable access to an array or an object, such as [`as_array_mut`] or
+/// [`as_object_mut`], first copy it if it is shared with another `Value`,
+/// so that changes never affect the clones.
+///
 /// [serde_json]: https://docs.serde.rs/serde_json/
 /// [serde_yaml]: https://docs.serde.rs/serde_yaml/
-#[derive(Clone, PartialEq, PartialOrd)]
+/// [`as_array_mut`]: #method.as_array_mut
+/// [`as_object_mut`]: #method.as_object_mut
+#[derive(Clone, PartialEq)]
 pub enum Value {
     /// Represents a null value.
     Null,
@@ -25,11 +62,76 @@ pub enum Value {
     /// Represents a string.
     String(String),
 
-    /// Represents an array.
-    Array(Vec<Value>),
+    /// Represents an array, shared copy-on-write.
+    Array(Arc<Vec<Value>>),
+
+    /// Represents an object, shared copy-on-write.
+    Object(Arc<Map<String, Value>>),
+}
+
+/// Returns the content of `shared`, only cloned when another `Value` shares
+/// it.
+pub(crate) fn unshare<T: Clone>(shared: Arc<T>) -> T {
+    Arc::try_unwrap(shared).unwrap_or_else(|shared| (*shared).clone())
+}
+
+impl Value {
+    /// Position of the variant, used to order values of different kinds.
+    fn variant_index(&self) -> u8 {
+        match *self {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+/// Values are totally ordered: values of different kinds are ordered by their
+/// variant, `Null < Bool < Number < String < Array < Object`. Arrays are
+/// compared lexicographically, and objects entry by entry, in key order,
+/// whatever the order of their keys.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => map::sorted(a).cmp(&map::sorted(b)),
+            _ => self.variant_index().cmp(&other.variant_index()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-    /// Represents an object.
-    Object(BTreeMap<String, Value>),
+/// Values are hashed consistently with their equality: objects are hashed
+/// entry by entry, in key order, whatever the order of their keys, and
+/// numbers as described by the `Hash` implementation of [`Number`].
+///
+/// [`Number`]: struct.Number.html
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.variant_index().hash(state);
+        match self {
+            Value::Null => {},
+            Value::Bool(b) => b.hash(state),
+            Value::Number(n) => n.hash(state),
+            Value::String(s) => s.hash(state),
+            Value::Array(list) => list.hash(state),
+            Value::Object(map) => map::sorted(map).hash(state),
+        }
+    }
 }
 
 impl Debug for Value {
@@ -45,6 +147,148 @@ impl Debug for Value {
     }
 }
 
+/// Writes `value` as a quoted JSON string, escaping what RFC 8259 requires.
+fn format_escaped_str(formatter: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    formatter.write_str("\"")?;
+
+    let mut start = 0;
+    for (index, character) in value.char_indices() {
+        let escape = match character {
+            '"'         => "\\\"",
+            '\\'        => "\\\\",
+            '\n'        => "\\n",
+            '\r'        => "\\r",
+            '\t'        => "\\t",
+            '\u{08}'    => "\\b",
+            '\u{0c}'    => "\\f",
+            c if c < '\u{20}' => {
+                formatter.write_str(&value[start..index])?;
+                write!(formatter, "\\u{:04x}", c as u32)?;
+                start = index + character.len_utf8();
+                continue;
+            },
+            _ => continue,
+        };
+
+        formatter.write_str(&value[start..index])?;
+        formatter.write_str(escape)?;
+        start = index + character.len_utf8();
+    }
+    formatter.write_str(&value[start..])?;
+
+    formatter.write_str("\"")
+}
+
+/// Writes `value` as JSON text indented by two spaces per level, `depth` being
+/// the level `value` sits at.
+fn format_pretty(formatter: &mut fmt::Formatter<'_>, value: &Value, depth: usize) -> fmt::Result {
+    const INDENT: &str = "  ";
+
+    match *value {
+        Value::Array(ref array) if !array.is_empty() => {
+            formatter.write_str("[\n")?;
+            for (index, each) in array.iter().enumerate() {
+                if index > 0 {
+                    formatter.write_str(",\n")?;
+                }
+                formatter.write_str(&INDENT.repeat(depth + 1))?;
+                format_pretty(formatter, each, depth + 1)?;
+            }
+            formatter.write_str("\n")?;
+            formatter.write_str(&INDENT.repeat(depth))?;
+            formatter.write_str("]")
+        },
+        Value::Object(ref map) if !map.is_empty() => {
+            formatter.write_str("{\n")?;
+            for (index, (key, each)) in map.iter().enumerate() {
+                if index > 0 {
+                    formatter.write_str(",\n")?;
+                }
+                formatter.write_str(&INDENT.repeat(depth + 1))?;
+                format_escaped_str(formatter, key)?;
+                formatter.write_str(": ")?;
+                format_pretty(formatter, each, depth + 1)?;
+            }
+            formatter.write_str("\n")?;
+            formatter.write_str(&INDENT.repeat(depth))?;
+            formatter.write_str("}")
+        },
+        _ => write!(formatter, "{}", value),
+    }
+}
+
+/// Formats the `Value` as compact JSON text, or as indented JSON text with the
+/// alternate flag (`{:#}`).
+///
+/// Strings are escaped, integers are written without any fractional part and
+/// floating point numbers keep theirs, so the output can be fed back into any
+/// JSON parser.
+impl fmt::Display for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if formatter.alternate() {
+            return format_pretty(formatter, self, 0);
+        }
+
+        match *self {
+            Value::Null => formatter.write_str("null"),
+            Value::Bool(b) => write!(formatter, "{}", b),
+            Value::Number(ref n) => fmt::Display::fmt(n, formatter),
+            Value::String(ref s) => format_escaped_str(formatter, s),
+            Value::Array(ref array) => {
+                formatter.write_str("[")?;
+                for (index, each) in array.iter().enumerate() {
+                    if index > 0 {
+                        formatter.write_str(",")?;
+                    }
+                    fmt::Display::fmt(each, formatter)?;
+                }
+                formatter.write_str("]")
+            },
+            Value::Object(ref map) => {
+                formatter.write_str("{")?;
+                for (index, (key, each)) in map.iter().enumerate() {
+                    if index > 0 {
+                        formatter.write_str(",")?;
+                    }
+                    format_escaped_str(formatter, key)?;
+                    formatter.write_str(":")?;
+                    fmt::Display::fmt(each, formatter)?;
+                }
+                formatter.write_str("}")
+            },
+        }
+    }
+}
+
+impl Serialize for Value {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(b),
+            Value::Number(ref n) => n.serialize(serializer),
+            Value::String(ref s) => serializer.serialize_str(s),
+            Value::Array(ref v) => {
+                let mut seq = serializer.serialize_seq(Some(v.len()))?;
+                for element in v.iter() {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Object(ref m) => {
+                let mut map = serializer.serialize_map(Some(m.len()))?;
+                for (k, v) in m.iter() {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
 impl Value {
     /// Index into an array or map. A string index can be used to access a
     /// value in a map, and a usize index can be used to access an element of an
@@ -70,6 +314,271 @@ impl Value {
         index.index_into_mut(self)
     }
 
+    /// Looks `key` up in the map, ignoring case: `"database_url"` finds the
+    /// `DATABASE_URL` and `Database_URL` keys alike.
+    ///
+    /// When several keys match, the first one in the order of the map wins,
+    /// that is the first in sorted order (uppercase letters sorting before
+    /// lowercase ones), or in file order with the `preserve_order` feature.
+    /// Returns `None` if `self` is not a map or no key matches.
+    pub fn get_ignore_case(&self, key: &str) -> Option<&Self> {
+        let key = key.to_lowercase();
+
+        self.as_object()?
+            .iter()
+            .find(|(each, _)| each.to_lowercase() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Lowercases the keys of every map found in the `Value`, recursively.
+    ///
+    /// Fails with an error of kind `FormatError`, leaving the `Value`
+    /// untouched, when two keys of a map only differ by case.
+    pub fn lowercase_keys(&mut self) -> result::Result<()> {
+        self.check_lowercase_keys("")?;
+        self.apply_lowercase_keys();
+
+        Ok(())
+    }
+
+    fn check_lowercase_keys(&self, path: &str) -> result::Result<()> {
+        match *self {
+            Self::Array(ref array) => {
+                for (index, each) in array.iter().enumerate() {
+                    each.check_lowercase_keys(&join_path(path, &index.to_string()))?;
+                }
+            },
+            Self::Object(ref map) => {
+                let mut lowercased = BTreeMap::new();
+
+                for (key, each) in map.iter() {
+                    if let Some(other) = lowercased.insert(key.to_lowercase(), key) {
+                        return Err(error::Error::new(
+                            error::ErrorKind::FormatError,
+                            format!("keys '{}' and '{}' collide at '{}'", other, key, path)
+                        ));
+                    }
+                    each.check_lowercase_keys(&join_path(path, key))?;
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn apply_lowercase_keys(&mut self) {
+        match *self {
+            Self::Array(ref mut array) => {
+                for each in Arc::make_mut(array).iter_mut() {
+                    each.apply_lowercase_keys();
+                }
+            },
+            Self::Object(ref mut map) => {
+                *map = Arc::new(unshare(std::mem::take(map)).into_iter()
+                    .map(|(key, mut each)| {
+                        each.apply_lowercase_keys();
+                        (key.to_lowercase(), each)
+                    })
+                    .collect());
+            },
+            _ => {},
+        }
+    }
+
+    /// Returns the value of the first of `keys` present in the map, skipping
+    /// the keys holding null. Useful to fall back to legacy keys, like
+    /// `value.get_first(&["database_url", "db_url"])`.
+    ///
+    /// Returns `None` if `self` is not a map, or if none of `keys` holds a
+    /// non-null value.
+    pub fn get_first<'a>(&'a self, keys: &[&str]) -> Option<&'a Self> {
+        keys.iter().filter_map(|key| self.get(key)).find(|value| !value.is_null())
+    }
+
+    /// Same as [`get_first`], but a key holding null is returned as any
+    /// other.
+    ///
+    /// [`get_first`]: #method.get_first
+    pub fn get_first_including_null<'a>(&'a self, keys: &[&str]) -> Option<&'a Self> {
+        keys.iter().find_map(|key| self.get(key))
+    }
+
+    /// Looks up a value by a JSON Pointer.
+    ///
+    /// JSON Pointer defines a string syntax for identifying a specific value
+    /// within a JavaScript Object Notation (JSON) document.
+    ///
+    /// A Pointer is a Unicode string with the reference tokens separated by
+    /// `/`. Inside tokens `/` is replaced by `~1` and `~` is replaced by `~0`.
+    /// The addressed value is returned and if there is no such value `None`
+    /// is returned. An empty pointer addresses the whole value.
+    ///
+    /// For more information read [RFC6901](https://tools.ietf.org/html/rfc6901).
+    pub fn pointer(&self, pointer: &str) -> Option<&Self> {
+        pointer_tokens(pointer)?
+            .into_iter()
+            .try_fold(self, |target, token| match target {
+                Self::Object(map) => map.get(&token),
+                Self::Array(list) => parse_index(&token).and_then(|x| list.get(x)),
+                _ => None,
+            })
+    }
+
+    /// Looks up a value by a JSON Pointer and returns a mutable reference to
+    /// that value.
+    ///
+    /// See [`pointer`] for the pointer syntax. Returns `None` when the pointer
+    /// is invalid, when any segment is missing, or when a segment tries to
+    /// index into a scalar.
+    ///
+    /// [`pointer`]: #method.pointer
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Self> {
+        pointer_tokens(pointer)?
+            .into_iter()
+            .try_fold(self, |target, token| match target {
+                Self::Object(map) => Arc::make_mut(map).get_mut(&token),
+                Self::Array(list) => parse_index(&token).and_then(move |x| Arc::make_mut(list).get_mut(x)),
+                _ => None,
+            })
+    }
+
+    /// Looks up a nested value using a dotted path such as
+    /// `"diesel.dbal.driver"`.
+    ///
+    /// Each segment is used like the index given to [`get`]: it is a key when
+    /// the current value is an object, and an array index when the current
+    /// value is an array and the segment is a number (`"servers.0.host"`).
+    /// Keys that contain a dot must escape it with a backslash
+    /// (`"env\\.local.url"` looks up the `url` key of the `env.local`
+    /// object), and a literal backslash is written `\\\\`.
+    ///
+    /// Returns `None` when any segment is missing, when an array index is out
+    /// of bounds, or when a segment tries to index into a scalar.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_path(&self, path: &str) -> Option<&Self> {
+        split_path(path).iter().try_fold(self, |target, segment| match target {
+            Self::Array(list) => segment.parse::<usize>().ok().and_then(|x| list.get(x)),
+            _ => target.get(segment),
+        })
+    }
+
+    /// Inserts `value` at a dotted path, as understood by [`get_path`], and
+    /// returns the value it replaced, if any.
+    ///
+    /// Missing intermediate values, and null ones, are created as objects.
+    /// A numeric segment indexes into an array, and appends to it when equal
+    /// to its length.
+    ///
+    /// Fails with an error of kind `FormatError` when a segment indexes into
+    /// a scalar, or into an array with an index greater than its length.
+    ///
+    /// [`get_path`]: #method.get_path
+    pub fn insert_at(&mut self, path: &str, value: Self) -> result::Result<Option<Self>> {
+        let segments = split_path(path);
+        let (last, parents) = segments.split_last().expect("a path has at least one segment");
+        let target = parents.iter().try_fold(self, |target, segment| {
+            if target.is_array() {
+                let list = target.as_array_mut().expect("expected an array");
+                let index = insertion_index(list, segment)?;
+
+                if index == list.len() {
+                    list.push(Self::Null);
+                }
+                Ok(&mut list[index])
+            }
+            else {
+                target.try_entry(segment.as_str()).map(|entry| entry.or_insert(Self::Null))
+            }
+        })?;
+
+        if target.is_null() {
+            *target = Self::Object(Arc::default());
+        }
+        match target {
+            Self::Object(map) => Ok(Arc::make_mut(map).insert(last.clone(), value)),
+            Self::Array(list) => {
+                let list = Arc::make_mut(list);
+                let index = insertion_index(list, last)?;
+
+                if index == list.len() {
+                    list.push(value);
+                    Ok(None)
+                }
+                else {
+                    Ok(Some(std::mem::replace(&mut list[index], value)))
+                }
+            },
+            _ => Err(error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("cannot access key {:?} in JSON {}", last, Type(target))
+            )),
+        }
+    }
+
+    /// Removes the value at a dotted path, as understood by [`get_path`], and
+    /// returns it.
+    ///
+    /// Returns `None` when any segment is missing, when an array index is out
+    /// of bounds, or when a segment tries to index into a scalar.
+    ///
+    /// [`get_path`]: #method.get_path
+    pub fn remove_at(&mut self, path: &str) -> Option<Self> {
+        let segments = split_path(path);
+        let (last, parents) = segments.split_last()?;
+        let target = parents.iter().try_fold(self, |target, segment| match target {
+            Self::Array(list) => segment.parse::<usize>().ok().and_then(move |x| Arc::make_mut(list).get_mut(x)),
+            _ => target.get_mut(segment.as_str()),
+        })?;
+
+        match target {
+            Self::Object(map) => map::remove(Arc::make_mut(map), last),
+            Self::Array(list) => last.parse::<usize>().ok()
+                .filter(|&x| x < list.len())
+                .map(|x| Arc::make_mut(list).remove(x)),
+            _ => None,
+        }
+    }
+
+    /// Gets the entry of the given key in an object, for in-place
+    /// manipulation.
+    ///
+    /// A null `Value` is treated as an empty object and replaced by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Value` is neither an object nor null. See [`try_entry`]
+    /// for a fallible variant.
+    ///
+    /// [`try_entry`]: #method.try_entry
+    pub fn entry<S: Into<String>>(&mut self, key: S) -> Entry<'_> {
+        let key = key.into();
+
+        if !self.is_object() && !self.is_null() {
+            panic!("cannot access key {:?} in JSON {}", key, Type(self));
+        }
+        self.try_entry(key).expect("expected an object")
+    }
+
+    /// Same as [`entry`], but fails with an error of kind `FormatError` when
+    /// the `Value` is neither an object nor null.
+    ///
+    /// [`entry`]: #method.entry
+    pub fn try_entry<S: Into<String>>(&mut self, key: S) -> result::Result<Entry<'_>> {
+        let key = key.into();
+
+        if self.is_null() {
+            *self = Self::Object(Arc::default());
+        }
+        match *self {
+            Self::Object(ref mut map) => Ok(Entry::new(Arc::make_mut(map).entry(key))),
+            _ => Err(error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("cannot access key {:?} in JSON {}", key, Type(self))
+            )),
+        }
+    }
+
     /// Returns true if the `Value` is an Object. Returns false otherwise.
     ///
     /// For any Value on which `is_object` returns true, `as_object` and
@@ -81,7 +590,7 @@ impl Value {
 
     /// If the `Value` is an Object, returns the associated Map. Returns None
     /// otherwise.
-    pub fn as_object(&self) -> Option<&BTreeMap<String, Self>> {
+    pub fn as_object(&self) -> Option<&Map<String, Self>> {
         match *self {
             Self::Object(ref map) => Some(map),
             _ => None,
@@ -90,9 +599,9 @@ impl Value {
 
     /// If the `Value` is an Object, returns the associated mutable Map.
     /// Returns None otherwise.
-    pub fn as_object_mut(&mut self) -> Option<&mut BTreeMap<String, Self>> {
+    pub fn as_object_mut(&mut self) -> Option<&mut Map<String, Self>> {
         match *self {
-            Self::Object(ref mut map) => Some(map),
+            Self::Object(ref mut map) => Some(Arc::make_mut(map)),
             _ => None,
         }
     }
@@ -119,11 +628,173 @@ impl Value {
     /// Returns None otherwise.
     pub fn as_array_mut(&mut self) -> Option<&mut Vec<Self>> {
         match *self {
-            Self::Array(ref mut list) => Some(list),
+            Self::Array(ref mut list) => Some(Arc::make_mut(list)),
             _ => None,
         }
     }
 
+    /// If the `Value` is an Array, returns its elements within `range`, e.g.
+    /// `value.slice(0..2)` for the first two. Returns None otherwise.
+    ///
+    /// Bounds past the end of the array are clamped to its length, so that
+    /// such a range gives fewer elements, or none, instead of panicking.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Option<&[Self]> {
+        let list = self.as_array()?;
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => list.len(),
+        }.min(list.len());
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        }.min(end);
+
+        Some(&list[start..end])
+    }
+
+    /// Returns the number of elements of an Array, or of entries of an
+    /// Object. Returns 0 for any other value, strings included.
+    pub fn len(&self) -> usize {
+        match *self {
+            Self::Array(ref list) => list.len(),
+            Self::Object(ref map) => map.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if [`len`] is 0, which is the case of empty arrays and
+    /// objects, and of any other value.
+    ///
+    /// [`len`]: #method.len
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// If the `Value` is a non-empty Array, returns its first element.
+    /// Returns None otherwise.
+    pub fn first(&self) -> Option<&Self> {
+        self.as_array().and_then(|list| list.first())
+    }
+
+    /// If the `Value` is a non-empty Array, returns its last element. Returns
+    /// None otherwise.
+    pub fn last(&self) -> Option<&Self> {
+        self.as_array().and_then(|list| list.last())
+    }
+
+    /// Tells whether the `Value` contains `needle`:
+    ///
+    ///   * an Array contains the elements equal to it,
+    ///   * an Object contains the Strings naming one of its keys,
+    ///   * a String contains the Strings found within it.
+    ///
+    /// Any other value contains nothing.
+    pub fn contains(&self, needle: &Self) -> bool {
+        match (self, needle) {
+            (Self::Array(list), needle) => list.contains(needle),
+            (Self::Object(map), Self::String(key)) => map.contains_key(key),
+            (Self::String(s), Self::String(pattern)) => s.contains(pattern.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the `Value` is an Array holding an element equal to
+    /// `needle`. Returns false otherwise.
+    pub fn array_contains(&self, needle: &Self) -> bool {
+        self.as_array().is_some_and(|list| list.contains(needle))
+    }
+
+    /// Returns true if the `Value` is an Array holding the String `s`, e.g.
+    /// to check a driver against `enabled_drivers: [mysql, postgres]`.
+    /// Returns false otherwise.
+    pub fn array_contains_str(&self, s: &str) -> bool {
+        self.as_array().is_some_and(|list| list.iter().any(|each| each.as_str() == Some(s)))
+    }
+
+    /// If the `Value` is an Object, returns its keys, in sorted order, or in
+    /// file order with the `preserve_order` feature. Returns an empty list
+    /// otherwise.
+    pub fn keys(&self) -> Vec<&str> {
+        self.as_object()
+            .map(|map| map.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the `Value` is an Object holding `key`. Returns false
+    /// otherwise.
+    pub fn has_key(&self, key: &str) -> bool {
+        self.as_object().is_some_and(|map| map.contains_key(key))
+    }
+
+    /// If the `Value` is an Object, returns an iterator over its entries, in
+    /// key order, or in file order with the `preserve_order` feature. Returns
+    /// None otherwise.
+    pub fn entries(&self) -> Option<impl Iterator<Item = (&String, &Self)>> {
+        self.as_object().map(|map| map.iter())
+    }
+
+    /// If the `Value` is an Object, returns an iterator over its entries, with
+    /// mutable values, in the order of [`entries`]. Returns None otherwise.
+    ///
+    /// [`entries`]: #method.entries
+    pub fn entries_mut(&mut self) -> Option<impl Iterator<Item = (&String, &mut Self)>> {
+        self.as_object_mut().map(|map| map.iter_mut())
+    }
+
+    /// If the `Value` is an Array, returns an iterator over its elements.
+    /// Returns None otherwise.
+    pub fn elements(&self) -> Option<impl Iterator<Item = &Self>> {
+        self.as_array().map(|vec| vec.iter())
+    }
+
+    /// If the `Value` is an Array, returns an iterator over its mutable
+    /// elements. Returns None otherwise.
+    pub fn elements_mut(&mut self) -> Option<impl Iterator<Item = &mut Self>> {
+        self.as_array_mut().map(|vec| vec.iter_mut())
+    }
+
+    /// Returns an iterator over the elements of an Array or the entries of an
+    /// Object.
+    ///
+    /// Items are `(key, value)` pairs, where `key` is `None` for the elements
+    /// of an Array. Iterating any other kind of `Value` yields nothing.
+    pub fn iter(&self) -> ValueIter<'_> {
+        ValueIter::new(self)
+    }
+
+    /// Same as [`iter`], with mutable values.
+    ///
+    /// [`iter`]: #method.iter
+    pub fn iter_mut(&mut self) -> ValueIterMut<'_> {
+        ValueIterMut::new(self)
+    }
+
+    /// Returns an iterator over the elements of an Array. The iterator is
+    /// empty if the `Value` is not an Array.
+    pub fn iter_array(&self) -> impl Iterator<Item = &Self> {
+        self.elements().into_iter().flatten()
+    }
+
+    /// Returns an iterator over the mutable elements of an Array. The
+    /// iterator is empty if the `Value` is not an Array.
+    pub fn iter_array_mut(&mut self) -> impl Iterator<Item = &mut Self> {
+        self.elements_mut().into_iter().flatten()
+    }
+
+    /// Returns an iterator over the entries of an Object. The iterator is
+    /// empty if the `Value` is not an Object.
+    pub fn iter_object(&self) -> impl Iterator<Item = (&String, &Self)> {
+        self.entries().into_iter().flatten()
+    }
+
+    /// Returns an iterator over the entries of an Object, with mutable
+    /// values. The iterator is empty if the `Value` is not an Object.
+    pub fn iter_object_mut(&mut self) -> impl Iterator<Item = (&String, &mut Self)> {
+        self.entries_mut().into_iter().flatten()
+    }
+
     /// Returns true if the `Value` is a String. Returns false otherwise.
     ///
     /// For any Value on which `is_string` returns true, `as_str` is guaranteed
@@ -213,6 +884,133 @@ impl Value {
         }
     }
 
+    /// Same as [`as_i64`], but also parses a String holding an integer, as
+    /// described by [`try_as_i64_lenient`].
+    ///
+    /// [`as_i64`]: #method.as_i64
+    /// [`try_as_i64_lenient`]: #method.try_as_i64_lenient
+    pub fn as_i64_lenient(&self) -> Option<i64> {
+        self.try_as_i64_lenient().ok()
+    }
+
+    /// Same as [`as_i64`], but also parses a String holding an integer.
+    ///
+    /// Surrounding whitespace is ignored, `_` may separate the digits, like in
+    /// `"1_000"`, and the prefixes `0x`, `0o` and `0b` introduce hexadecimal,
+    /// octal and binary integers, like in `"-0xff"`. Fails with an error of
+    /// kind `FormatError` when the `Value` is neither, or when the integer
+    /// does not fit in an i64.
+    ///
+    /// [`as_i64`]: #method.as_i64
+    pub fn try_as_i64_lenient(&self) -> result::Result<i64> {
+        self.try_lenient("an i64", Number::as_i64, |s| {
+            let (negative, magnitude) = parse_integer(s)?;
+            let integer = if negative {
+                0i64.checked_sub_unsigned(magnitude)
+            }
+            else {
+                i64::try_from(magnitude).ok()
+            };
+
+            integer.ok_or_else(|| "number out of range".to_owned())
+        })
+    }
+
+    /// Same as [`as_u64`], but also parses a String holding an integer, as
+    /// described by [`try_as_i64_lenient`].
+    ///
+    /// [`as_u64`]: #method.as_u64
+    /// [`try_as_i64_lenient`]: #method.try_as_i64_lenient
+    pub fn as_u64_lenient(&self) -> Option<u64> {
+        self.try_as_u64_lenient().ok()
+    }
+
+    /// Same as [`try_as_i64_lenient`], for u64.
+    ///
+    /// [`try_as_i64_lenient`]: #method.try_as_i64_lenient
+    pub fn try_as_u64_lenient(&self) -> result::Result<u64> {
+        self.try_lenient("a u64", Number::as_u64, |s| match parse_integer(s)? {
+            (true, magnitude) if magnitude > 0 => Err("number out of range".to_owned()),
+            (_, magnitude) => Ok(magnitude),
+        })
+    }
+
+    /// Same as [`as_f64`], but also parses a String holding a number, as
+    /// described by [`try_as_f64_lenient`].
+    ///
+    /// [`as_f64`]: #method.as_f64
+    /// [`try_as_f64_lenient`]: #method.try_as_f64_lenient
+    pub fn as_f64_lenient(&self) -> Option<f64> {
+        self.try_as_f64_lenient().ok()
+    }
+
+    /// Same as [`as_f64`], but also parses a String holding a number, either
+    /// a float like `"3.14e2"` or an integer as accepted by
+    /// [`try_as_i64_lenient`].
+    ///
+    /// Fails with an error of kind `FormatError` when the `Value` is neither,
+    /// or when the number is not finite, like `"nan"`, `"inf"` or `"1e400"`.
+    ///
+    /// [`as_f64`]: #method.as_f64
+    /// [`try_as_i64_lenient`]: #method.try_as_i64_lenient
+    pub fn try_as_f64_lenient(&self) -> result::Result<f64> {
+        self.try_lenient("an f64", Number::as_f64, parse_float)
+    }
+
+    /// Converts a Number with `number`, or parses a String with `parse`.
+    fn try_lenient<T, N, P>(&self, expected: &str, number: N, parse: P) -> result::Result<T>
+    where N: FnOnce(&Number) -> Option<T>, P: FnOnce(&str) -> Result<T, String>
+    {
+        match *self {
+            Self::Number(ref n) => number(n).ok_or_else(|| error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("{} is not {}", self, expected)
+            )),
+            Self::String(ref s) => parse(s).map_err(|err| error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("'{}' is not {}: {}", s, expected, err)
+            )),
+            _ => Err(error::Error::new(
+                error::ErrorKind::FormatError,
+                format!("expected {}, found {}", expected, Type(self))
+            )),
+        }
+    }
+
+    /// Returns true if both values are numbers with the same mathematical
+    /// value, as described by [`Number::numeric_eq`]. Returns false
+    /// otherwise.
+    ///
+    /// [`Number::numeric_eq`]: struct.Number.html#method.numeric_eq
+    pub fn numeric_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(n), Self::Number(other)) => n.numeric_eq(other),
+            _ => false,
+        }
+    }
+
+    /// Compares the values like `==` does, except that numbers are compared
+    /// by their mathematical value, as described by [`numeric_eq`], at any
+    /// depth: `{"version": 5.0}` equals `{"version": 5}`.
+    ///
+    /// [`numeric_eq`]: #method.numeric_eq
+    pub fn eq_loose(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(n), Self::Number(other)) => n.numeric_eq(other),
+            (Self::Array(list), Self::Array(other)) => {
+                list.len() == other.len()
+                    && list.iter().zip(other.iter()).all(|(each, other)| each.eq_loose(other))
+            },
+            (Self::Object(map), Self::Object(other)) => {
+                map.len() == other.len()
+                    && map.iter().all(|(key, each)| {
+                        other.get(key).map_or(false, |other| each.eq_loose(other))
+                    })
+            },
+            _ => self == other,
+        }
+    }
+
     /// Returns true if the `Value` is a Boolean. Returns false otherwise.
     ///
     /// For any Value on which `is_boolean` returns true, `as_bool` is
@@ -230,414 +1028,3083 @@ impl Value {
         }
     }
 
-    /// Returns true if the `Value` is a Null. Returns false otherwise.
+    /// Interprets the `Value` as a boolean, whichever way it is spelled.
     ///
-    /// For any Value on which `is_null` returns true, `as_null` is guaranteed
-    /// to return `Some(())`.
-    pub fn is_null(&self) -> bool {
-        self.as_null().is_some()
+    /// Besides Booleans, the Numbers `1` and `0` are accepted, as are the
+    /// Strings `true`, `yes`, `on` and `1`, or `false`, `no`, `off` and `0`,
+    /// ignoring case and surrounding whitespace. Returns None for any other
+    /// value, like `2` or `"y"`.
+    pub fn as_bool_lenient(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(b) => Some(b),
+            Self::Number(ref n) => match n.as_u64() {
+                Some(1) => Some(true),
+                Some(0) => Some(false),
+                _ => None,
+            },
+            Self::String(ref s) => match s.trim().to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 
-    /// If the `Value` is a Null, returns (). Returns None otherwise.
-    pub fn as_null(&self) -> Option<()> {
+    /// Interprets the `Value` as a duration.
+    ///
+    /// A Number is a number of seconds, possibly fractional. A String is a
+    /// number followed by an optional unit among `ms`, `s`, `m`, `h` and `d`
+    /// (seconds when omitted), like `"1500ms"` or `"2.5h"`. Returns None for
+    /// negative durations and for any other value.
+    pub fn as_duration(&self) -> Option<Duration> {
         match *self {
-            Self::Null => Some(()),
+            Self::Number(ref n) => match n.as_u64() {
+                Some(seconds) => Some(Duration::from_secs(seconds)),
+                None => n.as_f64().and_then(duration_from_secs_f64),
+            },
+            Self::String(ref s) => parse_duration(s),
             _ => None,
         }
     }
 
-    /// Takes the value out of the `Value`, leaving a `Null` in its place.
-    pub fn take(&mut self) -> Self {
-        std::mem::replace(self, Self::Null)
+    /// If the `Value` is a String, returns it as a path. Returns None
+    /// otherwise.
+    pub fn as_path(&self) -> Option<PathBuf> {
+        self.as_str().map(PathBuf::from)
     }
-}
 
-/// The default value is `Value::Null`.
-///
-/// This is useful for handling omitted `Value` fields when deserializing.
-impl Default for Value {
-    fn default() -> Self {
+    /// If the `Value` is a String holding a socket address, like
+    /// `"127.0.0.1:8000"` or `"[::1]:8000"`, returns it. Returns None
+    /// otherwise.
+    pub fn as_socket_addr(&self) -> Option<SocketAddr> {
+        self.try_as_socket_addr().ok()
+    }
+
+    /// Same as [`as_socket_addr`], but fails with an error of kind
+    /// `FormatError` describing why the `Value` is not a socket address.
+    ///
+    /// [`as_socket_addr`]: #method.as_socket_addr
+    pub fn try_as_socket_addr(&self) -> result::Result<SocketAddr> {
+        self.try_parse("a socket address")
+    }
+
+    /// If the `Value` is a String holding an IPv4 or IPv6 address, returns
+    /// it. Returns None otherwise.
+    pub fn as_ip_addr(&self) -> Option<IpAddr> {
+        self.try_as_ip_addr().ok()
+    }
+
+    /// Same as [`as_ip_addr`], but fails with an error of kind `FormatError`
+    /// describing why the `Value` is not an IP address.
+    ///
+    /// [`as_ip_addr`]: #method.as_ip_addr
+    pub fn try_as_ip_addr(&self) -> result::Result<IpAddr> {
+        self.try_parse("an IP address")
+    }
+
+    /// If the `Value` is a String holding an absolute URL, returns it. Returns
+    /// None otherwise.
+    ///
+    /// Only available with the `url` feature.
+    #[cfg(feature = "url")]
+    pub fn as_url(&self) -> Option<url::Url> {
+        self.try_as_url().ok()
+    }
+
+    /// Same as [`as_url`], but fails with an error of kind `FormatError`
+    /// describing why the `Value` is not a URL.
+    ///
+    /// Only available with the `url` feature.
+    ///
+    /// [`as_url`]: #method.as_url
+    #[cfg(feature = "url")]
+    pub fn try_as_url(&self) -> result::Result<url::Url> {
+        self.try_parse("a URL")
+    }
+
+    /// If the `Value` is a String holding base64, returns the bytes it
+    /// encodes. Returns None otherwise.
+    ///
+    /// Both the standard and the URL-safe alphabets are accepted, though not
+    /// mixed, with or without padding, and whitespace is skipped. The unused
+    /// bits of the last character must be zero.
+    pub fn as_bytes_base64(&self) -> Option<Vec<u8>> {
+        self.try_as_bytes_base64().ok()
+    }
+
+    /// Same as [`as_bytes_base64`], but fails with an error of kind
+    /// `FormatError` describing why the `Value` is not base64.
+    ///
+    /// [`as_bytes_base64`]: #method.as_bytes_base64
+    pub fn try_as_bytes_base64(&self) -> result::Result<Vec<u8>> {
+        self.try_decode("base64", bytes::decode_base64)
+    }
+
+    /// If the `Value` is a String holding hexadecimal, in either case,
+    /// returns the bytes it encodes. Returns None otherwise.
+    pub fn as_bytes_hex(&self) -> Option<Vec<u8>> {
+        self.try_as_bytes_hex().ok()
+    }
+
+    /// Same as [`as_bytes_hex`], but fails with an error of kind
+    /// `FormatError` describing why the `Value` is not hexadecimal.
+    ///
+    /// [`as_bytes_hex`]: #method.as_bytes_hex
+    pub fn try_as_bytes_hex(&self) -> result::Result<Vec<u8>> {
+        self.try_decode("hexadecimal", bytes::decode_hex)
+    }
+
+    /// Decodes the String held by the `Value`. Unlike [`try_parse`], the
+    /// errors leave the string out, as it usually is a secret.
+    ///
+    /// [`try_parse`]: #method.try_parse
+    fn try_decode(&self, encoding: &str, decode: fn(&str) -> Result<Vec<u8>, String>)
+        -> result::Result<Vec<u8>>
+    {
+        let s = self.as_str().ok_or_else(|| error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("expected {} bytes, found {}", encoding, Type(self))
+        ))?;
+
+        decode(s).map_err(|err| error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("invalid {} bytes: {}", encoding, err)
+        ))
+    }
+
+    /// Parses the String held by the `Value`.
+    fn try_parse<T>(&self, expected: &str) -> result::Result<T>
+    where T: std::str::FromStr, T::Err: fmt::Display
+    {
+        let s = self.as_str().ok_or_else(|| error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("expected {}, found {}", expected, Type(self))
+        ))?;
+
+        s.parse().map_err(|err| error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("'{}' is not {}: {}", s, expected, err)
+        ))
+    }
+
+    /// Parses the String held by the `Value` with `FromStr`, so that types
+    /// such as enums implementing it can be extracted directly.
+    ///
+    /// Fails with an error of kind `FormatError` when the `Value` is not a
+    /// String, or when parsing fails.
+    pub fn parse_as<T>(&self) -> result::Result<T>
+    where T: std::str::FromStr, T::Err: fmt::Display
+    {
+        self.try_parse(std::any::type_name::<T>())
+    }
+
+    /// If the `Value` is a String, returns the associated str. Returns
+    /// `default` otherwise.
+    pub fn as_str_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.as_str().unwrap_or(default)
+    }
+
+    /// Same as [`as_i64`], but returns `default` instead of None.
+    ///
+    /// [`as_i64`]: #method.as_i64
+    pub fn as_i64_or(&self, default: i64) -> i64 {
+        self.as_i64().unwrap_or(default)
+    }
+
+    /// Same as [`as_u64`], but returns `default` instead of None.
+    ///
+    /// [`as_u64`]: #method.as_u64
+    pub fn as_u64_or(&self, default: u64) -> u64 {
+        self.as_u64().unwrap_or(default)
+    }
+
+    /// Same as [`as_f64`], but returns `default` instead of None.
+    ///
+    /// [`as_f64`]: #method.as_f64
+    pub fn as_f64_or(&self, default: f64) -> f64 {
+        self.as_f64().unwrap_or(default)
+    }
+
+    /// Same as [`as_bool`], but returns `default` instead of None.
+    ///
+    /// [`as_bool`]: #method.as_bool
+    pub fn as_bool_or(&self, default: bool) -> bool {
+        self.as_bool().unwrap_or(default)
+    }
+
+    /// Indexes into the `Value` like [`get`] and returns the str found there.
+    ///
+    /// Returns `default` when there is no value at `index` or when it is not
+    /// a String.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_str_or<'a, I: Index>(&'a self, index: I, default: &'a str) -> &'a str {
+        self.get(index).map_or(default, |value| value.as_str_or(default))
+    }
+
+    /// Indexes into the `Value` like [`get`] and returns the i64 found there.
+    ///
+    /// Returns `default` when there is no value at `index` or when it is not
+    /// an i64.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_i64_or<I: Index>(&self, index: I, default: i64) -> i64 {
+        self.get(index).map_or(default, |value| value.as_i64_or(default))
+    }
+
+    /// Indexes into the `Value` like [`get`] and returns the u64 found there.
+    ///
+    /// Returns `default` when there is no value at `index` or when it is not
+    /// a u64.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_u64_or<I: Index>(&self, index: I, default: u64) -> u64 {
+        self.get(index).map_or(default, |value| value.as_u64_or(default))
+    }
+
+    /// Indexes into the `Value` like [`get`] and returns the f64 found there.
+    ///
+    /// Returns `default` when there is no value at `index` or when it is not
+    /// a Number.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_f64_or<I: Index>(&self, index: I, default: f64) -> f64 {
+        self.get(index).map_or(default, |value| value.as_f64_or(default))
+    }
+
+    /// Indexes into the `Value` like [`get`] and returns the bool found there.
+    ///
+    /// Returns `default` when there is no value at `index` or when it is not
+    /// a Bool.
+    ///
+    /// [`get`]: #method.get
+    pub fn get_bool_or<I: Index>(&self, index: I, default: bool) -> bool {
+        self.get(index).map_or(default, |value| value.as_bool_or(default))
+    }
+
+    /// Converts every element of an array with `convert`. Returns None if the
+    /// `Value` is not an array or if `convert` rejects any element.
+    fn as_vec_of<'a, T, F>(&'a self, convert: F) -> Option<Vec<T>>
+    where F: Fn(&'a Self) -> Option<T>
+    {
+        self.as_array()?.iter().map(convert).collect()
+    }
+
+    /// Converts the elements of an array with `convert`, skipping those it
+    /// rejects. Returns None if the `Value` is not an array.
+    fn as_vec_of_lenient<'a, T, F>(&'a self, convert: F) -> Option<Vec<T>>
+    where F: Fn(&'a Self) -> Option<T>
+    {
+        Some(self.as_array()?.iter().filter_map(convert).collect())
+    }
+
+    /// If the `Value` is an array whose elements are all Strings, returns
+    /// them as `&str`. Returns None otherwise.
+    pub fn as_vec_of_str(&self) -> Option<Vec<&str>> {
+        self.as_vec_of(Self::as_str)
+    }
+
+    /// Same as [`as_vec_of_str`], but skips the elements which are not Strings
+    /// instead of returning None.
+    ///
+    /// [`as_vec_of_str`]: #method.as_vec_of_str
+    pub fn as_vec_of_str_lenient(&self) -> Option<Vec<&str>> {
+        self.as_vec_of_lenient(Self::as_str)
+    }
+
+    /// If the `Value` is an array whose elements are all Strings, returns
+    /// them as owned `String`s. Returns None otherwise.
+    pub fn as_vec_of_string(&self) -> Option<Vec<String>> {
+        self.as_vec_of(|value| value.as_str().map(str::to_owned))
+    }
+
+    /// Same as [`as_vec_of_string`], but skips the elements which are not
+    /// Strings instead of returning None.
+    ///
+    /// [`as_vec_of_string`]: #method.as_vec_of_string
+    pub fn as_vec_of_string_lenient(&self) -> Option<Vec<String>> {
+        self.as_vec_of_lenient(|value| value.as_str().map(str::to_owned))
+    }
+
+    /// If the `Value` is an array whose elements are all numbers fitting in
+    /// an i64, returns them as i64. Returns None otherwise.
+    pub fn as_vec_of_i64(&self) -> Option<Vec<i64>> {
+        self.as_vec_of(Self::as_i64)
+    }
+
+    /// Same as [`as_vec_of_i64`], but skips the elements which are not such
+    /// numbers instead of returning None.
+    ///
+    /// [`as_vec_of_i64`]: #method.as_vec_of_i64
+    pub fn as_vec_of_i64_lenient(&self) -> Option<Vec<i64>> {
+        self.as_vec_of_lenient(Self::as_i64)
+    }
+
+    /// If the `Value` is an array whose elements are all numbers fitting in
+    /// a u64, returns them as u64. Returns None otherwise.
+    pub fn as_vec_of_u64(&self) -> Option<Vec<u64>> {
+        self.as_vec_of(Self::as_u64)
+    }
+
+    /// Same as [`as_vec_of_u64`], but skips the elements which are not such
+    /// numbers instead of returning None.
+    ///
+    /// [`as_vec_of_u64`]: #method.as_vec_of_u64
+    pub fn as_vec_of_u64_lenient(&self) -> Option<Vec<u64>> {
+        self.as_vec_of_lenient(Self::as_u64)
+    }
+
+    /// If the `Value` is an array whose elements are all numbers, returns
+    /// them as f64. Returns None otherwise.
+    pub fn as_vec_of_f64(&self) -> Option<Vec<f64>> {
+        self.as_vec_of(Self::as_f64)
+    }
+
+    /// Same as [`as_vec_of_f64`], but skips the elements which are not numbers
+    /// instead of returning None.
+    ///
+    /// [`as_vec_of_f64`]: #method.as_vec_of_f64
+    pub fn as_vec_of_f64_lenient(&self) -> Option<Vec<f64>> {
+        self.as_vec_of_lenient(Self::as_f64)
+    }
+
+    /// If the `Value` is an array whose elements are all Booleans, returns
+    /// them as bool. Returns None otherwise.
+    pub fn as_vec_of_bool(&self) -> Option<Vec<bool>> {
+        self.as_vec_of(Self::as_bool)
+    }
+
+    /// Same as [`as_vec_of_bool`], but skips the elements which are not Booleans
+    /// instead of returning None.
+    ///
+    /// [`as_vec_of_bool`]: #method.as_vec_of_bool
+    pub fn as_vec_of_bool_lenient(&self) -> Option<Vec<bool>> {
+        self.as_vec_of_lenient(Self::as_bool)
+    }
+
+    /// Returns true if the `Value` is a Null. Returns false otherwise.
+    ///
+    /// For any Value on which `is_null` returns true, `as_null` is guaranteed
+    /// to return `Some(())`.
+    pub fn is_null(&self) -> bool {
+        self.as_null().is_some()
+    }
+
+    /// If the `Value` is a Null, returns (). Returns None otherwise.
+    pub fn as_null(&self) -> Option<()> {
+        match *self {
+            Self::Null => Some(()),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the `Value` where the values of the object entries
+    /// whose key matches one of `keys` are replaced by `"<redacted>"`.
+    ///
+    /// Keys are matched case-insensitively, at any depth, so that secrets can
+    /// be stripped before logging a configuration. See [`RedactedDebug`] to
+    /// format a redacted `Value` without cloning it.
+    ///
+    /// [`RedactedDebug`]: struct.RedactedDebug.html
+    pub fn redacted(&self, keys: &[&str]) -> Self {
+        redact::redacted(self, keys)
+    }
+
+    /// Takes the value out of the `Value`, leaving a `Null` in its place.
+    pub fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::Null)
+    }
+
+    /// Removes the value at `index` and returns it. A string index removes the
+    /// entry of an object, and a usize index removes the element of an array,
+    /// shifting the following elements.
+    ///
+    /// Returns `None` if the type of `self` does not match the type of the
+    /// index, if the given key does not exist in the object, or if the given
+    /// index is not within the bounds of the array.
+    pub fn take_at<I: Index>(&mut self, index: I) -> Option<Self> {
+        index.remove_from(self)
+    }
+
+    /// Flattens the `Value` into a map of dotted paths, as understood by
+    /// [`get_path`], to leaf values: `{"diesel": {"dbal": {"driver": ..}}}`
+    /// gives a `diesel.dbal.driver` key, and `{"rooms": [..]}` gives
+    /// `rooms.0`, `rooms.1`, etc.
+    ///
+    /// Empty objects and arrays are kept as leaves, so that [`unflatten`]
+    /// rebuilds the exact same value. A scalar `Value` gives a single entry
+    /// with an empty key.
+    ///
+    /// [`get_path`]: #method.get_path
+    /// [`unflatten`]: #method.unflatten
+    pub fn flatten(&self) -> BTreeMap<String, Self> {
+        self.flatten_with_separator('.')
+    }
+
+    /// Same as [`flatten`], but joins the keys with `separator`. Separators
+    /// and backslashes found in keys are escaped with a backslash.
+    ///
+    /// [`flatten`]: #method.flatten
+    pub fn flatten_with_separator(&self, separator: char) -> BTreeMap<String, Self> {
+        let mut flattened = BTreeMap::new();
+
+        flatten::flatten("", self, separator, &mut flattened);
+
+        flattened
+    }
+
+    /// Rebuilds the nested value flattened by [`flatten`].
+    ///
+    /// Objects whose keys are exactly the indices `0` to `n - 1` become
+    /// arrays. When a key is both a leaf and the prefix of another key, as in
+    /// `a` and `a.b`, the nested one wins.
+    ///
+    /// [`flatten`]: #method.flatten
+    pub fn unflatten(map: &BTreeMap<String, Self>) -> Self {
+        Self::unflatten_with_separator(map, '.')
+    }
+
+    /// Same as [`unflatten`], for keys joined with `separator` by
+    /// [`flatten_with_separator`].
+    ///
+    /// [`unflatten`]: #method.unflatten
+    /// [`flatten_with_separator`]: #method.flatten_with_separator
+    pub fn unflatten_with_separator(map: &BTreeMap<String, Self>, separator: char) -> Self {
+        flatten::unflatten(map, separator)
+    }
+
+    /// Flattens the `Value` into a map of dotted paths to stringified leaves,
+    /// for consumers which only understand flat string maps, such as the
+    /// environment.
+    ///
+    /// Keys are built as by [`flatten`]. Strings are kept as they are, other
+    /// leaves are written as JSON, and null leaves are skipped.
+    ///
+    /// [`flatten`]: #method.flatten
+    pub fn to_string_map(&self) -> BTreeMap<String, String> {
+        flatten::to_string_map(self, StringMapOptions::default())
+            .expect("escaped paths are unique")
+    }
+
+    /// Same as [`to_string_map`], but lets `options` choose the separator,
+    /// how null leaves are written and whether keys are written the way
+    /// environment variables are, e.g. `DIESEL_DBAL_DRIVER`.
+    ///
+    /// Fails with an error of kind `FormatError` when two leaves would be
+    /// given the same key, such as `a.b` and `a_b` written the way
+    /// environment variables are.
+    ///
+    /// [`to_string_map`]: #method.to_string_map
+    pub fn to_string_map_with(&self, options: StringMapOptions) -> result::Result<BTreeMap<String, String>> {
+        flatten::to_string_map(self, options)
+    }
+
+    /// Calls `f` with every node of the `Value`, itself included, and its
+    /// dotted path as understood by [`get_path`] (empty for the `Value`
+    /// itself).
+    ///
+    /// Nodes are visited depth-first, each one before its children, in the
+    /// order of the arrays and maps. The traversal does not recurse, so that
+    /// it handles values nested however deep.
+    ///
+    /// [`get_path`]: #method.get_path
+    pub fn walk<F>(&self, f: F)
+    where F: FnMut(&str, &Self)
+    {
+        walk::walk(self, f);
+    }
+
+    /// Same as [`walk`], with mutable nodes. The children of a node are
+    /// visited after `f` returns, so replacing a node walks its replacement.
+    ///
+    /// [`walk`]: #method.walk
+    pub fn walk_mut<F>(&mut self, f: F)
+    where F: FnMut(&str, &mut Self)
+    {
+        walk::walk_mut(self, f);
+    }
+
+    /// Lists the nodes of the `Value`, itself included, for which
+    /// `predicate` returns true when given their dotted path and the node.
+    ///
+    /// Nodes are searched depth-first, each one before its children, in the
+    /// order of the arrays and the sorted keys of the maps, so the result
+    /// does not depend on how the maps are ordered.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket_config;
+    /// let value = value!({
+    ///     "primary": {"driver": "mysql"},
+    ///     "replicas": [{"driver": "pgsql"}],
+    /// });
+    ///
+    /// let paths: Vec<_> = value.select(|_, node| node.get("driver").is_some())
+    ///     .into_iter()
+    ///     .map(|(path, _)| path)
+    ///     .collect();
+    /// assert_eq!(paths, vec!["primary", "replicas.0"]);
+    /// ```
+    pub fn select<F>(&self, predicate: F) -> Vec<(String, &Self)>
+    where F: Fn(&str, &Self) -> bool
+    {
+        walk::select(self, predicate)
+    }
+
+    /// Same as [`select`], with mutable nodes, to fix them in place. As a
+    /// node cannot be borrowed mutably along with its children, the children
+    /// of a selected node are not searched.
+    ///
+    /// [`select`]: #method.select
+    pub fn select_mut<F>(&mut self, predicate: F) -> Vec<(String, &mut Self)>
+    where F: Fn(&str, &Self) -> bool
+    {
+        walk::select_mut(self, predicate)
+    }
+
+    /// Evaluates a jq-like query, such as
+    /// `"diesel.servers[*].host"` or `"diesel.dbal | keys"`, and returns the
+    /// values it yields, in order.
+    ///
+    /// A query is made of stages separated by `|`, each one applied to every
+    /// value the previous one yielded:
+    ///
+    /// ```text
+    /// query     := stage ( "|" stage )*
+    /// stage     := "." | function | path
+    /// path      := ( "." )? ( key | subscript ) ( "." key | subscript )*
+    /// subscript := "[" ( index | "*" ) "]"
+    /// function  := "keys" | "length" | "first" | "type"
+    /// ```
+    ///
+    /// Keys are escaped with a backslash as in [`get_path`], and a numeric
+    /// key also indexes into arrays. `[*]` yields the elements of an array or
+    /// the values of an object, and `.` the value itself. A key written like
+    /// a function is looked up as a key when it follows a `.` or starts a
+    /// path going on, e.g. `.keys` or `keys.first`. Whitespace is allowed
+    /// around stages and within subscripts.
+    ///
+    /// Paths leading nowhere yield nothing rather than null. The functions
+    /// behave as in jq:
+    ///
+    ///   * `keys` yields the sorted keys of an object, or the indices of an
+    ///     array,
+    ///   * `length` yields the number of elements of an array or an object,
+    ///     of characters of a string, and 0 for null,
+    ///   * `first` yields the first element of an array, or null when empty,
+    ///   * `type` yields the type of the value, such as `"object"`.
+    ///
+    /// Fails with an error of kind `FormatError`, reporting the byte offset
+    /// in `expression`, when the query is malformed or when a function does
+    /// not apply to a value, like `keys` to a string.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket_config;
+    /// # use rocket_config::Value;
+    /// let value = value!({"servers": [{"host": "first"}, {"host": "second"}]});
+    ///
+    /// assert_eq!(
+    ///     value.query("servers[*].host").unwrap(),
+    ///     vec![Value::from("first"), Value::from("second")]
+    /// );
+    /// assert_eq!(value.query("servers | length").unwrap(), vec![Value::from(2)]);
+    /// ```
+    ///
+    /// [`get_path`]: #method.get_path
+    pub fn query(&self, expression: &str) -> result::Result<Vec<Self>> {
+        query::query(self, expression)
+    }
+
+    /// Estimates the bytes the `Value` uses, itself and what it owns on the
+    /// heap: the capacities of its strings and arrays, and its map entries,
+    /// with an approximation of the bookkeeping of the maps.
+    ///
+    /// The estimate is meant for metrics rather than exact accounting, but it
+    /// grows with the document: more elements, keys or longer strings weigh
+    /// more. The traversal does not recurse, so that it handles values nested
+    /// however deep. Arrays and objects shared between clones are counted
+    /// once per `Value` holding them.
+    pub fn estimated_size(&self) -> usize {
+        size::estimated_size(self)
+    }
+
+    /// Replaces every string of the `Value` by what `f` returns for it. Keys
+    /// are left untouched.
+    pub fn map_strings<F>(&mut self, f: F)
+    where F: Fn(&str) -> String
+    {
+        self.walk_mut(|_, node| {
+            if let Self::String(s) = node {
+                *s = f(s);
+            }
+        });
+    }
+
+    /// Lists the differences between the `Value` and `other`.
+    ///
+    /// Objects are compared key by key, recursively, and arrays as a whole;
+    /// see [`diff_with`] to compare arrays element by element. Each entry is
+    /// located by a dotted path understood by [`get_path`]. Identical values
+    /// give an empty list.
+    ///
+    /// [`diff_with`]: #method.diff_with
+    /// [`get_path`]: #method.get_path
+    pub fn diff(&self, other: &Self) -> Vec<DiffEntry> {
+        self.diff_with(other, ArrayDiffStrategy::default())
+    }
+
+    /// Lists the differences between the `Value` and `other`, comparing
+    /// arrays according to `arrays`.
+    pub fn diff_with(&self, other: &Self, arrays: ArrayDiffStrategy) -> Vec<DiffEntry> {
+        let mut entries = Vec::new();
+
+        diff::diff("", self, other, arrays, &mut entries);
+
+        entries
+    }
+
+    /// Deep-merges `other` into the `Value`.
+    ///
+    /// Objects are merged key by key, recursively. Any other value, arrays
+    /// included, is replaced by its right-hand side counterpart. See
+    /// [`merge_with`] to customize this behavior.
+    ///
+    /// [`merge_with`]: #method.merge_with
+    pub fn merge(&mut self, other: &Self) {
+        self.merge_with(other, MergeStrategy::default());
+    }
+
+    /// Deep-merges `other` into the `Value` according to `strategy`.
+    ///
+    /// `strategy` controls how arrays found on both sides are combined and
+    /// whether a null on the right-hand side removes the matching key.
+    pub fn merge_with(&mut self, other: &Self, strategy: MergeStrategy) {
+        merge::merge(self, other, strategy);
+    }
+
+    /// Applies a JSON merge patch to the `Value`, as described by
+    /// [RFC7386](https://tools.ietf.org/html/rfc7386).
+    ///
+    /// Objects are patched recursively and a null in the patch removes the
+    /// matching key. Any patch that is not an object replaces the `Value`
+    /// entirely.
+    pub fn merge_patch(&mut self, patch: &Self) {
+        merge::merge_patch(self, patch);
+    }
+
+    /// Replaces the `%env(NAME)%` placeholders found in every string of the
+    /// `Value` by the value of the matching environment variable.
+    ///
+    /// `%env(NAME:-fallback)%` falls back to `fallback` when `NAME` is unset.
+    /// Placeholders referring to unset variables without fallback are left
+    /// untouched. See [`try_resolve_env`] for a strict variant.
+    ///
+    /// [`try_resolve_env`]: #method.try_resolve_env
+    pub fn resolve_env(&mut self) {
+        let _ = self.interpolate_env(false);
+    }
+
+    /// Same as [`resolve_env`], but fails with an error of kind
+    /// `MissingValue` when a `%env(resolve:NAME)%` placeholder refers to an
+    /// unset variable without fallback.
+    ///
+    /// [`resolve_env`]: #method.resolve_env
+    pub fn try_resolve_env(&mut self) -> result::Result<()> {
+        self.interpolate_env(true)
+    }
+
+    /// Keeps its own stack, as [`walk_mut`] does, so that deeply nested
+    /// values do not overflow the call stack.
+    ///
+    /// [`walk_mut`]: #method.walk_mut
+    fn interpolate_env(&mut self, strict: bool) -> result::Result<()> {
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            // Children are pushed in reverse so that the first placeholder
+            // failing is the first one written
+            match node {
+                Self::String(s) => {
+                    *s = env::interpolate(s, strict)?;
+                },
+                Self::Array(array) => {
+                    stack.extend(Arc::make_mut(array).iter_mut().rev());
+                },
+                Self::Object(map) => {
+                    stack.extend(Arc::make_mut(map).values_mut().rev());
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the `%reference%` placeholders found in every string of the
+    /// `Value` by what `resolver` returns for `reference`. The placeholders of
+    /// the returned strings are replaced in turn, up to 16 levels deep, which
+    /// stops references to themselves.
+    ///
+    /// `%%` gives a literal percent sign, as does a `%` that does not start a
+    /// placeholder: references are neither empty nor hold whitespace.
+    /// Environment placeholders, such as `%env(DATABASE_URL)%`, are left to
+    /// [`resolve_env`].
+    ///
+    /// Fails with an error of kind `MissingValue` naming the path of the string
+    /// and the reference when `resolver` returns None, and of kind
+    /// `FormatError` when placeholders are nested too deep. The `Value` is
+    /// left unchanged when it fails.
+    ///
+    /// [`resolve_env`]: #method.resolve_env
+    pub fn resolve_placeholders<F>(&mut self, resolver: F) -> result::Result<()>
+    where F: Fn(&str) -> Option<String>
+    {
+        let mut resolved = self.clone();
+
+        placeholder::resolve(&mut resolved, "", &resolver)?;
+        *self = resolved;
+        Ok(())
+    }
+
+    /// Converts any type implementing [`serde::Serialize`] into a `Value`.
+    ///
+    /// Sequences become Arrays, and maps and structs become Objects. Returns
+    /// an error of kind `SerializeError` if a map key is not a string or if an
+    /// integer does not fit in 64 bits. Non-finite floats become Null.
+    ///
+    /// [`serde::Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> result::Result<Self> {
+        value.serialize(ser::Serializer)
+    }
+
+    /// Deserializes the `Value` into any type implementing
+    /// [`serde::Deserialize`].
+    ///
+    /// Returns an error of kind `DeserializeError` if the `Value` does not
+    /// match `T`. Its message locates the offending value with a dotted path,
+    /// such as `invalid type: string "3306", expected u16 at 'dbal.port'`.
+    ///
+    /// [`serde::Deserialize`]: https://docs.serde.rs/serde/trait.Deserialize.html
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> result::Result<T> {
+        T::deserialize(self)
+    }
+
+    /// Serializes the `Value` as a compact JSON string.
+    ///
+    /// Object keys are written in their sorted order, or in file order with
+    /// the `preserve_order` feature, so the output is stable across calls.
+    pub fn to_json_string(&self) -> result::Result<String> {
+        serde_json::to_string(self).map_err(|err| {
+            error::Error::new(error::ErrorKind::SerializeError, err)
+        })
+    }
+
+    /// Formats the `Value` as JSON text indented by two spaces per level. This
+    /// is the same as `format!("{:#}", value)`, and never fails, unlike
+    /// [`to_json_string_pretty`].
+    ///
+    /// [`to_json_string_pretty`]: #method.to_json_string_pretty
+    pub fn to_string_pretty(&self) -> String {
+        format!("{:#}", self)
+    }
+
+    /// Renders the `Value` as indented, YAML-ish text meant to be read by
+    /// humans, e.g. in launch-time logs: the keys of objects are sorted, the
+    /// elements of arrays are bulleted, and strings are only quoted when they
+    /// would read as something else.
+    ///
+    /// The output is not meant to be parsed back. Strings longer than 80
+    /// characters are truncated, see [`dump_with_max_length`] to choose
+    /// another length. Secrets are rendered too: dump the [`redacted`] copy of
+    /// the `Value` to log it safely.
+    ///
+    /// [`dump_with_max_length`]: #method.dump_with_max_length
+    /// [`redacted`]: #method.redacted
+    pub fn dump(&self) -> String {
+        self.dump_with_max_length(constants::DUMP_MAX_STRING_LENGTH)
+    }
+
+    /// Same as [`dump`], but truncates strings longer than `max_length`
+    /// characters.
+    ///
+    /// [`dump`]: #method.dump
+    pub fn dump_with_max_length(&self, max_length: usize) -> String {
+        dump::dump(self, max_length)
+    }
+
+    /// Serializes the `Value` as a pretty-printed JSON string.
+    pub fn to_json_string_pretty(&self) -> result::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|err| {
+            error::Error::new(error::ErrorKind::SerializeError, err)
+        })
+    }
+
+    /// Serializes the `Value` as a YAML string.
+    ///
+    /// With the `arbitrary_precision` feature, numbers beyond the range of
+    /// u64, i64 and f64 are rounded, as YAML cannot hold them.
+    pub fn to_yaml_string(&self) -> result::Result<String> {
+        #[cfg(feature = "arbitrary_precision")]
+        let result = serde_yaml::to_string(&serde_yaml::Value::from(self));
+        #[cfg(not(feature = "arbitrary_precision"))]
+        let result = serde_yaml::to_string(self);
+
+        result.map_err(|err| {
+            error::Error::new(error::ErrorKind::SerializeError, err)
+        })
+    }
+}
+
+/// Parses a duration written as a number followed by an optional unit.
+fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(input.len());
+    let (amount, unit) = input.split_at(split);
+    let milliseconds: u64 = match unit.trim_start() {
+        "ms" => 1,
+        "" | "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        _ => return None,
+    };
+
+    match amount.parse::<u64>() {
+        Ok(amount) => amount.checked_mul(milliseconds).map(Duration::from_millis),
+        Err(_) => amount.parse::<f64>().ok()
+            .and_then(|amount| duration_from_secs_f64(amount * milliseconds as f64 / 1_000.0)),
+    }
+}
+
+/// Parses an integer, with an optional sign, surrounding whitespace, `_`
+/// digit separators and a `0x`, `0o` or `0b` prefix, into its sign and
+/// magnitude.
+fn parse_integer(input: &str) -> Result<(bool, u64), String> {
+    let input = remove_separators(input)?;
+    let (negative, unsigned) = match input.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, input.strip_prefix('+').unwrap_or(&input)),
+    };
+    let (radix, digits) = match unsigned.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => (16, &unsigned[2..]),
+        Some("0o") => (8, &unsigned[2..]),
+        Some("0b") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+
+    // from_str_radix accepts a sign of its own
+    if digits.starts_with(['+', '-']) {
+        return Err("invalid digit found in string".to_owned());
+    }
+
+    u64::from_str_radix(digits, radix)
+        .map(|magnitude| (negative, magnitude))
+        .map_err(|err| err.to_string())
+}
+
+/// Parses a finite float, or an integer as accepted by [`parse_integer`].
+///
+/// [`parse_integer`]: fn.parse_integer.html
+fn parse_float(input: &str) -> Result<f64, String> {
+    let float = match parse_integer(input) {
+        Ok((negative, magnitude)) if negative => -(magnitude as f64),
+        Ok((_, magnitude)) => magnitude as f64,
+        Err(_) => remove_separators(input)?.parse::<f64>().map_err(|err| err.to_string())?,
+    };
+
+    if float.is_finite() { Ok(float) } else { Err("not a finite number".to_owned()) }
+}
+
+/// Trims `input` and removes the `_` separating its digits.
+fn remove_separators(input: &str) -> Result<String, String> {
+    let input = input.trim();
+
+    if input.starts_with('_') || input.ends_with('_') || input.contains("__") {
+        Err("misplaced digit separator".to_owned())
+    }
+    else {
+        Ok(input.replace('_', ""))
+    }
+}
+
+/// Converts a number of seconds to a duration, if it is positive and fits.
+fn duration_from_secs_f64(seconds: f64) -> Option<Duration> {
+    if seconds.is_finite() && seconds >= 0.0 && seconds < u64::MAX as f64 {
+        Some(Duration::from_secs_f64(seconds))
+    }
+    else {
+        None
+    }
+}
+
+/// Splits a dotted path into its segments, honoring `\.` and `\\` escapes.
+fn split_path(path: &str) -> Vec<String> {
+    split_path_with(path, '.')
+}
+
+/// Splits a path into the segments separated by `separator`, honoring the
+/// escapes written by [`join_path_with`].
+///
+/// [`join_path_with`]: fn.join_path_with.html
+pub(super) fn split_path_with(path: &str, separator: char) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut characters = path.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => {
+                if let Some(escaped) = characters.next() {
+                    segment.push(escaped);
+                }
+            },
+            _ if character == separator => segments.push(std::mem::take(&mut segment)),
+            _ => segment.push(character),
+        }
+    }
+    segments.push(segment);
+
+    segments
+}
+
+/// Appends `segment` to the dotted `path`, escaping its dots and backslashes
+/// the way [`split_path`] expects them.
+///
+/// [`split_path`]: fn.split_path.html
+pub(super) fn join_path(path: &str, segment: &str) -> String {
+    join_path_with(path, segment, '.')
+}
+
+/// Appends `segment` to `path`, separated by `separator`, escaping the
+/// separators and backslashes of `segment` with a backslash.
+pub(super) fn join_path_with(path: &str, segment: &str, separator: char) -> String {
+    let mut joined = String::with_capacity(path.len() + segment.len() + 1);
+
+    if !path.is_empty() {
+        joined.push_str(path);
+        joined.push(separator);
+    }
+    for character in segment.chars() {
+        if character == separator || character == '\\' {
+            joined.push('\\');
+        }
+        joined.push(character);
+    }
+
+    joined
+}
+
+/// Parses the array index designated by a path segment for an insertion into
+/// `list`, which may be its length to append an element.
+fn insertion_index(list: &[Value], segment: &str) -> result::Result<usize> {
+    match segment.parse::<usize>() {
+        Ok(index) if index <= list.len() => Ok(index),
+        Ok(index) => Err(error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("index {} is out of bounds in JSON array of length {}", index, list.len())
+        )),
+        Err(_) => Err(error::Error::new(
+            error::ErrorKind::FormatError,
+            format!("cannot access key {:?} in JSON array", segment)
+        )),
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens, as described by
+/// RFC6901. Returns `None` if the pointer is neither empty nor starts with `/`.
+fn pointer_tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    Some(pointer
+        .split('/')
+        .skip(1)
+        .map(|x| x.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Parses an array index as described by RFC6901, rejecting leading zeros
+/// and signs.
+fn parse_index(s: &str) -> Option<usize> {
+    if s.starts_with('+') || (s.starts_with('0') && s.len() != 1) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// The default value is `Value::Null`.
+///
+/// This is useful for handling omitted `Value` fields when deserializing.
+impl Default for Value {
+    fn default() -> Self {
         Self::Null
     }
-}
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<Number> for Value {
+    fn from(n: Number) -> Self {
+        Self::Number(n)
+    }
+}
+
+/// Converts a `f64` into a `Value::Number`.
+///
+/// Infinite and NaN values are not numbers and are converted into
+/// `Value::Null` instead.
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        Number::from_f64(f).map_or(Self::Null, Self::Number)
+    }
+}
+
+/// Converts a `f32` into a `Value::Number`, widening it to a `f64`.
+///
+/// Infinite and NaN values are not numbers and are converted into
+/// `Value::Null` instead.
+impl From<f32> for Value {
+    fn from(f: f32) -> Self {
+        Self::from(f64::from(f))
+    }
+}
+
+macro_rules! impl_from_integer {
+    (
+        $($ty:ty),*
+    ) => {
+        $(
+            impl From<$ty> for Value {
+                #[inline]
+                fn from(n: $ty) -> Self {
+                    Self::Number(Number::from(n))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(vec: Vec<T>) -> Self {
+        Self::Array(Arc::new(vec.into_iter().map(Into::into).collect()))
+    }
+}
+
+impl<V: Into<Value>> From<BTreeMap<String, V>> for Value {
+    fn from(map: BTreeMap<String, V>) -> Self {
+        Self::Object(Arc::new(map.into_iter().map(|(key, each)| (key, each.into())).collect()))
+    }
+}
+
+#[cfg(feature = "preserve_order")]
+impl<V: Into<Value>> From<Map<String, V>> for Value {
+    fn from(map: Map<String, V>) -> Self {
+        Self::Object(Arc::new(map.into_iter().map(|(key, each)| (key, each.into())).collect()))
+    }
+}
+
+/// Collects key-value pairs into a `Value::Object`, the last value of a
+/// repeated key replacing the previous ones.
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self::Object(Arc::new(iter.into_iter().map(|(key, each)| (key.into(), each.into())).collect()))
+    }
+}
+
+/// Converts an `Option` into a `Value`, `None` being converted into
+/// `Value::Null`.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Self {
+        option.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Fails with an error of kind `FormatError` telling what was `expected`
+/// instead of `value`.
+fn unexpected(value: &Value, expected: &str) -> error::Error {
+    error::Error::new(
+        error::ErrorKind::FormatError,
+        format!("expected {}, found {}", expected, Type(value))
+    )
+}
+
+impl TryFrom<&Value> for String {
+    type Error = error::Error;
+
+    fn try_from(value: &Value) -> result::Result<Self> {
+        value.as_str().map(str::to_owned).ok_or_else(|| unexpected(value, "a string"))
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = error::Error;
+
+    fn try_from(value: Value) -> result::Result<Self> {
+        match value {
+            Value::String(s) => Ok(s),
+            value => Err(unexpected(&value, "a string")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = error::Error;
+
+    fn try_from(value: &Value) -> result::Result<Self> {
+        value.as_bool().ok_or_else(|| unexpected(value, "a boolean"))
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = error::Error;
+
+    fn try_from(value: Value) -> result::Result<Self> {
+        Self::try_from(&value)
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = error::Error;
+
+    fn try_from(value: &Value) -> result::Result<Self> {
+        value.as_f64().ok_or_else(|| unexpected(value, "an f64"))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = error::Error;
+
+    fn try_from(value: Value) -> result::Result<Self> {
+        Self::try_from(&value)
+    }
+}
+
+macro_rules! impl_try_from_integer {
+    (
+        $($ty:ty => $expected:expr),*
+    ) => {
+        $(
+            /// Fails with an error of kind `FormatError` when the `Value` is
+            /// not an integer, or when it is out of range.
+            impl TryFrom<&Value> for $ty {
+                type Error = error::Error;
+
+                fn try_from(value: &Value) -> result::Result<Self> {
+                    let n = match *value {
+                        Value::Number(ref n) => n,
+                        _ => return Err(unexpected(value, $expected)),
+                    };
+                    let converted = match (n.as_u64(), n.as_i64()) {
+                        (Some(u), _) => <$ty>::try_from(u).ok(),
+                        (None, Some(i)) => <$ty>::try_from(i).ok(),
+                        (None, None) => None,
+                    };
+
+                    converted.ok_or_else(|| error::Error::new(
+                        error::ErrorKind::FormatError,
+                        format!("{} is not {}", value, $expected)
+                    ))
+                }
+            }
+
+            impl TryFrom<Value> for $ty {
+                type Error = error::Error;
+
+                fn try_from(value: Value) -> result::Result<Self> {
+                    Self::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(
+    u8 => "a u8", u16 => "a u16", u32 => "a u32", u64 => "a u64", u128 => "a u128", usize => "a usize",
+    i8 => "an i8", i16 => "an i16", i32 => "an i32", i64 => "an i64", i128 => "an i128", isize => "an isize"
+);
+
+impl TryFrom<&Value> for Vec<Value> {
+    type Error = error::Error;
+
+    fn try_from(value: &Value) -> result::Result<Self> {
+        value.as_array().cloned().ok_or_else(|| unexpected(value, "an array"))
+    }
+}
+
+impl TryFrom<Value> for Vec<Value> {
+    type Error = error::Error;
+
+    fn try_from(value: Value) -> result::Result<Self> {
+        match value {
+            Value::Array(list) => Ok(unshare(list)),
+            value => Err(unexpected(&value, "an array")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for BTreeMap<String, Value> {
+    type Error = error::Error;
+
+    fn try_from(value: &Value) -> result::Result<Self> {
+        value.as_object()
+            .map(|map| map.iter().map(|(key, each)| (key.clone(), each.clone())).collect())
+            .ok_or_else(|| unexpected(value, "an object"))
+    }
+}
+
+impl TryFrom<Value> for BTreeMap<String, Value> {
+    type Error = error::Error;
+
+    fn try_from(value: Value) -> result::Result<Self> {
+        match value {
+            Value::Object(map) => Ok(unshare(map).into_iter().collect()),
+            value => Err(unexpected(&value, "an object")),
+        }
+    }
+}
+
+impl From<&serde_json::Value> for Value
+{
+    /// Converts [serde_json] deserialization results under a common value:
+    /// [Value].
+    /// 
+    /// [serde_yaml](https://docs.serde.rs/serde_json/index.html)
+    /// [Value](./struct.Value.html)
+    fn from(json: &serde_json::Value) -> Self
+    {
+        match json {
+            serde_json::Value::Null         => {
+                Self::Null
+            },
+            serde_json::Value::String(ref str)  => {
+                Self::String(str.to_string())
+            },
+            serde_json::Value::Bool(ref bool)   => {
+                Self::Bool(*bool)
+            },
+            serde_json::Value::Number(ref n)    => {
+                Number::try_from(n).map_or(Self::Null, Self::Number)
+            },
+            serde_json::Value::Array(json)   => {
+                let vec: Vec<Self> = json.iter().map(|each| {
+                    // Dangerous recusivity
+                    Self::from(each)
+                }).collect();
+
+                Self::Array(Arc::new(vec))
+            },
+            serde_json::Value::Object(json)   => {
+                let map: Map<String, Self> = json.iter()
+                .map(|(key, each)| {
+                    // Dangerous recusivity
+                    (key.to_string(), Self::from(each))
+                }).collect();
+
+                Self::Object(Arc::new(map))
+            },
+        }
+    }
+}
+
+impl From<&serde_yaml::Value> for Value
+{
+    /// Converts [serde_yaml] deserialization results under a common value:
+    /// [Value].
+    /// 
+    /// Non-finite floats, such as `.nan` and `.inf`, are converted to
+    /// `Value::Null`, while loading a configuration file holding one fails.
+    /// Mapping keys that are not strings are converted to their compact JSON
+    /// representation: `42` becomes `"42"` and `true` becomes `"true"`. When
+    /// keys collide that way, such as `42` and `"42"`, the last one wins,
+    /// while loading a configuration file holding them fails.
+    ///
+    /// [serde_yaml](https://docs.serde.rs/serde_yaml/index.html)
+    /// [Value](./struct.Value.html)
+    fn from(yaml: &serde_yaml::Value) -> Self
+    {
+        match yaml {
+            serde_yaml::Value::Null             => {
+                Self::Null
+            },
+            serde_yaml::Value::String(ref str)  => {
+                Self::String(str.to_string())
+            },
+            serde_yaml::Value::Bool(ref bool)   => {
+                Self::Bool(*bool)
+            },
+            serde_yaml::Value::Number(ref n)    => {
+                Number::try_from(n).map_or(Self::Null, Self::Number)
+            },
+            serde_yaml::Value::Sequence(yaml)   => {
+                let vec: Vec<Self> = yaml.iter().map(|each| {
+                    // Dangerous recusivity
+                    Self::from(each)
+                }).collect();
+
+                Self::Array(Arc::new(vec))
+            },
+            serde_yaml::Value::Mapping(yaml)    => {
+                let map: Map<String, Self> = yaml.iter()
+                .map(|(key, each)| {
+                    // YAML allows any value as key, non-string keys are
+                    // stringified (`42` becomes `"42"`)
+                    let key = match key.as_str() {
+                        Some(key) => key.to_owned(),
+                        None => Self::from(key).to_string(),
+                    };
+
+                    // Dangerous recusivity
+                    (key, Self::from(each))
+                }).collect();
+
+                Self::Object(Arc::new(map))
+            },
+        }
+    }
+}
+
+impl From<&Value> for serde_json::Value
+{
+    /// Converts a [Value] back into its [serde_json] representation.
+    ///
+    /// [serde_json](https://docs.serde.rs/serde_json/index.html)
+    /// [Value](./struct.Value.html)
+    fn from(value: &Value) -> Self
+    {
+        match value {
+            Value::Null             => {
+                Self::Null
+            },
+            Value::Bool(b)          => {
+                Self::Bool(*b)
+            },
+            Value::Number(n)        => {
+                Self::Number(serde_json::Number::from(n))
+            },
+            Value::String(s)        => {
+                Self::String(s.clone())
+            },
+            Value::Array(array)     => {
+                Self::Array(array.iter().map(Self::from).collect())
+            },
+            Value::Object(map)      => {
+                Self::Object(map.iter().map(|(key, each)| {
+                    (key.clone(), Self::from(each))
+                }).collect())
+            },
+        }
+    }
+}
+
+impl From<&Value> for serde_yaml::Value
+{
+    /// Converts a [Value] back into its [serde_yaml] representation.
+    ///
+    /// [serde_yaml](https://docs.serde.rs/serde_yaml/index.html)
+    /// [Value](./struct.Value.html)
+    fn from(value: &Value) -> Self
+    {
+        match value {
+            Value::Null             => {
+                Self::Null
+            },
+            Value::Bool(b)          => {
+                Self::Bool(*b)
+            },
+            Value::Number(n)        => {
+                Self::Number(serde_yaml::Number::from(n))
+            },
+            Value::String(s)        => {
+                Self::String(s.clone())
+            },
+            Value::Array(array)     => {
+                Self::Sequence(array.iter().map(Self::from).collect())
+            },
+            Value::Object(map)      => {
+                Self::Mapping(map.iter().map(|(key, each)| {
+                    (Self::String(key.clone()), Self::from(each))
+                }).collect())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_value() {
+        let value = Value::Null;
+
+        // Checks if the good value is attributed
+        assert_eq!(value, Value::Null);
+
+        // Checks if tester works fine
+        assert!(value.is_null());
+
+        // Checks if converter works fine
+        assert!(value.as_null().is_some());
+        assert_eq!(value.as_null().unwrap(), ());
+    }
+
+    #[test]
+    fn bool_value() {
+        let value = Value::Bool(true);
+
+        // Checks if the good value is attributed
+        assert_eq!(value, Value::Bool(true));
+
+        // Checks if tester works fine
+        assert!(value.is_boolean());
+
+        // Checks if converter works fine
+        assert!(value.as_bool().is_some());
+        assert_eq!(value.as_bool().unwrap(), true);
+    }
+
+    #[test]
+    fn string_value() {
+        let value = Value::String("test string".to_owned());
+
+        // Checks if the good value is attributed
+        assert_eq!(value, Value::String("test string".to_owned()));
+
+        // Checks if tester works fine
+        assert!(value.is_string());
+
+        // Checks if converter works fine
+        assert!(value.as_str().is_some());
+        assert_eq!(value.as_str().unwrap(), "test string".to_owned());
+    }
+
+    #[test]
+    fn number_value() {
+        let value_unsigned = Value::Number(Number::from(10u8));
+        let value_signed = Value::Number(Number::from(10i8));
+        let value_float = Value::Number(Number::from_f64(10.12).expect("failed to create number from float"));
+
+        // Checks if the good value is attributed
+        assert_eq!(value_unsigned, Value::Number(Number::from(10u8)));
+        assert_eq!(value_signed, Value::Number(Number::from(10i8)));
+
+        // Checks if tester works fine
+        assert!(value_unsigned.is_number());
+        assert!(value_unsigned.is_u64());
+        assert!(value_signed.is_number());
+        assert!(value_unsigned.is_i64());
+        assert!(value_float.is_number());
+        assert!(value_float.is_f64());
+
+        // Checks if converters work fine
+        assert!(value_unsigned.as_u64().is_some());
+        assert_eq!(value_unsigned.as_u64().unwrap(), 10u64);
+
+        assert!(value_unsigned.as_i64().is_some());
+        assert_eq!(value_unsigned.as_i64().unwrap(), 10i64);
+
+        assert!(value_float.as_f64().is_some());
+        assert_eq!(value_float.as_f64().unwrap(), 10.12);
+    }
+
+    #[test]
+    fn array_value() {
+        let value = Value::Array(Arc::new(vec!(
+            Value::String("test".to_owned()),
+            Value::String("test 2".to_owned())
+        )));
+
+        // Checks if the good value is attributed
+        assert_eq!(value, Value::Array(Arc::new(vec!(
+            Value::String("test".to_owned()),
+            Value::String("test 2".to_owned())
+        ))));
+
+        // Checks if tester works fine
+        assert!(value.is_array());
+
+        // Checks if converter works fine
+        assert!(value.as_array().is_some());
+        assert_eq!(value.as_array().unwrap(), &vec!(
+            Value::String("test".to_owned()),
+            Value::String("test 2".to_owned())
+        ));
+    }
+
+    #[test]
+    fn object_value() {
+        let value = Value::Object(Arc::new({
+            let mut map = super::Map::new();
+
+            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
+            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
+            map
+        }));
+
+        // Checks if the good value is attributed
+        assert_eq!(value, Value::Object(Arc::new({
+            let mut map = super::Map::new();
+
+            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
+            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
+            map
+        })));
+
+        // Checks if tester works fine
+        assert!(value.is_object());
+
+        // Checks if converter works fine
+        assert!(value.as_object().is_some()); 
+        assert_eq!(value.as_object().unwrap(), &{
+            let mut map = super::Map::new();
+
+            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
+            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
+            map
+        });
+
+        // Checks if mut converter works fine
+        let mut cloned_value = value.clone();
+        assert!(cloned_value.as_object_mut().is_some()); 
+        assert_eq!(cloned_value.as_object_mut().unwrap(), &mut {
+            let mut map = super::Map::new();
+
+            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
+            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
+            map
+        });
+
+    }
+
+    #[test]
+    fn clone_on_write() {
+        let original = value!({"hosts": ["first", "second"], "pool": {"min": 1}});
+
+        // Clones share arrays and objects
+        let mut cloned = original.clone();
+        assert!(std::ptr::eq(original.as_object().unwrap(), cloned.as_object().unwrap()));
+        assert!(std::ptr::eq(original["hosts"].as_array().unwrap(), cloned["hosts"].as_array().unwrap()));
+
+        // Until either side changes them
+        cloned["hosts"][0] = Value::from("third");
+        cloned.as_object_mut().unwrap().insert("port".to_owned(), Value::from(3306));
+        cloned["pool"]["min"] = Value::from(2);
+        assert_eq!(original, value!({"hosts": ["first", "second"], "pool": {"min": 1}}));
+        assert_eq!(cloned, value!({"hosts": ["third", "second"], "pool": {"min": 2}, "port": 3306}));
+
+        // Only what was changed is copied
+        let mut cloned = original.clone();
+        cloned["pool"].as_object_mut().unwrap().clear();
+        assert!(!std::ptr::eq(original["pool"].as_object().unwrap(), cloned["pool"].as_object().unwrap()));
+        assert!(std::ptr::eq(original["hosts"].as_array().unwrap(), cloned["hosts"].as_array().unwrap()));
+
+        // The original is not affected by changes made through any accessor
+        let mut cloned = original.clone();
+        cloned.insert_at("hosts.2", Value::from("third")).unwrap();
+        cloned.remove_at("pool.min");
+        *cloned.pointer_mut("/hosts/0").unwrap() = Value::Null;
+        cloned.lowercase_keys().unwrap();
+        assert_eq!(original, value!({"hosts": ["first", "second"], "pool": {"min": 1}}));
+        assert_eq!(cloned, value!({"hosts": [null, "second", "third"], "pool": {}}));
+    }
+
+    #[test]
+    fn from_json_value() {
+        let json = json!({
+            "house": {
+                "rooms": [
+                    "kitchen",
+                    "living room",
+                    "toilet",
+                    "room 1",
+                    "room 2"
+                ],
+                "inhabitant_number": 2,
+                "inhabitants": [
+                    {
+                        "name": "Doe",
+                        "firstname": "John",
+                        "age": 37.5,
+                        "job": true,
+                    },
+                    {
+                        "name": "Doe",
+                        "firstname": "Jane",
+                        "age": 36.4,
+                        "job": true,
+                    }
+                ],
+                "cars": null,
+            }
+        });
+
+        // If it does not panic, it worked
+        let json_value = Value::from(&json);
+        #[cfg(not(feature = "preserve_order"))]
+        assert_eq!(
+            format!("{:?}", json_value),
+            "Object({\"house\": Object({\"cars\": Null, \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"age\": Number(37.5), \"firstname\": String(\"John\"), \"job\": Bool(true), \"name\": String(\"Doe\")}), Object({\"age\": Number(36.4), \"firstname\": String(\"Jane\"), \"job\": Bool(true), \"name\": String(\"Doe\")})]), \"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")])})})"
+        );
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(
+            format!("{:?}", json_value),
+            "Object({\"house\": Object({\"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")]), \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"name\": String(\"Doe\"), \"firstname\": String(\"John\"), \"age\": Number(37.5), \"job\": Bool(true)}), Object({\"name\": String(\"Doe\"), \"firstname\": String(\"Jane\"), \"age\": Number(36.4), \"job\": Bool(true)})]), \"cars\": Null})})"
+        );
+    }
+
+    #[test]
+    fn from_yaml_value() {
+        let yaml = serde_yaml::Value::Mapping({
+            let mut mapping = serde_yaml::Mapping::new();
+
+            mapping.insert(
+                serde_yaml::Value::String("house".to_owned()),
+                serde_yaml::Value::Mapping({
+                    let mut mapping = serde_yaml::Mapping::new();
+
+                    mapping.insert(
+                        serde_yaml::Value::String("cars".to_owned()),
+                        serde_yaml::Value::Null
+                    );
+
+                    mapping.insert(
+                        serde_yaml::Value::String("rooms".to_owned()),
+                        serde_yaml::Value::Sequence({
+                            let mut sequence = serde_yaml::Sequence::new();
+
+                            sequence.push(serde_yaml::Value::String("kitchen".to_owned()));
+                            sequence.push(serde_yaml::Value::String("living room".to_owned()));
+                            sequence.push(serde_yaml::Value::String("toilet".to_owned()));
+                            sequence.push(serde_yaml::Value::String("room 1".to_owned()));
+                            sequence.push(serde_yaml::Value::String("room 2".to_owned()));
+
+                            sequence
+                        })
+                    );
+
+                    mapping.insert(
+                        serde_yaml::Value::String("inhabitant_number".to_owned()),
+                        serde_yaml::Value::Number(serde_yaml::Number::from(2u64))
+                    );
+
+                    mapping.insert(
+                        serde_yaml::Value::String("inhabitants".to_owned()),
+                        serde_yaml::Value::Sequence({
+                            let mut sequence = serde_yaml::Sequence::new();
+
+                            sequence.push(serde_yaml::Value::Mapping({
+                                let mut mapping = serde_yaml::Mapping::new();
+
+                                mapping.insert(
+                                    serde_yaml::Value::String("firstname".to_owned()),
+                                    serde_yaml::Value::String("John".to_owned())
+                                );
+                                mapping.insert(
+                                    serde_yaml::Value::String("name".to_owned()),
+                                    serde_yaml::Value::String("Doe".to_owned())
+                                );
+                                mapping.insert(
+                                    serde_yaml::Value::String("age".to_owned()),
+                                    serde_yaml::Value::Number(
+                                        serde_yaml::Number::from(37.5)
+                                    )
+                                );
+
+                                mapping
+                            }));
+
+                            sequence.push(serde_yaml::Value::Mapping({
+                                let mut mapping = serde_yaml::Mapping::new();
+
+                                mapping.insert(
+                                    serde_yaml::Value::String("firstname".to_owned()),
+                                    serde_yaml::Value::String("Jane".to_owned())
+                                );
+                                mapping.insert(
+                                    serde_yaml::Value::String("name".to_owned()),
+                                    serde_yaml::Value::String("Doe".to_owned())
+                                );
+                                mapping.insert(
+                                    serde_yaml::Value::String("age".to_owned()),
+                                    serde_yaml::Value::Number(
+                                        serde_yaml::Number::from(36.4)
+                                    )
+                                );
+
+                                mapping
+                            }));
+
+                            sequence
+                        })
+                    );
+
+                    mapping
+                })
+            );
+
+            mapping
+        });
+
+        // If it does not panic, it worked
+        let yaml_value = Value::from(&yaml);
+        #[cfg(not(feature = "preserve_order"))]
+        assert_eq!(
+            format!("{:?}", yaml_value),
+            "Object({\"house\": Object({\"cars\": Null, \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"age\": Number(37.5), \"firstname\": String(\"John\"), \"name\": String(\"Doe\")}), Object({\"age\": Number(36.4), \"firstname\": String(\"Jane\"), \"name\": String(\"Doe\")})]), \"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")])})})"
+        );
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(
+            format!("{:?}", yaml_value),
+            "Object({\"house\": Object({\"cars\": Null, \"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")]), \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"firstname\": String(\"John\"), \"name\": String(\"Doe\"), \"age\": Number(37.5)}), Object({\"firstname\": String(\"Jane\"), \"name\": String(\"Doe\"), \"age\": Number(36.4)})])})})"
+        );
+    }
+
+    #[test]
+    fn display_value() {
+        let json = json!({
+            "big": u64::MAX,
+            "cars": null,
+            "empty_array": [],
+            "empty_object": {},
+            "float": 10.0,
+            "inhabitants": [
+                {
+                    "age": 37.5,
+                    "job": true,
+                    "name": "John \"Doe\"\n",
+                }
+            ],
+            "negative": -12,
+        });
+        let value = Value::from(&json);
+
+        assert_eq!(
+            format!("{}", value),
+            "{\"big\":18446744073709551615,\"cars\":null,\"empty_array\":[],\"empty_object\":{},\"float\":10.0,\"inhabitants\":[{\"age\":37.5,\"job\":true,\"name\":\"John \\\"Doe\\\"\\n\"}],\"negative\":-12}"
+        );
+
+        // Output must round-trip through a JSON parser
+        let reparsed: serde_json::Value = serde_json::from_str(&format!("{}", value))
+            .expect("failed to parse displayed value");
+        assert_eq!(reparsed, json);
+
+        assert_eq!(format!("{}", Value::String("\u{1}".to_owned())), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn to_string_pretty() {
+        let json = json!({
+            "cars": null,
+            "empty_array": [],
+            "empty_object": {},
+            "inhabitants": [
+                {
+                    "age": 37.5,
+                    "name": "John \"Doe\"\n",
+                },
+                [1, -2]
+            ],
+        });
+        let value = Value::from(&json);
+
+        assert_eq!(
+            value.to_string_pretty(),
+            r#"{
+  "cars": null,
+  "empty_array": [],
+  "empty_object": {},
+  "inhabitants": [
+    {
+      "age": 37.5,
+      "name": "John \"Doe\"\n"
+    },
+    [
+      1,
+      -2
+    ]
+  ]
+}"#
+        );
+        assert_eq!(format!("{:#}", value), value.to_string_pretty());
+        assert_eq!(value.to_string_pretty(), value.to_json_string_pretty().expect("failed to serialize value"));
+        assert_eq!(Value::from("tab\t").to_string_pretty(), "\"tab\\t\"");
+        assert_eq!(Value::Null.to_string_pretty(), "null");
+    }
+
+    #[test]
+    fn to_json_string() {
+        let json = json!({
+            "age": 37.5,
+            "balance": -12,
+            "children": 2,
+            "job": null,
+            "name": "Doe",
+            "pets": [],
+        });
+        let value = Value::from(&json);
+
+        assert_eq!(
+            value.to_json_string().expect("failed to serialize value"),
+            "{\"age\":37.5,\"balance\":-12,\"children\":2,\"job\":null,\"name\":\"Doe\",\"pets\":[]}"
+        );
+
+        let pretty = value.to_json_string_pretty().expect("failed to serialize value");
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).expect("failed to parse json"),
+            json
+        );
+    }
+
+    #[test]
+    fn to_yaml_string() {
+        let json = json!({
+            "name": "Doe",
+            "age": 37.5,
+            "children": 2,
+            "balance": -12,
+            "rooms": ["kitchen", "toilet"],
+        });
+        let value = Value::from(&json);
+
+        let yaml = value.to_yaml_string().expect("failed to serialize value");
+        let reparsed = serde_yaml::from_str::<serde_yaml::Value>(&yaml)
+            .expect("failed to parse yaml");
+
+        assert_eq!(Value::from(&reparsed), value);
+        assert!(reparsed["children"].is_u64());
+        assert!(reparsed["balance"].is_i64());
+        assert!(reparsed["age"].is_f64());
+    }
+
+    #[test]
+    fn into_json_value() {
+        let json = json!({
+            "house": {
+                "rooms": ["kitchen", "living room"],
+                "inhabitant_number": 2,
+                "temperature": -3,
+                "inhabitants": [
+                    {
+                        "name": "Doe",
+                        "age": 37.5,
+                        "job": true,
+                    }
+                ],
+                "cars": null,
+                "big": u64::MAX,
+            }
+        });
+
+        let value = Value::from(&json);
+        assert_eq!(serde_json::Value::from(&value), json);
+    }
 
-impl From<&serde_json::Value> for Value
-{
-    /// Converts [serde_json] deserialization results under a common value:
-    /// [Value].
-    /// 
-    /// [serde_yaml](https://docs.serde.rs/serde_json/index.html)
-    /// [Value](./struct.Value.html)
-    fn from(json: &serde_json::Value) -> Self
-    {
-        match json {
-            serde_json::Value::Null         => {
-                Self::Null
-            },
-            serde_json::Value::String(ref str)  => {
-                Self::String(str.to_string())
-            },
-            serde_json::Value::Bool(ref bool)   => {
-                Self::Bool(*bool)
-            },
-            serde_json::Value::Number(ref n)    => {
-                Self::Number(Number::from(n))
-            },
-            serde_json::Value::Array(json)   => {
-                let vec: Vec<Self> = json.iter().map(|each| {
-                    // Dangerous recusivity
-                    Self::from(each)
-                }).collect();
+    #[test]
+    fn into_yaml_value() {
+        let yaml = serde_yaml::from_str::<serde_yaml::Value>(
+            "house:\n  rooms: [kitchen, living room]\n  inhabitant_number: 2\n  temperature: -3\n  age: 37.5\n  job: true\n  cars: ~\n"
+        ).expect("failed to parse yaml");
 
-                Self::Array(vec)
-            },
-            serde_json::Value::Object(json)   => {
-                let map: BTreeMap<String, Self> = json.iter()
-                .map(|(key, each)| {
-                    // Dangerous recusivity
-                    (key.to_string(), Self::from(each))
-                }).collect();
+        let value = Value::from(&yaml);
+        assert_eq!(serde_yaml::Value::from(&value), yaml);
+    }
 
-                Self::Object(map)
-            },
+    #[test]
+    fn serialize_value() {
+        let json = json!({
+            "house": {
+                "rooms": ["kitchen", "living room"],
+                "inhabitant_number": 2,
+                "temperature": -3,
+                "surface": 120.5,
+                "cars": null,
+                "inhabitants": [
+                    { "name": "Doe", "job": true }
+                ],
+            }
+        });
+        let value = Value::from(&json);
+
+        let serialized = serde_json::to_value(&value).expect("failed to serialize value");
+        assert_eq!(serialized, json);
+        assert_eq!(Value::from(&serialized), value);
+
+        assert!(serialized["house"]["inhabitant_number"].is_u64());
+        assert!(serialized["house"]["temperature"].is_i64());
+        assert!(serialized["house"]["surface"].is_f64());
+
+        let yaml = serde_yaml::to_value(&value).expect("failed to serialize value");
+        assert_eq!(Value::from(&yaml), value);
+    }
+
+    #[test]
+    fn resolve_env() {
+        std::env::set_var("ROCKET_CONFIG_TEST_RESOLVE_ENV", "mysql://localhost");
+        std::env::remove_var("ROCKET_CONFIG_TEST_RESOLVE_ENV_UNSET");
+
+        let mut value = Value::from(&json!({
+            "url": "%env(resolve:ROCKET_CONFIG_TEST_RESOLVE_ENV)%",
+            "urls": ["%env(ROCKET_CONFIG_TEST_RESOLVE_ENV_UNSET:-sqlite://)%"],
+            "missing": "%env(ROCKET_CONFIG_TEST_RESOLVE_ENV_UNSET)%",
+            "port": 3306,
+        }));
+        value.resolve_env();
+
+        assert_eq!(value, Value::from(&json!({
+            "url": "mysql://localhost",
+            "urls": ["sqlite://"],
+            "missing": "%env(ROCKET_CONFIG_TEST_RESOLVE_ENV_UNSET)%",
+            "port": 3306,
+        })));
+
+        let mut value = Value::from(&json!({
+            "url": "%env(resolve:ROCKET_CONFIG_TEST_RESOLVE_ENV_UNSET)%",
+        }));
+        let err = value.try_resolve_env().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::MissingValue);
+
+        // Deeply nested values do not overflow the call stack
+        let mut value = Value::from("%env(ROCKET_CONFIG_TEST_RESOLVE_ENV)%");
+        for _ in 0..10_000 {
+            value = Value::from(vec![value]);
+        }
+        value.resolve_env();
+
+        while let Value::Array(mut list) = value {
+            value = Arc::make_mut(&mut list).pop().unwrap_or(Value::Null);
         }
+        assert_eq!(value, Value::from("mysql://localhost"));
     }
-}
 
-impl From<&serde_yaml::Value> for Value
-{
-    /// Converts [serde_yaml] deserialization results under a common value:
-    /// [Value].
-    /// 
-    /// [serde_yaml](https://docs.serde.rs/serde_yaml/index.html)
-    /// [Value](./struct.Value.html)
-    fn from(yaml: &serde_yaml::Value) -> Self
-    {
-        match yaml {
-            serde_yaml::Value::Null             => {
-                Self::Null
+    #[test]
+    fn resolve_placeholders() {
+        let mut value = Value::from(&json!({
+            "database": {
+                "url": "mysql://%host%:%port%/app",
+                "replicas": ["%host%", "100%%"],
             },
-            serde_yaml::Value::String(ref str)  => {
-                Self::String(str.to_string())
+            "port": 3306,
+        }));
+        value.resolve_placeholders(|reference| match reference {
+            "host" => Some("localhost".to_owned()),
+            "port" => Some("3306".to_owned()),
+            _ => None,
+        }).expect("failed to resolve placeholders");
+
+        assert_eq!(value, Value::from(&json!({
+            "database": {
+                "url": "mysql://localhost:3306/app",
+                "replicas": ["localhost", "100%"],
             },
-            serde_yaml::Value::Bool(ref bool)   => {
-                Self::Bool(*bool)
+            "port": 3306,
+        })));
+
+        let mut value = Value::from(&json!({"servers": [{"url": "%host%"}, {"url": "%missing%"}]}));
+        let err = value.resolve_placeholders(|reference| match reference {
+            "host" => Some("localhost".to_owned()),
+            _ => None,
+        }).expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::MissingValue);
+        assert_eq!(err.to_string(), "unresolved placeholder '%missing%' in 'servers.1.url'");
+        assert_eq!(value, Value::from(&json!({"servers": [{"url": "%host%"}, {"url": "%missing%"}]})));
+
+        let mut value = Value::from("%self%");
+        let err = value.resolve_placeholders(|_| Some("%self%".to_owned()))
+            .expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+    }
+
+    #[test]
+    fn pointer() {
+        let value = Value::from(&json!({
+            "foo": ["bar", "baz"],
+            "": 0,
+            "a/b": 1,
+            "c%d": 2,
+            "e^f": 3,
+            "g|h": 4,
+            "i\\j": 5,
+            "k\"l": 6,
+            " ": 7,
+            "m~n": 8,
+        }));
+
+        assert_eq!(value.pointer("").unwrap(), &value);
+        assert_eq!(value.pointer("/foo").unwrap(), &Value::from(&json!(["bar", "baz"])));
+        assert_eq!(value.pointer("/foo/0").unwrap(), &Value::from("bar"));
+        assert_eq!(value.pointer("/").unwrap(), &Value::from(0));
+        assert_eq!(value.pointer("/a~1b").unwrap(), &Value::from(1));
+        assert_eq!(value.pointer("/c%d").unwrap(), &Value::from(2));
+        assert_eq!(value.pointer("/e^f").unwrap(), &Value::from(3));
+        assert_eq!(value.pointer("/g|h").unwrap(), &Value::from(4));
+        assert_eq!(value.pointer("/i\\j").unwrap(), &Value::from(5));
+        assert_eq!(value.pointer("/k\"l").unwrap(), &Value::from(6));
+        assert_eq!(value.pointer("/ ").unwrap(), &Value::from(7));
+        assert_eq!(value.pointer("/m~0n").unwrap(), &Value::from(8));
+
+        // Invalid pointers
+        assert!(value.pointer("/unknown").is_none());
+        assert!(value.pointer("/e^f/ertz").is_none());
+        assert!(value.pointer("/foo/00").is_none());
+        assert!(value.pointer("/foo/01").is_none());
+        assert!(value.pointer("/foo/+1").is_none());
+        assert!(value.pointer("/foo/2").is_none());
+        assert!(value.pointer("foo").is_none());
+    }
+
+    #[test]
+    fn split_path() {
+        assert_eq!(super::split_path("diesel"), vec!["diesel"]);
+        assert_eq!(super::split_path("diesel.dbal.driver"), vec!["diesel", "dbal", "driver"]);
+        assert_eq!(super::split_path("env\\.local.url"), vec!["env.local", "url"]);
+        assert_eq!(super::split_path("back\\\\slash"), vec!["back\\slash"]);
+    }
+
+    #[test]
+    fn join_path() {
+        assert_eq!(super::join_path("", "diesel"), "diesel");
+        assert_eq!(super::join_path("diesel", "dbal"), "diesel.dbal");
+        assert_eq!(super::join_path("diesel", "env.local"), "diesel.env\\.local");
+        assert_eq!(super::join_path("", "back\\slash"), "back\\\\slash");
+        assert_eq!(
+            super::split_path(&super::join_path(&super::join_path("", "env.local"), "back\\slash")),
+            vec!["env.local", "back\\slash"]
+        );
+    }
+
+    #[test]
+    fn get_path() {
+        let value = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "driver": "mysql",
+                },
             },
-            serde_yaml::Value::Number(ref n)    => {
-                Self::Number(Number::from(n))
+            "servers": [
+                { "host": "localhost" },
+            ],
+            "env.local": {
+                "url": "sqlite://",
             },
-            serde_yaml::Value::Sequence(yaml)   => {
-                let vec: Vec<Self> = yaml.iter().map(|each| {
-                    // Dangerous recusivity
-                    Self::from(each)
-                }).collect();
+        }));
 
-                Self::Array(vec)
-            },
-            serde_yaml::Value::Mapping(yaml)    => {
-                let map: BTreeMap<String, Self> = yaml.iter()
-                .map(|(key, each)| {
-                    let key = {
-                        if !key.is_string() {
-                            unimplemented!();
-                        }
+        assert_eq!(value.get_path("diesel.dbal.driver"), Some(&Value::from("mysql")));
+        assert_eq!(value.get_path("servers.0.host"), Some(&Value::from("localhost")));
+        assert_eq!(value.get_path("env\\.local.url"), Some(&Value::from("sqlite://")));
 
-                        key.as_str().unwrap().to_owned()
-                    };
+        // Missing intermediate keys
+        assert_eq!(value.get_path("diesel.orm.driver"), None);
+        // Array indices out of bounds
+        assert_eq!(value.get_path("servers.1.host"), None);
+        assert_eq!(value.get_path("servers.-1.host"), None);
+        // Indexing into a scalar
+        assert_eq!(value.get_path("diesel.dbal.driver.name"), None);
+    }
 
-                    // Dangerous recusivity
-                    (key, Self::from(each))
-                }).collect();
+    #[test]
+    fn from_primitives() {
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(Value::from("test"), Value::String("test".to_owned()));
+        assert_eq!(Value::from("test".to_owned()), Value::String("test".to_owned()));
 
-                Self::Object(map)
+        // Signed integers follow the Number conversion rules
+        let negative = Value::from(-5i32);
+        assert!(negative.is_i64() && !negative.is_u64());
+        assert_eq!(negative.as_i64(), Some(-5));
+        let positive = Value::from(5i32);
+        assert!(positive.is_u64());
+        assert_eq!(positive.as_u64(), Some(5));
+        assert_eq!(Value::from(5usize), positive);
+
+        assert_eq!(Value::from(10.12).as_f64(), Some(10.12));
+        assert_eq!(Value::from(f64::NAN), Value::Null);
+        assert_eq!(Value::from(f64::INFINITY), Value::Null);
+        assert_eq!(Value::from(0.5f32).as_f64(), Some(0.5));
+        assert_eq!(Value::from(0.1f32).as_f64(), Some(f64::from(0.1f32)));
+        assert_eq!(Value::from(f32::NAN), Value::Null);
+        assert_eq!(Value::from(f32::NEG_INFINITY), Value::Null);
+    }
+
+    #[test]
+    fn from_collections() {
+        assert_eq!(
+            Value::from(vec!["kitchen", "toilet"]),
+            Value::Array(Arc::new(vec![
+                Value::String("kitchen".to_owned()),
+                Value::String("toilet".to_owned())
+            ]))
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("inhabitants".to_owned(), 2u8);
+        assert_eq!(
+            Value::from(map),
+            Value::from(&json!({ "inhabitants": 2 }))
+        );
+
+        assert_eq!(Value::from(Some("Doe")), Value::String("Doe".to_owned()));
+        assert_eq!(Value::from(None::<bool>), Value::Null);
+        assert_eq!(
+            Value::from(vec![Some(1), None]),
+            Value::from(&json!([1, null]))
+        );
+    }
+
+    #[test]
+    fn try_from() {
+        use std::convert::TryInto;
+
+        let value = value!({
+            "port": 8000,
+            "offset": -1,
+            "big": 70000,
+            "ratio": 0.5,
+            "debug": true,
+            "driver": "mysql",
+            "servers": ["first"],
+            "dbal": {"charset": "utf8"},
+        });
+
+        assert_eq!(u16::try_from(&value["port"]).unwrap(), 8000);
+        assert_eq!(i8::try_from(value["offset"].clone()).unwrap(), -1);
+        assert_eq!(u128::try_from(&value["big"]).unwrap(), 70000);
+        assert_eq!(f64::try_from(&value["ratio"]).unwrap(), 0.5);
+        assert_eq!(f64::try_from(&value["port"]).unwrap(), 8000.0);
+        assert!(bool::try_from(&value["debug"]).unwrap());
+        assert_eq!(String::try_from(value["driver"].clone()).unwrap(), "mysql");
+        assert_eq!(Vec::<Value>::try_from(&value["servers"]).unwrap(), vec![Value::from("first")]);
+        assert_eq!(
+            BTreeMap::<String, Value>::try_from(value["dbal"].clone()).unwrap(),
+            vec![("charset".to_owned(), Value::from("utf8"))].into_iter().collect()
+        );
+
+        let port: u16 = value["port"].clone().try_into().unwrap();
+        assert_eq!(port, 8000);
+
+        // Out of range
+        let err = u16::try_from(&value["big"]).expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(err.to_string(), "70000 is not a u16");
+        let err = u64::try_from(&value["offset"]).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "-1 is not a u64");
+        let err = i32::try_from(&value["ratio"]).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "0.5 is not an i32");
+
+        // Other variants
+        let err = u16::try_from(&value["driver"]).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected a u16, found string");
+        let err = String::try_from(value["port"].clone()).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected a string, found number");
+        let err = bool::try_from(&Value::Null).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected a boolean, found null");
+        let err = Vec::<Value>::try_from(value["dbal"].clone()).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected an array, found object");
+        let err = BTreeMap::<String, Value>::try_from(&value["servers"]).expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected an object, found array");
+    }
+
+    #[test]
+    fn parse_as() {
+        #[derive(Debug, PartialEq)]
+        enum Driver {
+            Mysql,
+            Postgres,
+        }
+
+        impl std::str::FromStr for Driver {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    "mysql" => Ok(Driver::Mysql),
+                    "postgres" => Ok(Driver::Postgres),
+                    _ => Err(format!("unknown driver '{}'", s)),
+                }
+            }
+        }
+
+        assert_eq!(value!("mysql").parse_as::<Driver>().unwrap(), Driver::Mysql);
+        assert_eq!(value!("postgres").parse_as::<Driver>().unwrap(), Driver::Postgres);
+        assert_eq!(value!("8000").parse_as::<u16>().unwrap(), 8000);
+
+        let err = value!("sqlite").parse_as::<Driver>().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert!(err.to_string().ends_with("unknown driver 'sqlite'"), "unexpected error message: {}", err);
+        let err = value!(3306).parse_as::<Driver>().expect_err("expected an Err, got a result");
+        assert!(err.to_string().ends_with("found number"), "unexpected error message: {}", err);
+    }
+
+    #[test]
+    fn from_iterator() {
+        let pool = vec![("min", 1), ("max", 8)].into_iter().collect::<Value>();
+        assert_eq!(pool, Value::from(&json!({ "min": 1, "max": 8 })));
+
+        let repeated = vec![("port".to_owned(), 80), ("port".to_owned(), 8000)].into_iter().collect::<Value>();
+        assert_eq!(repeated, Value::from(&json!({ "port": 8000 })));
+        assert_eq!(Vec::<(String, Value)>::new().into_iter().collect::<Value>(), Value::Object(Arc::default()));
+    }
+
+    #[test]
+    fn from_nested() {
+        let replicas = vec![
+            vec![("url", Value::from("mysql://first")), ("weight", Value::from(2))],
+            vec![("url", Value::from("mysql://second")), ("weight", Value::from(0.5f32))],
+        ];
+        let diesel = vec![
+            ("driver", Value::from("mysql")),
+            ("port", Value::from(3306u16)),
+            ("debug", Value::from(false)),
+            ("password", Value::from(None::<String>)),
+            ("hosts", Value::from(vec!["first", "second"])),
+            ("replicas", Value::from(replicas.into_iter().map(Value::from_iter).collect::<Vec<_>>())),
+        ].into_iter().collect::<Value>();
+
+        assert_eq!(Value::from_iter(vec![("diesel", diesel)]), Value::from(&json!({
+            "diesel": {
+                "driver": "mysql",
+                "port": 3306,
+                "debug": false,
+                "password": null,
+                "hosts": ["first", "second"],
+                "replicas": [
+                    {"url": "mysql://first", "weight": 2},
+                    {"url": "mysql://second", "weight": 0.5},
+                ],
+            }
+        })));
+    }
+
+    #[test]
+    fn from_non_finite_yaml() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str("[.nan, .inf, -.inf, 1.5]")
+            .expect("failed to parse yaml");
+
+        assert_eq!(Value::from(&yaml), value!([null, null, null, 1.5]));
+    }
+
+    #[test]
+    fn from_non_string_yaml_keys() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(
+            "42: answer\ntrue: yes\n1.5: float\n~: none\n[1, 2]: sequence\nkey: value"
+        ).expect("failed to parse yaml");
+
+        assert_eq!(Value::from(&yaml), value!({
+            "42": "answer",
+            "true": "yes",
+            "1.5": "float",
+            "null": "none",
+            "[1,2]": "sequence",
+            "key": "value"
+        }));
+    }
+
+    #[test]
+    fn entries() {
+        let mut value = value!({
+            "diesel": { "driver": "mysql", "port": 3306 },
+            "servers": ["a", "b"]
+        });
+
+        let keys = value.entries()
+            .expect("expected an object")
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["diesel", "servers"]);
+
+        let nested = value["diesel"].entries()
+            .expect("expected an object")
+            .map(|(key, each)| format!("{}={}", key, each))
+            .collect::<Vec<_>>();
+        assert_eq!(nested, vec!["driver=\"mysql\"", "port=3306"]);
+
+        for (_, each) in value["diesel"].entries_mut().expect("expected an object") {
+            *each = Value::Null;
+        }
+        assert_eq!(value["diesel"], value!({ "driver": null, "port": null }));
+
+        assert!(value["servers"].entries().is_none());
+        assert!(Value::Null.entries().is_none());
+    }
+
+    #[test]
+    fn slice() {
+        let value = value!({ "hosts": ["first", "second", "third"], "port": 3306 });
+        let hosts = &value["hosts"];
+
+        assert_eq!(hosts.slice(0..2), Some(&[Value::from("first"), Value::from("second")][..]));
+        assert_eq!(hosts.slice(1..), Some(&[Value::from("second"), Value::from("third")][..]));
+        assert_eq!(hosts.slice(..=0), Some(&[Value::from("first")][..]));
+        assert_eq!(hosts.slice(..).map(<[Value]>::len), Some(3));
+
+        // Empty and out of range
+        assert_eq!(hosts.slice(1..1), Some(&[][..]));
+        assert_eq!(hosts.slice((Bound::Included(2), Bound::Excluded(1))), Some(&[][..]));
+        assert_eq!(hosts.slice(1..10), Some(&[Value::from("second"), Value::from("third")][..]));
+        assert_eq!(hosts.slice(5..), Some(&[][..]));
+        assert_eq!(hosts.slice(..=usize::MAX).map(<[Value]>::len), Some(3));
+
+        assert_eq!(value.slice(..), None);
+        assert_eq!(value["port"].slice(0..1), None);
+    }
+
+    #[test]
+    fn len() {
+        let value = value!({ "hosts": ["first", "second", "third"], "pool": {}, "port": 3306 });
+
+        assert_eq!(value.len(), 3);
+        assert_eq!(value["hosts"].len(), 3);
+        assert_eq!(value["pool"].len(), 0);
+        assert_eq!(value!([]).len(), 0);
+        assert!(value["pool"].is_empty());
+        assert!(!value["hosts"].is_empty());
+
+        // Scalars
+        assert_eq!(value["port"].len(), 0);
+        assert_eq!(value!("mysql").len(), 0);
+        assert!(value!(null).is_empty());
+    }
+
+    #[test]
+    fn first_and_last() {
+        let value = value!({ "hosts": ["first", "second", "third"], "port": 3306 });
+
+        assert_eq!(value["hosts"].first(), Some(&Value::from("first")));
+        assert_eq!(value["hosts"].last(), Some(&Value::from("third")));
+        assert_eq!(value!(["only"]).first(), value!(["only"]).last());
+        assert_eq!(value!([]).first(), None);
+        assert_eq!(value!([]).last(), None);
+        assert_eq!(value.first(), None);
+        assert_eq!(value["port"].last(), None);
+    }
+
+    #[test]
+    fn contains() {
+        let value = value!({ "hosts": ["first", 2, null], "url": "mysql://localhost" });
+
+        // Arrays
+        assert!(value["hosts"].contains(&Value::from("first")));
+        assert!(value["hosts"].contains(&Value::from(2)));
+        assert!(value["hosts"].contains(&Value::Null));
+        assert!(!value["hosts"].contains(&Value::from("second")));
+
+        // Objects
+        assert!(value.contains(&Value::from("hosts")));
+        assert!(!value.contains(&Value::from("port")));
+        assert!(!value.contains(&Value::from(2)));
+
+        // Strings
+        assert!(value["url"].contains(&Value::from("localhost")));
+        assert!(value["url"].contains(&Value::from("")));
+        assert!(!value["url"].contains(&Value::from("pgsql")));
+        assert!(!value!("2").contains(&Value::from(2)));
+
+        // Other scalars
+        assert!(!value!(2).contains(&Value::from(2)));
+        assert!(!value!(null).contains(&Value::Null));
+    }
+
+    #[test]
+    fn array_contains() {
+        let value = value!({
+            "features": {
+                "enabled_drivers": ["mysql", "postgres"],
+                "ports": [3306, 5432],
             },
+            "driver": "mysql",
+        });
+        let drivers = value.get_path("features.enabled_drivers").expect("expected drivers");
+
+        assert!(drivers.array_contains_str("mysql"));
+        assert!(!drivers.array_contains_str("sqlite"));
+        assert!(drivers.array_contains(&Value::from("postgres")));
+        assert!(value.get_path("features.ports").is_some_and(|ports| ports.array_contains(&Value::from(5432))));
+        assert!(!value.get_path("features.ports").is_some_and(|ports| ports.array_contains_str("5432")));
+
+        // Non arrays, strings included
+        assert!(!value["driver"].array_contains_str("mysql"));
+        assert!(!value["driver"].array_contains(&Value::from("mysql")));
+        assert!(!value["features"].array_contains_str("enabled_drivers"));
+        assert!(!Value::Null.array_contains(&Value::Null));
+    }
+
+    #[test]
+    fn keys() {
+        let value = value!({
+            "diesel": {"dbal": {"charset": "utf8", "driver": "mysql"}},
+            "servers": [{"host": "first"}],
+        });
+
+        assert_eq!(value.keys(), vec!["diesel", "servers"]);
+        assert_eq!(value.get_path("diesel.dbal").map(Value::keys), Some(vec!["charset", "driver"]));
+        assert!(value.get_path("diesel.dbal").is_some_and(|dbal| dbal.has_key("driver")));
+        assert!(value.get_path("servers.0").is_some_and(|server| server.has_key("host")));
+        assert!(!value.has_key("dbal"));
+
+        // Non objects
+        assert!(value["servers"].keys().is_empty());
+        assert!(!value["servers"].has_key("0"));
+        assert!(value!("diesel").keys().is_empty());
+        assert!(!value!("diesel").has_key("diesel"));
+        assert!(!Value::Null.has_key(""));
+    }
+
+    #[test]
+    fn elements() {
+        let mut value = value!({ "servers": ["a", "b"], "port": 3306 });
+
+        let servers = value["servers"].elements()
+            .expect("expected an array")
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        assert_eq!(servers, vec!["a", "b"]);
+
+        for each in value["servers"].elements_mut().expect("expected an array") {
+            *each = Value::from(true);
         }
+        assert_eq!(value["servers"], value!([true, true]));
+
+        assert!(value.elements().is_none());
+        assert!(value["port"].elements_mut().is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn as_or() {
+        assert_eq!(Value::from("mysql").as_str_or("pgsql"), "mysql");
+        assert_eq!(Value::from(12).as_str_or("pgsql"), "pgsql");
+        assert_eq!(Value::from(-12).as_i64_or(0), -12);
+        assert_eq!(Value::from(-12).as_u64_or(30), 30);
+        assert_eq!(Value::from(0.5).as_f64_or(1.0), 0.5);
+        assert_eq!(Value::Null.as_f64_or(1.0), 1.0);
+        assert!(Value::from(true).as_bool_or(false));
+        assert!(Value::from("true").as_bool_or(true));
+    }
 
     #[test]
-    fn null_value() {
-        let value = Value::Null;
+    fn get_or() {
+        let value = value!({
+            "driver": "mysql",
+            "timeout": 10,
+            "offset": -2,
+            "ratio": 0.5,
+            "debug": true,
+            "password": null,
+            "servers": ["a"]
+        });
 
-        // Checks if the good value is attributed
-        assert_eq!(value, Value::Null);
+        // Stored values
+        assert_eq!(value.get_str_or("driver", "pgsql"), "mysql");
+        assert_eq!(value.get_u64_or("timeout", 30), 10);
+        assert_eq!(value.get_i64_or("offset", 0), -2);
+        assert_eq!(value.get_f64_or("ratio", 1.0), 0.5);
+        assert!(value.get_bool_or("debug", false));
+        assert_eq!(value["servers"].get_str_or(0, "b"), "a");
+
+        // Missing keys
+        assert_eq!(value.get_str_or("host", "localhost"), "localhost");
+        assert_eq!(value.get_u64_or("retries", 3), 3);
+        assert_eq!(value["servers"].get_str_or(1, "b"), "b");
+
+        // Null and type mismatches
+        assert_eq!(value.get_str_or("password", "secret"), "secret");
+        assert_eq!(value.get_u64_or("offset", 0), 0);
+        assert_eq!(value.get_i64_or("driver", 1), 1);
+        assert_eq!(value.get_f64_or("debug", 1.5), 1.5);
+        assert!(!value.get_bool_or("servers", false));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values = vec![
+            value!({"a": 1}),
+            value!([1, 2]),
+            value!("a"),
+            value!(1.5),
+            value!(true),
+            value!(null),
+            value!(1),
+            value!(false),
+            value!([1]),
+            value!(""),
+        ];
+        values.sort();
+
+        assert_eq!(values, vec![
+            value!(null),
+            value!(false),
+            value!(true),
+            value!(1),
+            value!(1.5),
+            value!(""),
+            value!("a"),
+            value!([1]),
+            value!([1, 2]),
+            value!({"a": 1}),
+        ]);
+    }
+
+    #[test]
+    fn nested_ordering() {
+        use std::collections::BTreeSet;
+
+        assert!(value!({"a": {"b": 1}}) < value!({"a": {"b": 2}}));
+        assert!(value!({"a": {"b": 2}}) < value!({"a": {"c": 1}}));
+        assert!(value!({"a": [1, {"b": null}]}) < value!({"a": [1, {"b": false}]}));
+        assert!(value!({"a": 1, "b": 2}) < value!({"b": 1}));
+        assert!(value!({"a": 1}) < value!({"a": 1, "b": 2}));
+        assert_eq!(
+            value!({"b": 1, "a": [2, 3]}).cmp(&value!({"a": [2, 3], "b": 1})),
+            std::cmp::Ordering::Equal
+        );
+
+        let set = vec![value!([2]), value!({}), value!([1, 3]), value!([2])]
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![value!([1, 3]), value!([2]), value!({})]);
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::HashSet;
 
-        // Checks if tester works fine
-        assert!(value.is_null());
+        let set = vec![
+            value!({"b": 1, "a": [2, {"d": null, "c": -0.0}]}),
+            value!({"a": [2, {"c": 0.0, "d": null}], "b": 1}),
+            value!({"a": [2, {"c": 0, "d": null}], "b": 1}),
+            value!({"a": [{"c": 0.0, "d": null}, 2], "b": 1}),
+            value!([null]),
+            value!([]),
+            value!(null),
+        ].into_iter().collect::<HashSet<_>>();
 
-        // Checks if converter works fine
-        assert!(value.as_null().is_some());
-        assert_eq!(value.as_null().unwrap(), ());
+        assert_eq!(set.len(), 6);
+        assert!(set.contains(&value!({"b": 1, "a": [2, {"c": 0.0, "d": null}]})));
+        assert!(!set.contains(&value!({"b": 1.0, "a": [2, {"c": 0.0, "d": null}]})));
     }
 
     #[test]
-    fn bool_value() {
-        let value = Value::Bool(true);
+    fn pointer_mut() {
+        let mut value = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": ["mysql://first", {"url": "mysql://second"}],
+                },
+                "port": 3306,
+            },
+            "a/b": 1,
+        }));
 
-        // Checks if the good value is attributed
-        assert_eq!(value, Value::Bool(true));
+        *value.pointer_mut("/diesel/dbal/url").unwrap() = Value::from("mysql://localhost");
+        *value.pointer_mut("/diesel/dbal/replicas/0").unwrap() = Value::from("mysql://primary");
+        *value.pointer_mut("/diesel/dbal/replicas/1/url").unwrap() = Value::Null;
+        *value.pointer_mut("/a~1b").unwrap() = Value::from(2);
 
-        // Checks if tester works fine
-        assert!(value.is_boolean());
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "url": "mysql://localhost",
+                    "replicas": ["mysql://primary", {"url": null}],
+                },
+                "port": 3306,
+            },
+            "a/b": 2,
+        })));
 
-        // Checks if converter works fine
-        assert!(value.as_bool().is_some());
-        assert_eq!(value.as_bool().unwrap(), true);
+        // Descending into scalars or missing values
+        assert!(value.pointer_mut("/diesel/port/0").is_none());
+        assert!(value.pointer_mut("/diesel/dbal/url/scheme").is_none());
+        assert!(value.pointer_mut("/diesel/dbal/replicas/2").is_none());
+        assert!(value.pointer_mut("/diesel/dbal/replicas/01").is_none());
+        assert!(value.pointer_mut("/diesel/orm").is_none());
+        assert!(value.pointer_mut("diesel").is_none());
+
+        value.pointer_mut("").unwrap().take();
+        assert!(value.is_null());
     }
 
     #[test]
-    fn string_value() {
-        let value = Value::String("test string".to_owned());
+    fn insert_at() {
+        let mut value = Value::Null;
 
-        // Checks if the good value is attributed
-        assert_eq!(value, Value::String("test string".to_owned()));
+        assert_eq!(value.insert_at("diesel.dbal.driver", Value::from("mysql")).unwrap(), None);
+        assert_eq!(value.insert_at("diesel.dbal.driver", Value::from("pgsql")).unwrap(), Some(Value::from("mysql")));
+        assert_eq!(value.insert_at("diesel.replicas.0", Value::from("first")).unwrap(), None);
+        assert_eq!(value.insert_at("diesel.replicas.1.url", Value::from("second")).unwrap(), None);
+        assert_eq!(value.insert_at("diesel.replicas.0", Value::from("primary")).unwrap(), Some(Value::from("first")));
+        assert_eq!(value.insert_at("env\\.local", Value::from(true)).unwrap(), None);
 
-        // Checks if tester works fine
-        assert!(value.is_string());
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {
+                "dbal": {"driver": "pgsql"},
+                "replicas": {"0": "primary", "1": {"url": "second"}},
+            },
+            "env.local": true,
+        })));
 
-        // Checks if converter works fine
-        assert!(value.as_str().is_some());
-        assert_eq!(value.as_str().unwrap(), "test string".to_owned());
+        let mut value = Value::from(&json!({"servers": ["first"], "port": 3306}));
+
+        assert_eq!(value.insert_at("servers.1", Value::from("second")).unwrap(), None);
+        assert_eq!(value.insert_at("servers.0", Value::from("primary")).unwrap(), Some(Value::from("first")));
+        assert_eq!(value.get_path("servers"), Some(&Value::from(vec!["primary", "second"])));
+
+        let err = value.insert_at("servers.3", Value::Null)
+            .expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert!(value.insert_at("servers.host", Value::Null).is_err());
+        assert!(value.insert_at("port.number", Value::Null).is_err());
+        assert!(value.insert_at("port.number.value", Value::Null).is_err());
+        assert_eq!(value.get_path("port"), Some(&Value::from(3306)));
     }
 
     #[test]
-    fn number_value() {
-        let value_unsigned = Value::Number(Number::from(10u8));
-        let value_signed = Value::Number(Number::from(10i8));
-        let value_float = Value::Number(Number::from_f64(10.12).expect("failed to create number from float"));
+    fn remove_at() {
+        let mut value = Value::from(&json!({
+            "diesel": {
+                "dbal": {"driver": "mysql"},
+                "servers": ["first", "second"],
+            },
+            "port": 3306,
+        }));
 
-        // Checks if the good value is attributed
-        assert_eq!(value_unsigned, Value::Number(Number::from(10u8)));
-        assert_eq!(value_signed, Value::Number(Number::from(10i8)));
+        assert_eq!(value.remove_at("diesel.servers.0"), Some(Value::from("first")));
+        assert_eq!(value.remove_at("diesel.servers.1"), None);
+        assert_eq!(value.remove_at("diesel.dbal.charset"), None);
+        assert_eq!(value.remove_at("port.number"), None);
+        assert_eq!(value.remove_at("diesel.orm.driver"), None);
+        assert_eq!(value.remove_at("diesel.dbal.driver"), Some(Value::from("mysql")));
 
-        // Checks if tester works fine
-        assert!(value_unsigned.is_number());
-        assert!(value_unsigned.is_u64());
-        assert!(value_signed.is_number());
-        assert!(value_unsigned.is_i64());
-        assert!(value_float.is_number());
-        assert!(value_float.is_f64());
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {
+                "dbal": {},
+                "servers": ["second"],
+            },
+            "port": 3306,
+        })));
+    }
 
-        // Checks if converters work fine
-        assert!(value_unsigned.as_u64().is_some());
-        assert_eq!(value_unsigned.as_u64().unwrap(), 10u64);
+    #[test]
+    fn as_vec_of() {
+        let value = Value::from(&json!({
+            "rooms": ["kitchen", "living room"],
+            "ports": [3306, 5432],
+            "offsets": [-1, 2],
+            "ratios": [0.5, 1],
+            "flags": [true, false],
+            "mixed": ["kitchen", 3306, -1, 0.5, true, null],
+            "empty": [],
+            "scalar": "kitchen",
+        }));
 
-        assert!(value_unsigned.as_i64().is_some());
-        assert_eq!(value_unsigned.as_i64().unwrap(), 10i64);
+        // Strict
+        assert_eq!(value["rooms"].as_vec_of_str(), Some(vec!["kitchen", "living room"]));
+        assert_eq!(value["rooms"].as_vec_of_string(), Some(vec!["kitchen".to_owned(), "living room".to_owned()]));
+        assert_eq!(value["ports"].as_vec_of_u64(), Some(vec![3306, 5432]));
+        assert_eq!(value["offsets"].as_vec_of_i64(), Some(vec![-1, 2]));
+        assert_eq!(value["ratios"].as_vec_of_f64(), Some(vec![0.5, 1.0]));
+        assert_eq!(value["flags"].as_vec_of_bool(), Some(vec![true, false]));
+        assert_eq!(value["offsets"].as_vec_of_u64(), None);
+        assert_eq!(value["mixed"].as_vec_of_str(), None);
+        assert_eq!(value["mixed"].as_vec_of_bool(), None);
+        assert_eq!(value["empty"].as_vec_of_str(), Some(vec![]));
+        assert_eq!(value["empty"].as_vec_of_f64(), Some(vec![]));
+        assert_eq!(value["scalar"].as_vec_of_str(), None);
 
-        assert!(value_float.as_f64().is_some());
-        assert_eq!(value_float.as_f64().unwrap(), 10.12);
+        // Lenient
+        assert_eq!(value["mixed"].as_vec_of_str_lenient(), Some(vec!["kitchen"]));
+        assert_eq!(value["mixed"].as_vec_of_string_lenient(), Some(vec!["kitchen".to_owned()]));
+        assert_eq!(value["mixed"].as_vec_of_u64_lenient(), Some(vec![3306]));
+        assert_eq!(value["mixed"].as_vec_of_i64_lenient(), Some(vec![3306, -1]));
+        assert_eq!(value["mixed"].as_vec_of_f64_lenient(), Some(vec![3306.0, -1.0, 0.5]));
+        assert_eq!(value["mixed"].as_vec_of_bool_lenient(), Some(vec![true]));
+        assert_eq!(value["empty"].as_vec_of_bool_lenient(), Some(vec![]));
+        assert_eq!(value["scalar"].as_vec_of_str_lenient(), None);
     }
 
     #[test]
-    fn array_value() {
-        let value = Value::Array(vec!(
-            Value::String("test".to_owned()),
-            Value::String("test 2".to_owned())
-        ));
-
-        // Checks if the good value is attributed
-        assert_eq!(value, Value::Array(vec!(
-            Value::String("test".to_owned()),
-            Value::String("test 2".to_owned())
-        )));
+    fn take_at() {
+        let mut value = Value::from(&json!({
+            "diesel": {"password": "secret", "driver": "mysql"},
+            "servers": ["first", "second", "third"],
+        }));
 
-        // Checks if tester works fine
-        assert!(value.is_array());
+        assert_eq!(value["diesel"].take_at("password"), Some(Value::from("secret")));
+        assert_eq!(value["diesel"].take_at("password".to_owned()), None);
+        assert_eq!(value["servers"].take_at(0), Some(Value::from("first")));
+        assert_eq!(value["servers"].take_at(2), None);
+        assert_eq!(value["servers"].take_at(&1), Some(Value::from("third")));
+        assert_eq!(value.take_at(0), None);
+        assert_eq!(value["servers"].take_at("first"), None);
+        assert_eq!(value["diesel"]["driver"].take_at("driver"), None);
+        assert_eq!(Value::Null.take_at(0), None);
 
-        // Checks if converter works fine
-        assert!(value.as_array().is_some());
-        assert_eq!(value.as_array().unwrap(), &vec!(
-            Value::String("test".to_owned()),
-            Value::String("test 2".to_owned())
-        ));
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {"driver": "mysql"},
+            "servers": ["second"],
+        })));
     }
 
     #[test]
-    fn object_value() {
-        let value = Value::Object({
-            let mut map = std::collections::BTreeMap::new();
+    fn as_duration() {
+        use std::time::Duration;
 
-            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
-            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
-            map
-        });
+        assert_eq!(value!("1500ms").as_duration(), Some(Duration::from_millis(1500)));
+        assert_eq!(value!("30s").as_duration(), Some(Duration::from_secs(30)));
+        assert_eq!(value!("10m").as_duration(), Some(Duration::from_secs(600)));
+        assert_eq!(value!("2h").as_duration(), Some(Duration::from_secs(7200)));
+        assert_eq!(value!("1d").as_duration(), Some(Duration::from_secs(86400)));
+        assert_eq!(value!(" 1.5 h ").as_duration(), Some(Duration::from_secs(5400)));
+        assert_eq!(value!("45").as_duration(), Some(Duration::from_secs(45)));
+        assert_eq!(value!(30).as_duration(), Some(Duration::from_secs(30)));
+        assert_eq!(value!(2.5).as_duration(), Some(Duration::from_millis(2500)));
+        assert_eq!(value!(0).as_duration(), Some(Duration::from_secs(0)));
 
-        // Checks if the good value is attributed
-        assert_eq!(value, Value::Object({
-            let mut map = std::collections::BTreeMap::new();
+        // Invalid
+        assert_eq!(value!("-5s").as_duration(), None);
+        assert_eq!(value!(-5).as_duration(), None);
+        assert_eq!(value!(-0.5).as_duration(), None);
+        assert_eq!(value!("5 minutes").as_duration(), None);
+        assert_eq!(value!("ms").as_duration(), None);
+        assert_eq!(value!("1.2.3s").as_duration(), None);
+        assert_eq!(value!("").as_duration(), None);
+        assert_eq!(value!("99999999999999999999d").as_duration(), None);
+        assert_eq!(value!(true).as_duration(), None);
+        assert_eq!(value!(null).as_duration(), None);
+    }
 
-            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
-            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
-            map
+    #[test]
+    fn get_ignore_case() {
+        let value = Value::from(&json!({
+            "database_url": "mysql://lower",
+            "Database_URL": "mysql://mixed",
+            "port": 3306,
         }));
 
-        // Checks if tester works fine
-        assert!(value.is_object());
+        #[cfg(not(feature = "preserve_order"))]
+        assert_eq!(value.get_ignore_case("DATABASE_URL"), Some(&Value::from("mysql://mixed")));
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(value.get_ignore_case("DATABASE_URL"), Some(&Value::from("mysql://lower")));
+        assert_eq!(value.get_ignore_case("PORT"), Some(&Value::from(3306)));
+        assert_eq!(value.get_ignore_case("Port"), Some(&Value::from(3306)));
+        assert_eq!(value.get_ignore_case("host"), None);
+        assert_eq!(Value::from("port").get_ignore_case("port"), None);
+    }
 
-        // Checks if converter works fine
-        assert!(value.as_object().is_some()); 
-        assert_eq!(value.as_object().unwrap(), &{
-            let mut map = std::collections::BTreeMap::new();
+    #[test]
+    fn lowercase_keys() {
+        let mut value = Value::from(&json!({
+            "Diesel": {"DATABASE_URL": "mysql://localhost", "Pool": 10},
+            "Servers": [{"Host": "localhost"}],
+        }));
+        value.lowercase_keys().expect("failed to lowercase keys");
 
-            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
-            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
-            map
-        });
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {"database_url": "mysql://localhost", "pool": 10},
+            "servers": [{"host": "localhost"}],
+        })));
 
-        // Checks if mut converter works fine
-        let mut cloned_value = value.clone();
-        assert!(cloned_value.as_object_mut().is_some()); 
-        assert_eq!(cloned_value.as_object_mut().unwrap(), &mut {
-            let mut map = std::collections::BTreeMap::new();
+        let colliding = Value::from(&json!({
+            "diesel": {"servers": [{"Host": "first", "HOST": "second"}]},
+            "Diesel": null,
+        }));
+        let mut value = colliding.clone();
+        let err = value.lowercase_keys().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(value, colliding);
 
-            map.insert("name".to_owned(), Value::String("Doe".to_owned()));
-            map.insert("firstname".to_owned(), Value::String("John".to_owned()));
-            map
-        });
+        let mut value = Value::from(&json!({"servers": [{"Host": "first", "HOST": "second"}]}));
+        let err = value.lowercase_keys().expect_err("expected an Err, got a result");
+        #[cfg(not(feature = "preserve_order"))]
+        assert_eq!(err.to_string(), "keys 'HOST' and 'Host' collide at 'servers.0'");
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(err.to_string(), "keys 'Host' and 'HOST' collide at 'servers.0'");
+    }
+
+    #[test]
+    fn get_first() {
+        let value = Value::from(&json!({
+            "database_url": null,
+            "db_url": "mysql://localhost",
+            "dsn": "sqlite://",
+        }));
 
+        assert_eq!(value.get_first(&["database_url", "db_url", "dsn"]), Some(&Value::from("mysql://localhost")));
+        assert_eq!(value.get_first(&["url", "dsn"]), Some(&Value::from("sqlite://")));
+        assert_eq!(value.get_first(&["database_url"]), None);
+        assert_eq!(value.get_first(&["url", "uri"]), None);
+        assert_eq!(value.get_first(&[]), None);
+        assert_eq!(Value::from("db_url").get_first(&["db_url"]), None);
+
+        assert_eq!(value.get_first_including_null(&["database_url", "db_url"]), Some(&Value::Null));
+        assert_eq!(value.get_first_including_null(&["url", "db_url"]), Some(&Value::from("mysql://localhost")));
+        assert_eq!(value.get_first_including_null(&["url", "uri"]), None);
+        assert_eq!(value.get_first_including_null(&[]), None);
     }
 
     #[test]
-    fn from_json_value() {
-        let json = json!({
-            "house": {
-                "rooms": [
-                    "kitchen",
-                    "living room",
-                    "toilet",
-                    "room 1",
-                    "room 2"
-                ],
-                "inhabitant_number": 2,
-                "inhabitants": [
-                    {
-                        "name": "Doe",
-                        "firstname": "John",
-                        "age": 37.5,
-                        "job": true,
-                    },
-                    {
-                        "name": "Doe",
-                        "firstname": "Jane",
-                        "age": 36.4,
-                        "job": true,
-                    }
-                ],
-                "cars": null,
-            }
-        });
+    fn eq_loose() {
+        let integer = Value::from(&json!({"version": 5, "ports": [80, -443], "name": "diesel"}));
+        let float = Value::from(&json!({"version": 5.0, "ports": [80.0, -443.0], "name": "diesel"}));
 
-        // If it does not panic, it worked
-        let json_value = Value::from(&json);
-        assert_eq!(
-            format!("{:?}", json_value),
-            "Object({\"house\": Object({\"cars\": Null, \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"age\": Number(37.5), \"firstname\": String(\"John\"), \"job\": Bool(true), \"name\": String(\"Doe\")}), Object({\"age\": Number(36.4), \"firstname\": String(\"Jane\"), \"job\": Bool(true), \"name\": String(\"Doe\")})]), \"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")])})})"
-        );
+        assert_ne!(integer, float);
+        assert!(integer.eq_loose(&float));
+        assert!(float.eq_loose(&integer));
+        assert!(Value::from(1u8).numeric_eq(&Value::from(1.0)));
+        assert!(Value::from(-0.0).numeric_eq(&Value::from(0)));
+
+        assert!(!Value::from("5").numeric_eq(&Value::from(5)));
+        assert!(!Value::Null.numeric_eq(&Value::Null));
+        assert!(Value::Null.eq_loose(&Value::Null));
+        assert!(!Value::from("5").eq_loose(&Value::from(5)));
+        assert!(!integer.eq_loose(&Value::from(&json!({"version": 5.5, "ports": [80, -443], "name": "diesel"}))));
+        assert!(!integer.eq_loose(&Value::from(&json!({"version": 5, "ports": [80], "name": "diesel"}))));
+        assert!(!integer.eq_loose(&Value::from(&json!({"version": 5, "ports": [80, -443], "label": "diesel"}))));
     }
 
     #[test]
-    fn from_yaml_value() {
-        let yaml = serde_yaml::Value::Mapping({
-            let mut mapping = serde_yaml::Mapping::new();
+    fn as_integer_lenient() {
+        let cases = vec![
+            (value!(42), Some(42), Some(42)),
+            (value!(-42), Some(-42), None),
+            (value!("42"), Some(42), Some(42)),
+            (value!("  42 "), Some(42), Some(42)),
+            (value!("+42"), Some(42), Some(42)),
+            (value!("-42"), Some(-42), None),
+            (value!("-0"), Some(0), Some(0)),
+            (value!("1_000"), Some(1_000), Some(1_000)),
+            (value!("0xff"), Some(255), Some(255)),
+            (value!("0XFF"), Some(255), Some(255)),
+            (value!("-0x80"), Some(-128), None),
+            (value!("0o17"), Some(15), Some(15)),
+            (value!("0b1010_1010"), Some(170), Some(170)),
+            (value!("-9223372036854775808"), Some(i64::MIN), None),
+            (value!("18446744073709551615"), None, Some(u64::MAX)),
 
-            mapping.insert(
-                serde_yaml::Value::String("house".to_owned()),
-                serde_yaml::Value::Mapping({
-                    let mut mapping = serde_yaml::Mapping::new();
+            // Overflows and parse failures
+            (value!("18446744073709551616"), None, None),
+            (value!("-9223372036854775809"), None, None),
+            (value!(1.5), None, None),
+            (value!("1.5"), None, None),
+            (value!("3.14e2"), None, None),
+            (value!("0x"), None, None),
+            (value!("0x-1"), None, None),
+            (value!("--1"), None, None),
+            (value!("_1"), None, None),
+            (value!("1__0"), None, None),
+            (value!("4 2"), None, None),
+            (value!("nan"), None, None),
+            (value!(""), None, None),
+            (value!(true), None, None),
+            (value!(null), None, None),
+        ];
 
-                    mapping.insert(
-                        serde_yaml::Value::String("cars".to_owned()),
-                        serde_yaml::Value::Null
-                    );
+        for (value, i64_expected, u64_expected) in cases {
+            assert_eq!(value.as_i64_lenient(), i64_expected, "{:?}", value);
+            assert_eq!(value.as_u64_lenient(), u64_expected, "{:?}", value);
+        }
 
-                    mapping.insert(
-                        serde_yaml::Value::String("rooms".to_owned()),
-                        serde_yaml::Value::Sequence({
-                            let mut sequence = serde_yaml::Sequence::new();
+        let err = value!("0xfg").try_as_i64_lenient().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(err.to_string(), "'0xfg' is not an i64: invalid digit found in string");
+        let err = value!("-1").try_as_u64_lenient().expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "'-1' is not a u64: number out of range");
+        let err = value!(-1).try_as_u64_lenient().expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "-1 is not a u64");
+        let err = value!([1]).try_as_i64_lenient().expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected an i64, found array");
+    }
 
-                            sequence.push(serde_yaml::Value::String("kitchen".to_owned()));
-                            sequence.push(serde_yaml::Value::String("living room".to_owned()));
-                            sequence.push(serde_yaml::Value::String("toilet".to_owned()));
-                            sequence.push(serde_yaml::Value::String("room 1".to_owned()));
-                            sequence.push(serde_yaml::Value::String("room 2".to_owned()));
+    #[test]
+    fn as_f64_lenient() {
+        let cases = vec![
+            (value!(1.5), Some(1.5)),
+            (value!(42), Some(42.0)),
+            (value!("3.14e2"), Some(314.0)),
+            (value!(" -0.5 "), Some(-0.5)),
+            (value!("1_000.5"), Some(1_000.5)),
+            (value!("0xff"), Some(255.0)),
+            (value!("-0b11"), Some(-3.0)),
+            (value!("18446744073709551616"), Some(18_446_744_073_709_551_616.0)),
 
-                            sequence
-                        })
-                    );
+            // Parse failures and non-finite numbers
+            (value!("nan"), None),
+            (value!("NaN"), None),
+            (value!("inf"), None),
+            (value!("-infinity"), None),
+            (value!("1e400"), None),
+            (value!("1.5.0"), None),
+            (value!("_1.5"), None),
+            (value!(""), None),
+            (value!(false), None),
+        ];
 
-                    mapping.insert(
-                        serde_yaml::Value::String("inhabitant_number".to_owned()),
-                        serde_yaml::Value::Number(serde_yaml::Number::from(2u64))
-                    );
+        for (value, expected) in cases {
+            assert_eq!(value.as_f64_lenient(), expected, "{:?}", value);
+        }
 
-                    mapping.insert(
-                        serde_yaml::Value::String("inhabitants".to_owned()),
-                        serde_yaml::Value::Sequence({
-                            let mut sequence = serde_yaml::Sequence::new();
+        let err = value!("nan").try_as_f64_lenient().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(err.to_string(), "'nan' is not an f64: not a finite number");
+    }
 
-                            sequence.push(serde_yaml::Value::Mapping({
-                                let mut mapping = serde_yaml::Mapping::new();
+    #[test]
+    fn as_bool_lenient() {
+        let cases = vec![
+            (value!(true), Some(true)),
+            (value!(false), Some(false)),
+            (value!(1), Some(true)),
+            (value!(0), Some(false)),
+            (value!("true"), Some(true)),
+            (value!("TRUE "), Some(true)),
+            (value!("Yes"), Some(true)),
+            (value!(" on"), Some(true)),
+            (value!("1"), Some(true)),
+            (value!("false"), Some(false)),
+            (value!("NO"), Some(false)),
+            (value!("Off\n"), Some(false)),
+            (value!("0"), Some(false)),
 
-                                mapping.insert(
-                                    serde_yaml::Value::String("firstname".to_owned()),
-                                    serde_yaml::Value::String("John".to_owned())
-                                );
-                                mapping.insert(
-                                    serde_yaml::Value::String("name".to_owned()),
-                                    serde_yaml::Value::String("Doe".to_owned())
-                                );
-                                mapping.insert(
-                                    serde_yaml::Value::String("age".to_owned()),
-                                    serde_yaml::Value::Number(
-                                        serde_yaml::Number::from(37.5)
-                                    )
-                                );
+            // Anything else
+            (value!(2), None),
+            (value!(-1), None),
+            (value!(1.0), None),
+            (value!("2"), None),
+            (value!("y"), None),
+            (value!("enabled"), None),
+            (value!("t rue"), None),
+            (value!(""), None),
+            (value!(null), None),
+            (value!([true]), None),
+            (value!({"enabled": true}), None),
+        ];
 
-                                mapping
-                            }));
+        for (value, expected) in cases {
+            assert_eq!(value.as_bool_lenient(), expected, "{:?}", value);
+        }
 
-                            sequence.push(serde_yaml::Value::Mapping({
-                                let mut mapping = serde_yaml::Mapping::new();
+        // The strict interpretation is left as it is
+        assert_eq!(value!("true").as_bool(), None);
+        assert_eq!(value!(1).as_bool(), None);
+    }
 
-                                mapping.insert(
-                                    serde_yaml::Value::String("firstname".to_owned()),
-                                    serde_yaml::Value::String("Jane".to_owned())
-                                );
-                                mapping.insert(
-                                    serde_yaml::Value::String("name".to_owned()),
-                                    serde_yaml::Value::String("Doe".to_owned())
-                                );
-                                mapping.insert(
-                                    serde_yaml::Value::String("age".to_owned()),
-                                    serde_yaml::Value::Number(
-                                        serde_yaml::Number::from(36.4)
-                                    )
-                                );
+    #[test]
+    fn as_addresses() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+        use std::path::PathBuf;
 
-                                mapping
-                            }));
+        assert_eq!(
+            value!("127.0.0.1:8000").as_socket_addr(),
+            Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8000))
+        );
+        assert_eq!(
+            value!("[::1]:8000").as_socket_addr(),
+            Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8000))
+        );
+        assert_eq!(value!("::1").as_ip_addr(), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+        assert_eq!(value!("127.0.0.1").as_ip_addr(), Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert_eq!(value!("../logs/app.log").as_path(), Some(PathBuf::from("../logs/app.log")));
 
-                            sequence
-                        })
-                    );
+        // Invalid
+        assert_eq!(value!("::1:8000").as_socket_addr(), None);
+        assert_eq!(value!("localhost:8000").as_socket_addr(), None);
+        assert_eq!(value!("127.0.0.1").as_socket_addr(), None);
+        assert_eq!(value!(8000).as_socket_addr(), None);
+        assert_eq!(value!("[::1]:8000").as_ip_addr(), None);
+        assert_eq!(value!(null).as_path(), None);
 
-                    mapping
-                })
-            );
+        let err = value!("[::1]").try_as_socket_addr().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        let err = value!(true).try_as_ip_addr().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+    }
 
-            mapping
-        });
+    #[test]
+    fn as_bytes() {
+        assert_eq!(value!("c2VjcmV0").as_bytes_base64(), Some(b"secret".to_vec()));
+        assert_eq!(value!("c2VjcmV0IQ").as_bytes_base64(), Some(b"secret!".to_vec()));
+        assert_eq!(value!("c2VjcmV0IQ==").as_bytes_base64(), Some(b"secret!".to_vec()));
+        assert_eq!(value!("DEADbeef").as_bytes_hex(), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(value!("").as_bytes_hex(), Some(vec![]));
 
-        // If it does not panic, it worked
-        let yaml_value = Value::from(&yaml);
+        // Invalid
+        assert_eq!(value!("c2VjcmV0*").as_bytes_base64(), None);
+        assert_eq!(value!("0xff").as_bytes_hex(), None);
+        assert_eq!(value!(42).as_bytes_hex(), None);
+
+        let err = value!("s3cr3t!").try_as_bytes_base64().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
+        assert_eq!(err.to_string(), "invalid base64 bytes: invalid character at offset 6");
+        let err = value!("abc").try_as_bytes_hex().expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "invalid hexadecimal bytes: odd number of digits");
+        let err = value!(null).try_as_bytes_hex().expect_err("expected an Err, got a result");
+        assert_eq!(err.to_string(), "expected hexadecimal bytes, found null");
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn as_url() {
         assert_eq!(
-            format!("{:?}", yaml_value),
-            "Object({\"house\": Object({\"cars\": Null, \"inhabitant_number\": Number(2), \"inhabitants\": Array([Object({\"age\": Number(37.5), \"firstname\": String(\"John\"), \"name\": String(\"Doe\")}), Object({\"age\": Number(36.4), \"firstname\": String(\"Jane\"), \"name\": String(\"Doe\")})]), \"rooms\": Array([String(\"kitchen\"), String(\"living room\"), String(\"toilet\"), String(\"room 1\"), String(\"room 2\")])})})"
+            value!("https://example.com:8443/path?q=1").as_url().map(String::from),
+            Some(String::from("https://example.com:8443/path?q=1"))
         );
+
+        // Invalid
+        assert_eq!(value!("example.com/path").as_url(), None);
+        assert_eq!(value!("http://[::1").as_url(), None);
+        assert_eq!(value!("https://exa mple.com").as_url(), None);
+        assert_eq!(value!(42).as_url(), None);
+
+        let err = value!("not a url").try_as_url().expect_err("expected an Err, got a result");
+        assert_eq!(err.kind(), error::ErrorKind::FormatError);
     }
-}
+
+    #[test]
+    fn pointer_mut_escaped() {
+        let mut value = Value::from(&json!({
+            "x": {"y": ["z", "zz"]},
+            "": 0,
+            "a/b": 1,
+            "m~n": 2,
+            "~1": 3,
+        }));
+
+        *value.pointer_mut("/x/y/1").unwrap() = Value::from("yy");
+        *value.pointer_mut("/").unwrap() = Value::from(10);
+        *value.pointer_mut("/a~1b").unwrap() = Value::from(11);
+        *value.pointer_mut("/m~0n").unwrap() = Value::from(12);
+        *value.pointer_mut("/~01").unwrap() = Value::from(13);
+
+        assert_eq!(value.pointer("/x/y/1").unwrap(), &Value::from("yy"));
+        assert_eq!(value, Value::from(&json!({
+            "x": {"y": ["z", "yy"]},
+            "": 10,
+            "a/b": 11,
+            "m~n": 12,
+            "~1": 13,
+        })));
+
+        // "~01" unescapes to "~1", never to "/"
+        assert!(value.pointer_mut("/a~01b").is_none());
+        assert!(value.pointer_mut("/x/y/-").is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,344 @@
+use std::sync::Arc;
+
+use super::Value;
+use super::value::join_path;
+
+/// Calls `f` with every node of `value` and its dotted path, depth-first, the
+/// nodes before their children.
+///
+/// The traversal keeps its own stack, so that deeply nested values do not
+/// overflow the call stack.
+pub(crate) fn walk<F>(value: &Value, mut f: F)
+where F: FnMut(&str, &Value)
+{
+    let mut stack = vec![(String::new(), value)];
+
+    while let Some((path, node)) = stack.pop() {
+        f(&path, node);
+
+        // Children are pushed in reverse so that they are popped in order
+        match node {
+            Value::Array(list) => {
+                for (index, each) in list.iter().enumerate().rev() {
+                    stack.push((join_path(&path, &index.to_string()), each));
+                }
+            },
+            Value::Object(map) => {
+                for (key, each) in map.iter().rev() {
+                    stack.push((join_path(&path, key), each));
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Same as [`walk`], with mutable nodes. The children of a node are walked
+/// once `f` has returned, so they are the ones `f` left.
+///
+/// [`walk`]: fn.walk.html
+pub(crate) fn walk_mut<F>(value: &mut Value, mut f: F)
+where F: FnMut(&str, &mut Value)
+{
+    let mut stack = vec![(String::new(), value)];
+
+    while let Some((path, node)) = stack.pop() {
+        f(&path, node);
+
+        match node {
+            Value::Array(list) => {
+                for (index, each) in Arc::make_mut(list).iter_mut().enumerate().rev() {
+                    stack.push((join_path(&path, &index.to_string()), each));
+                }
+            },
+            Value::Object(map) => {
+                for (key, each) in Arc::make_mut(map).iter_mut().rev() {
+                    stack.push((join_path(&path, key), each));
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Lists the nodes of `value` for which `predicate` returns true, with their
+/// dotted paths, depth-first, the nodes before their children and the keys
+/// of every object sorted.
+pub(crate) fn select<F>(value: &Value, predicate: F) -> Vec<(String, &Value)>
+where F: Fn(&str, &Value) -> bool
+{
+    let mut selected = Vec::new();
+    let mut stack = vec![(String::new(), value)];
+
+    while let Some((path, node)) = stack.pop() {
+        match node {
+            Value::Array(list) => {
+                for (index, each) in list.iter().enumerate().rev() {
+                    stack.push((join_path(&path, &index.to_string()), each));
+                }
+            },
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+
+                for (key, each) in entries.into_iter().rev() {
+                    stack.push((join_path(&path, key), each));
+                }
+            },
+            _ => {},
+        }
+
+        if predicate(&path, node) {
+            selected.push((path, node));
+        }
+    }
+
+    selected
+}
+
+/// Same as [`select`], with mutable nodes. As a node cannot be borrowed
+/// along with its children, the children of a selected node are not
+/// searched.
+///
+/// [`select`]: fn.select.html
+pub(crate) fn select_mut<F>(value: &mut Value, predicate: F) -> Vec<(String, &mut Value)>
+where F: Fn(&str, &Value) -> bool
+{
+    let mut selected = Vec::new();
+    let mut stack = vec![(String::new(), value)];
+
+    while let Some((path, node)) = stack.pop() {
+        if predicate(&path, node) {
+            selected.push((path, node));
+            continue;
+        }
+
+        match node {
+            Value::Array(list) => {
+                for (index, each) in Arc::make_mut(list).iter_mut().enumerate().rev() {
+                    stack.push((join_path(&path, &index.to_string()), each));
+                }
+            },
+            Value::Object(map) => {
+                let mut entries: Vec<_> = Arc::make_mut(map).iter_mut().collect();
+                entries.sort_by_key(|(key, _)| *key);
+
+                for (key, each) in entries.into_iter().rev() {
+                    stack.push((join_path(&path, key), each));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::value::{Map, Value};
+
+    const DEPTH: usize = 10_000;
+
+    /// Builds a document nesting `DEPTH` objects, alternating with arrays.
+    fn deep() -> Value {
+        let mut value = Value::from(" leaf ");
+
+        for level in 0..DEPTH {
+            value = if level % 2 == 0 {
+                let mut map = Map::new();
+                map.insert("a".to_owned(), value);
+                Value::from(map)
+            }
+            else {
+                Value::from(vec![value])
+            };
+        }
+        value
+    }
+
+    /// Takes `value` apart level by level, as dropping it at once would
+    /// recurse as deep as it is nested.
+    fn dismantle(mut value: Value) {
+        loop {
+            value = match value {
+                Value::Array(mut list) => match Arc::make_mut(&mut list).pop() {
+                    Some(each) => each,
+                    None => break,
+                },
+                Value::Object(mut map) => match Arc::make_mut(&mut map).remove("a") {
+                    Some(each) => each,
+                    None => break,
+                },
+                _ => break,
+            };
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let value = Value::from(&json!({
+            "diesel": {"driver": "mysql", "env.local": true},
+            "servers": ["first", {"host": "localhost"}],
+        }));
+        let mut visited = Vec::new();
+
+        value.walk(|path, node| visited.push((path.to_owned(), node.is_object() || node.is_array())));
+
+        assert_eq!(visited, vec![
+            ("".to_owned(), true),
+            ("diesel".to_owned(), true),
+            ("diesel.driver".to_owned(), false),
+            ("diesel.env\\.local".to_owned(), false),
+            ("servers".to_owned(), true),
+            ("servers.0".to_owned(), false),
+            ("servers.1".to_owned(), true),
+            ("servers.1.host".to_owned(), false),
+        ]);
+        for (path, _) in visited.iter().skip(1) {
+            assert!(value.get_path(path).is_some());
+        }
+    }
+
+    #[test]
+    fn walk_mut() {
+        let mut value = Value::from(&json!({
+            "diesel": {"port": "3306"},
+            "servers": ["first"],
+        }));
+
+        value.walk_mut(|path, node| match path {
+            "diesel.port" => *node = Value::from(3306),
+            "servers" => *node = Value::from(&json!([{"host": "localhost"}])),
+            "servers.0.host" => *node = Value::from("127.0.0.1"),
+            _ => {},
+        });
+
+        assert_eq!(value, Value::from(&json!({
+            "diesel": {"port": 3306},
+            "servers": [{"host": "127.0.0.1"}],
+        })));
+    }
+
+    #[test]
+    fn select() {
+        let value = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "driver": "mysql",
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": [
+                        {"driver": "mysql", "url": "%env(REPLICA_URL)%"},
+                        "mysql://second",
+                    ],
+                },
+                "port": 3306,
+                "password": "%env(DATABASE_PASSWORD:-root)%",
+            },
+            "comment": "reads %env(NAME)% placeholders",
+        }));
+
+        let selected = value.select(|_, node| node.as_str().is_some_and(|s| s.contains("%env")));
+        assert_eq!(selected, vec![
+            ("comment".to_owned(), &Value::from("reads %env(NAME)% placeholders")),
+            ("diesel.dbal.replicas.0.url".to_owned(), &Value::from("%env(REPLICA_URL)%")),
+            ("diesel.dbal.url".to_owned(), &Value::from("%env(resolve:DATABASE_URL)%")),
+            ("diesel.password".to_owned(), &Value::from("%env(DATABASE_PASSWORD:-root)%")),
+        ]);
+
+        // Nodes are selected along with their children
+        let paths: Vec<_> = value.select(|_, node| node.get("driver").is_some() || node.is_array())
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, vec!["diesel.dbal", "diesel.dbal.replicas", "diesel.dbal.replicas.0"]);
+
+        let selected = value.select(|path, _| path.is_empty());
+        assert_eq!(selected, vec![(String::new(), &value)]);
+        assert!(value.select(|_, _| false).is_empty());
+    }
+
+    #[test]
+    fn select_mut() {
+        let mut value = Value::from(&json!({
+            "diesel": {
+                "dbal": {
+                    "driver": "mysql",
+                    "url": "%env(resolve:DATABASE_URL)%",
+                    "replicas": [{"driver": "mysql", "url": "%env(REPLICA_URL)%"}],
+                },
+                "password": "%env(DATABASE_PASSWORD:-root)%",
+            },
+        }));
+
+        for (_, node) in value.select_mut(|_, node| node.as_str().is_some_and(|s| s.contains("%env"))) {
+            *node = Value::Null;
+        }
+        assert!(value.select(|_, node| node.as_str().is_some_and(|s| s.contains("%env"))).is_empty());
+        assert_eq!(value.get_path("diesel.dbal.replicas.0.url"), Some(&Value::Null));
+
+        // The children of a selected node are not searched
+        let paths: Vec<_> = value.select_mut(|_, node| node.get("driver").is_some())
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, vec!["diesel.dbal"]);
+    }
+
+    #[test]
+    fn select_deeply_nested() {
+        let mut value = deep();
+
+        let selected = value.select(|_, node| node.is_string());
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0.split('.').count(), DEPTH);
+
+        assert_eq!(value.select_mut(|_, node| node.is_string()).len(), 1);
+
+        dismantle(value);
+    }
+
+    #[test]
+    fn map_strings() {
+        let mut value = Value::from(&json!({
+            " key ": " value ",
+            "servers": [" first ", 8000, null],
+        }));
+
+        value.map_strings(|s| s.trim().to_owned());
+
+        assert_eq!(value, Value::from(&json!({
+            " key ": "value",
+            "servers": ["first", 8000, null],
+        })));
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let mut value = deep();
+        let mut nodes = 0;
+        let mut leaf_path = String::new();
+
+        value.walk(|path, node| {
+            nodes += 1;
+            if node.is_string() {
+                leaf_path = path.to_owned();
+            }
+        });
+        assert_eq!(nodes, DEPTH + 1);
+        assert_eq!(leaf_path.split('.').count(), DEPTH);
+
+        value.map_strings(|s| s.trim().to_owned());
+
+        let mut leaves = Vec::new();
+        value.walk_mut(|_, node| {
+            if let Some(s) = node.as_str() {
+                leaves.push(s.to_owned());
+            }
+        });
+        assert_eq!(leaves, vec!["leaf"]);
+
+        dismantle(value);
+    }
+}
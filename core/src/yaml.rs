@@ -0,0 +1,248 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::Arc;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use crate::error;
+use crate::result;
+use crate::value::{Map, Number, Value};
+
+/// Parses YAML content into a `Value`, as `Value::from` converts a
+/// `serde_yaml::Value`, but strictly.
+///
+/// Non-finite floats, such as `.nan` and `.inf`, have no `Value`, and keys
+/// which are not strings are stringified (`42` becomes `"42"`), so that they
+/// may collide with another key of their mapping, such as `"42"`. Both fail
+/// with an error of kind `FormatError` located at the offending scalar or
+/// key, instead of being converted to null or overwriting each other, as
+/// does any key found twice in a mapping. Syntax errors fail with an error
+/// of kind `Parse`.
+pub(crate) fn parse(content: &str) -> result::Result<Value>
+{
+    let invalid = Cell::new(false);
+
+    ValueSeed { invalid: &invalid, keys: None }
+        .deserialize(serde_yaml::Deserializer::from_str(content))
+        .map_err(|err| {
+            let kind = if invalid.get() {
+                error::ErrorKind::FormatError
+            }
+            else {
+                error::ErrorKind::Parse
+            };
+
+            match err.location() {
+                Some(location) => error::Error::new(kind, err)
+                    .with_location(location.line(), location.column()),
+                None => error::Error::new(kind, err),
+            }
+        })
+}
+
+/// Deserializes a `Value`, telling through `invalid` whether it failed on
+/// content YAML allows but a `Value` cannot hold.
+///
+/// When deserializing a mapping key, `keys` holds the other keys of its
+/// mapping, so that a duplicate fails while its scalar is visited, which
+/// locates the error at the key.
+#[derive(Clone, Copy)]
+struct ValueSeed<'a>
+{
+    invalid: &'a Cell<bool>,
+    keys: Option<&'a Map<String, Value>>,
+}
+
+impl<'a> ValueSeed<'a>
+{
+    fn invalid<E: de::Error>(&self, message: String) -> E
+    {
+        self.invalid.set(true);
+        E::custom(message)
+    }
+
+    /// Returns the visited `value`, unless it is a duplicate key.
+    fn visited<E: de::Error>(self, value: Value) -> Result<Value, E>
+    {
+        match self.keys {
+            Some(keys) if keys.contains_key(&key(&value)) => {
+                Err(self.invalid(format!("duplicate key '{}'", key(&value))))
+            },
+            _ => Ok(value),
+        }
+    }
+
+    /// Seeds the elements of a visited sequence or mapping, which are not
+    /// keys even when their container is.
+    fn nested(self) -> Self
+    {
+        Self { keys: None, ..self }
+    }
+}
+
+/// Stringifies a mapping key: YAML allows any value as key, non-string keys
+/// become their compact JSON representation (`42` becomes `"42"`).
+fn key(value: &Value) -> String
+{
+    match value {
+        Value::String(key) => key.clone(),
+        key => key.to_string(),
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ValueSeed<'a>
+{
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where D: de::Deserializer<'de>
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ValueSeed<'a>
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        formatter.write_str("any YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<Value, E>
+    {
+        self.visited(Value::Bool(b))
+    }
+
+    fn visit_i64<E: de::Error>(self, i: i64) -> Result<Value, E>
+    {
+        self.visited(Value::Number(Number::from(i)))
+    }
+
+    fn visit_u64<E: de::Error>(self, u: u64) -> Result<Value, E>
+    {
+        self.visited(Value::Number(Number::from(u)))
+    }
+
+    fn visit_f64<E: de::Error>(self, f: f64) -> Result<Value, E>
+    {
+        match Number::from_f64(f) {
+            Some(n) => self.visited(Value::Number(n)),
+            None => Err(self.invalid(format!("'{}' is not a valid number", f))),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Value, E>
+    {
+        self.visited(Value::String(s.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, s: String) -> Result<Value, E>
+    {
+        self.visited(Value::String(s))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E>
+    {
+        self.visited(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E>
+    {
+        self.visited(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where D: de::Deserializer<'de>
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where A: SeqAccess<'de>
+    {
+        let mut vec = Vec::new();
+
+        while let Some(each) = seq.next_element_seed(self.nested())? {
+            vec.push(each);
+        }
+
+        self.visited(Value::Array(Arc::new(vec)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where A: MapAccess<'de>
+    {
+        let mut values = Map::new();
+
+        loop {
+            let seed = ValueSeed { invalid: self.invalid, keys: Some(&values) };
+            let each_key = match map.next_key_seed(seed)? {
+                Some(each_key) => key(&each_key),
+                None => break,
+            };
+            let each = map.next_value_seed(self.nested())?;
+
+            values.insert(each_key, each);
+        }
+
+        self.visited(Value::Object(Arc::new(values)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error;
+    use super::parse;
+
+    #[test]
+    fn parse_values() {
+        let value = parse("
+diesel:
+  driver: mysql
+  port: 3306
+  ratio: -0.5
+  debug: false
+  password: ~
+  replicas: [first, {url: second}]
+42: answer
+true: yes
+1.5: float
+~: none
+[1, 2]: sequence
+").expect("failed to parse yaml");
+
+        assert_eq!(value, value!({
+            "diesel": {
+                "driver": "mysql",
+                "port": 3306,
+                "ratio": -0.5,
+                "debug": false,
+                "password": null,
+                "replicas": ["first", {"url": "second"}]
+            },
+            "42": "answer",
+            "true": "yes",
+            "1.5": "float",
+            "null": "none",
+            "[1,2]": "sequence"
+        }));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let examples = vec![
+            ("ratio: .inf", error::ErrorKind::FormatError, (1, 8)),
+            ("ratios:\n  - 1.5\n  - -.inf", error::ErrorKind::FormatError, (3, 5)),
+            ("ratio: .nan", error::ErrorKind::FormatError, (1, 8)),
+            ("42: answer\n\"42\": question", error::ErrorKind::FormatError, (2, 1)),
+            ("driver: mysql\ndriver: pgsql", error::ErrorKind::FormatError, (2, 1)),
+            ("driver: [mysql", error::ErrorKind::Parse, (2, 1)),
+        ];
+
+        for (content, kind, location) in examples {
+            let err = parse(content).expect_err("expected an Err, got a result");
+            assert_eq!(err.kind(), kind, "parsing {:?}", content);
+            assert_eq!(err.location(), Some(location), "parsing {:?}", content);
+        }
+    }
+}
@@ -185,6 +185,22 @@ fn hello(configuration: DieselConfiguration, name: String, age: u8) -> String {
     format!("Hello, {} year old named {}!", age, name)
 }
 
+#[get("/<key>/driver")]
+fn driver(configuration: DieselConfiguration, key: String) -> String {
+    match configuration.get(key) {
+        Ok(Some(value)) => value["dbal"]["driver"].as_str().unwrap_or("none").to_owned(),
+        _ => "missing".to_owned(),
+    }
+}
+
+#[get("/optional")]
+fn optional(configuration: Option<DieselConfiguration>) -> &'static str {
+    match configuration {
+        Some(_) => "configured",
+        None => "not configured",
+    }
+}
+
 #[test]
 fn rocket_test() {
     // Creates temporary environment
@@ -202,7 +218,8 @@ fn rocket_test() {
     {
         let rocket = rocket::ignite()
             .attach(ConfigurationsFairing::new())
-            .mount("/hello", routes![hello]);
+            .mount("/hello", routes![hello])
+            .mount("/", routes![driver]);
         let client = Client::new(rocket).expect("valid rocket instance");
 
         let req = client.get("/hello/John%20Doe/37");
@@ -211,6 +228,13 @@ fn rocket_test() {
 
         assert!(body.is_some());
         assert_eq!(body.unwrap(), "Hello, 37 year old named John Doe!");
+
+        // The guard looks keys up by owned strings
+        let mut response = client.get("/diesel/driver").dispatch();
+        assert_eq!(response.body_string(), Some("mysql".to_owned()));
+
+        let mut response = client.get("/doctrine/driver").dispatch();
+        assert_eq!(response.body_string(), Some("missing".to_owned()));
     }
 
     // Deletes temporary environment
@@ -221,4 +245,89 @@ fn rocket_test() {
 
     // Deletes temp dir
     delete_temporary_directory(temp_dir);
-}
\ No newline at end of file
+}
+
+#[test]
+fn optional_configuration_test() {
+    // Creates temporary environment, with empty configuration directories
+    let temp_dir = tempfile::tempdir().expect(
+        &format!("failed to create temp dir in {:?}", env::temp_dir())
+    );
+    let directory = temp_dir.path().join("config");
+
+    std::fs::create_dir_all(directory.join("dev"))
+        .expect("failed to create configuration directories");
+
+    // Real logic
+    {
+        let rocket = rocket::ignite()
+            .attach(ConfigurationsFairing::with_directory(&directory))
+            .mount("/", routes![optional]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/optional").dispatch();
+
+        assert_eq!(response.status(), rocket::http::Status::Ok);
+        assert_eq!(response.body_string(), Some("not configured".to_owned()));
+    }
+
+    // Deletes temp dir
+    delete_temporary_directory(temp_dir);
+}
+
+#[test]
+fn unmanaged_factory_test() {
+    // Without the fairing, the factory is not managed
+    let rocket = rocket::ignite()
+        .mount("/", routes![driver, optional]);
+    let client = Client::new(rocket).expect("valid rocket instance");
+
+    let response = client.get("/diesel/driver").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+
+    let mut response = client.get("/optional").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(response.body_string(), Some("not configured".to_owned()));
+}
+
+#[test]
+fn fail_on_error_test() {
+    // Creates temporary environment, with malformed files only
+    let temp_dir = tempfile::tempdir().expect(
+        &format!("failed to create temp dir in {:?}", env::temp_dir())
+    );
+    let directory = temp_dir.path().join("config");
+
+    std::fs::create_dir_all(directory.join("dev"))
+        .expect("failed to create configuration directories");
+    std::fs::write(directory.join("diesel.json"), "{\"dbal\": ")
+        .expect("failed to write diesel.json");
+    std::fs::write(directory.join("doctrine.yaml"), "dbal: [")
+        .expect("failed to write doctrine.yaml");
+
+    // Real logic
+    {
+        // Failures are ignored by default
+        let rocket = rocket::ignite()
+            .attach(ConfigurationsFairing::with_directory(&directory))
+            .mount("/", routes![optional]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/optional").dispatch();
+        assert_eq!(response.body_string(), Some("not configured".to_owned()));
+
+        for lenient in &[false, true] {
+            let fairing = ConfigurationsFairing::with_directory(&directory)
+                .lenient(*lenient)
+                .fail_on_error(true);
+            let rocket = rocket::ignite()
+                .attach(fairing)
+                .mount("/", routes![optional]);
+
+            assert!(Client::new(rocket).is_err());
+        }
+    }
+
+    // Deletes temp dir
+    delete_temporary_directory(temp_dir);
+}